@@ -1,12 +1,16 @@
 use bevy::{
-    asset::{AssetPath, LoadState},
+    animation::AnimationPlayer,
+    asset::LoadState,
     ecs::system::CommandQueue,
-    gltf::{Gltf, GltfMesh, GltfNode},
+    gltf::{Gltf, GltfExtras},
     prelude::*,
+    render::mesh::skinning::SkinnedMesh,
     utils::HashMap,
 };
 
-use space_prefab::component::{AssetMaterial, AssetMesh, MaterialPrefab};
+use space_prefab::component::{
+    AnimationPlayerPrefab, AssetMaterial, AssetMesh, CameraPlay, MaterialPrefab, SkinnedMeshPrefab,
+};
 use space_shared::PrefabMarker;
 
 use super::{BackgroundTask, BackgroundTaskStorage};
@@ -67,16 +71,31 @@ fn queue_push(
     mut events: EventWriter<GltfLoaded>,
     assets: Res<AssetServer>,
 ) {
-    if !queue.0.is_empty() && assets.get_load_state(&queue.0[0]) == Some(LoadState::Loaded) {
-        events.send(GltfLoaded(queue.0.remove(0)));
+    while !queue.0.is_empty() {
+        match assets.get_load_state(&queue.0[0]) {
+            Some(LoadState::Loaded) => {
+                events.send(GltfLoaded(queue.0.remove(0)));
+            }
+            Some(LoadState::Failed) => {
+                let handle = queue.0.remove(0);
+                error!("Failed to load gltf {:?} for unpacking", handle.path());
+            }
+            _ => break,
+        }
     }
 }
 
 struct UnpackContext<'a> {
-    material_map: &'a HashMap<Handle<StandardMaterial>, usize>,
-    mesh_map: &'a HashMap<Handle<GltfMesh>, usize>,
-    gltf_meshs: &'a Assets<GltfMesh>,
-    gltf_path: &'a AssetPath<'a>,
+    scene_world: &'a World,
+    /// Asset paths of animation clips with loaded clips
+    animations: &'a [(String, &'a AnimationClip)],
+}
+
+/// Mesh skins waiting for all joints to be spawned
+struct UnpackSkin {
+    entity: Entity,
+    inverse_bindposes: String,
+    joints: Vec<Entity>,
 }
 
 fn unpack_gltf(world: &mut World) {
@@ -104,144 +123,388 @@ fn unpack_gltf(world: &mut World) {
 
         let mut commands = Commands::new(&mut command_queue, world);
 
-        let gltf_nodes = world.resource::<Assets<GltfNode>>();
-        let gltf_meshs = world.resource::<Assets<GltfMesh>>();
         let scenes = world.resource::<Assets<Scene>>();
+        let clips = world.get_resource::<Assets<AnimationClip>>();
 
-        let mut mesh_map = HashMap::new();
-        for idx in 0..gltf.meshes.len() {
-            mesh_map.insert(gltf.meshes[idx].clone(), idx);
-        }
-
-        let mut material_map = HashMap::new();
-        for idx in 0..gltf.materials.len() {
-            info!("Material: {:?}", &gltf.materials[idx]);
-            material_map.insert(gltf.materials[idx].clone(), idx);
-        }
+        let animations = gltf
+            .animations
+            .iter()
+            .filter_map(|handle| Some((handle.path()?.to_string(), clips?.get(handle)?)))
+            .collect::<Vec<_>>();
 
         for idx in 0..gltf.scenes.len() {
             let Some(scene) = scenes.get(&gltf.scenes[idx]) else {
                 continue;
             };
 
-            //find roots nodes
-            let mut roots = vec![];
+            let ctx = UnpackContext {
+                scene_world: &scene.world,
+                animations: &animations,
+            };
+            let mut entity_map = HashMap::new();
+            let mut skins = vec![];
+
+            //gltf scene has single root entity, its children are gltf root nodes
             for e in scene.world.iter_entities() {
-                if !e.contains::<Parent>() && e.contains::<Children>() {
-                    let children = e.get::<Children>().unwrap();
-                    for child in children.iter() {
-                        if let Some(name) = scene.world.entity(*child).get::<Name>() {
-                            info!("Name: {:?}", &name);
-                            if let Some(node_handle) = gltf.named_nodes.get(name.as_str()) {
-                                if let Some(node) = gltf_nodes.get(node_handle) {
-                                    roots.push(node.clone());
-                                }
-                            }
+                if !e.contains::<Parent>() {
+                    if let Some(children) = e.get::<Children>() {
+                        for child in children.iter() {
+                            spawn_node(&mut commands, *child, &ctx, &mut entity_map, &mut skins);
                         }
                     }
                 }
             }
 
-            info!("Roots: {:?}", &roots);
-
-            let ctx = UnpackContext {
-                material_map: &material_map,
-                mesh_map: &mesh_map,
-                gltf_meshs,
-                gltf_path: &gltf_path,
-            };
-
-            for root in roots.iter() {
-                spawn_node(&mut commands, root, gltf, &ctx);
+            for skin in skins {
+                // joints must stay in the same order as inverse bindposes
+                let Some(joints) = skin
+                    .joints
+                    .iter()
+                    .map(|joint| entity_map.get(joint).copied())
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    warn!(
+                        "Skin of {:?} in {:?} has joints outside of unpacked scene, skin is skipped",
+                        skin.entity, gltf_path
+                    );
+                    continue;
+                };
+                commands.entity(skin.entity).insert(SkinnedMeshPrefab {
+                    inverse_bindposes: skin.inverse_bindposes,
+                    joints,
+                });
             }
         }
-
-        break;
     }
 
     command_queue.apply(world);
 }
 
+/// Copy gltf node from scene world as prefab entity with all children
 fn spawn_node(
     commands: &mut Commands,
-    node: &GltfNode,
-    _gltf: &Gltf,
+    scene_entity: Entity,
     ctx: &UnpackContext<'_>,
+    entity_map: &mut HashMap<Entity, Entity>,
+    skins: &mut Vec<UnpackSkin>,
 ) -> Entity {
+    let node = ctx.scene_world.entity(scene_entity);
     let id = commands
         .spawn((
             SpatialBundle {
-                transform: node.transform,
+                transform: node.get::<Transform>().copied().unwrap_or_default(),
                 ..default()
             },
             PrefabMarker,
         ))
         .id();
+    entity_map.insert(scene_entity, id);
 
-    if let Some(handle) = &node.mesh {
-        if let Some(mesh) = ctx.gltf_meshs.get(handle) {
-            if mesh.primitives.len() == 1 {
-                commands.entity(id).insert(AssetMesh {
-                    path: format!(
-                        "{}#Mesh{}/Primitive{}",
-                        ctx.gltf_path.path().display(),
-                        ctx.mesh_map.get(handle).unwrap(),
-                        0
-                    ),
-                });
+    if let Some(name) = node.get::<Name>() {
+        commands.entity(id).insert(name.clone());
+    }
 
-                if let Some(material_handle) = &mesh.primitives[0].material {
-                    if let Some(idx) = ctx.material_map.get(material_handle) {
-                        commands.entity(id).insert(AssetMaterial {
-                            path: format!("{}#Material{}", ctx.gltf_path.path().display(), idx),
-                        });
-                    } else {
-                        commands.entity(id).insert(MaterialPrefab::default());
-                    }
-                } else {
-                    commands.entity(id).insert(MaterialPrefab::default());
-                }
-            } else {
-                commands.entity(id).with_children(|parent| {
-                    for idx in 0..mesh.primitives.len() {
-                        let mut id = parent.spawn((
-                            SpatialBundle::default(),
-                            AssetMesh {
-                                path: format!(
-                                    "{}#Mesh{}/Primitive{}",
-                                    ctx.gltf_path.path().display(),
-                                    ctx.mesh_map.get(handle).unwrap(),
-                                    idx
-                                ),
-                            },
-                            PrefabMarker,
-                        ));
-
-                        if let Some(material_handle) = &mesh.primitives[idx].material {
-                            if let Some(idx) = ctx.material_map.get(material_handle) {
-                                id.insert(AssetMaterial {
-                                    path: format!(
-                                        "{}#Material{}",
-                                        ctx.gltf_path.path().display(),
-                                        idx
-                                    ),
-                                });
-                            } else {
-                                id.insert(MaterialPrefab::default());
-                            }
-                        } else {
-                            id.insert(MaterialPrefab::default());
-                        }
-                    }
-                });
+    // components from extras will be inserted by prefab systems
+    if let Some(extras) = node.get::<GltfExtras>() {
+        commands.entity(id).insert(extras.clone());
+    }
+
+    // lights
+    if let Some(light) = node.get::<PointLight>() {
+        commands.entity(id).insert(*light);
+    }
+    if let Some(light) = node.get::<SpotLight>() {
+        commands.entity(id).insert(*light);
+    }
+    if let Some(light) = node.get::<DirectionalLight>() {
+        commands.entity(id).insert(light.clone());
+    }
+
+    // cameras are used only in game mode
+    if node.contains::<Camera3d>() {
+        commands.entity(id).insert((
+            Camera3d::default(),
+            node.get::<Projection>().cloned().unwrap_or_default(),
+            CameraPlay::default(),
+        ));
+    }
+
+    if node.contains::<AnimationPlayer>() {
+        // clip curves are addressed by names path starting from animation root
+        let clips = node.get::<Name>().map_or_else(Vec::new, |name| {
+            ctx.animations
+                .iter()
+                .filter(|(_, clip)| clip.compatible_with(name))
+                .map(|(path, _)| path.clone())
+                .collect()
+        });
+        commands.entity(id).insert(AnimationPlayerPrefab {
+            clips,
+            autoplay: None,
+        });
+    }
+
+    let children = node
+        .get::<Children>()
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    let (primitives, nodes): (Vec<Entity>, Vec<Entity>) = children
+        .into_iter()
+        .partition(|child| ctx.scene_world.entity(*child).contains::<Handle<Mesh>>());
+
+    // single primitive is stored in node itself, else each primitive is separate child
+    if let [primitive] = primitives.as_slice() {
+        insert_primitive(commands, id, *primitive, ctx, skins);
+    } else {
+        for primitive in primitives {
+            let primitive_id = commands
+                .spawn((SpatialBundle::default(), PrefabMarker))
+                .id();
+            if let Some(name) = ctx.scene_world.get::<Name>(primitive) {
+                commands.entity(primitive_id).insert(name.clone());
             }
+            insert_primitive(commands, primitive_id, primitive, ctx, skins);
+            commands.entity(id).add_child(primitive_id);
         }
     }
 
-    for child in &node.children {
-        let child_id = spawn_node(commands, child, _gltf, ctx);
+    for child in nodes {
+        let child_id = spawn_node(commands, child, ctx, entity_map, skins);
         commands.entity(id).add_child(child_id);
     }
 
     id
 }
+
+fn insert_primitive(
+    commands: &mut Commands,
+    id: Entity,
+    primitive: Entity,
+    ctx: &UnpackContext<'_>,
+    skins: &mut Vec<UnpackSkin>,
+) {
+    let primitive = ctx.scene_world.entity(primitive);
+
+    if let Some(extras) = primitive.get::<GltfExtras>() {
+        commands.entity(id).insert(extras.clone());
+    }
+
+    if let Some(path) = primitive.get::<Handle<Mesh>>().and_then(|mesh| mesh.path()) {
+        commands.entity(id).insert(AssetMesh {
+            path: path.to_string(),
+        });
+    }
+
+    if let Some(path) = primitive
+        .get::<Handle<StandardMaterial>>()
+        .and_then(|material| material.path())
+    {
+        commands.entity(id).insert(AssetMaterial {
+            path: path.to_string(),
+        });
+    } else {
+        commands.entity(id).insert(MaterialPrefab::default());
+    }
+
+    if let Some(skin) = primitive.get::<SkinnedMesh>() {
+        if let Some(path) = skin.inverse_bindposes.path() {
+            skins.push(UnpackSkin {
+                entity: id,
+                inverse_bindposes: path.to_string(),
+                joints: skin.joints.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use bevy::{
+        asset::{
+            io::{
+                memory::{Dir, MemoryAssetReader},
+                AssetSource, AssetSourceId, Reader,
+            },
+            AssetLoader, AsyncReadExt, LoadContext,
+        },
+        render::mesh::skinning::SkinnedMeshInverseBindposes,
+        utils::BoxedFuture,
+    };
+
+    use super::*;
+
+    /// Loader of test gltf files. File text is name of scene layout instead of gltf data
+    #[derive(Default)]
+    struct TestGltfLoader;
+
+    impl AssetLoader for TestGltfLoader {
+        type Asset = Gltf;
+        type Settings = ();
+        type Error = std::io::Error;
+
+        fn load<'a>(
+            &'a self,
+            reader: &'a mut Reader,
+            _settings: &'a (),
+            load_context: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<Gltf, Self::Error>> {
+            Box::pin(async move {
+                let mut layout = String::new();
+                reader.read_to_string(&mut layout).await?;
+                let scene = test_scene(&layout, load_context);
+                let scene = load_context.add_labeled_asset("Scene0".to_string(), scene);
+                Ok(Gltf {
+                    scenes: vec![scene],
+                    named_scenes: default(),
+                    meshes: vec![],
+                    named_meshes: default(),
+                    materials: vec![],
+                    named_materials: default(),
+                    nodes: vec![],
+                    named_nodes: default(),
+                    default_scene: None,
+                    animations: vec![],
+                    named_animations: default(),
+                })
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["gltf"]
+        }
+    }
+
+    /// Scene with root entity and single "Node" gltf node, like scenes of gltf loader
+    fn test_scene(layout: &str, load_context: &mut LoadContext) -> Scene {
+        let mut world = World::new();
+        let node = world.spawn(Name::new("Node")).id();
+        match layout {
+            "multi_primitive" => {
+                for idx in 0..2 {
+                    let primitive = world
+                        .spawn((
+                            Name::new(format!("Primitive{idx}")),
+                            Handle::<Mesh>::default(),
+                        ))
+                        .id();
+                    world.entity_mut(node).add_child(primitive);
+                }
+            }
+            "unmapped_skin" => {
+                // joint is not a descendant of scene root, so it is not unpacked
+                let joint = world.spawn(Name::new("Joint")).id();
+                let inverse_bindposes = load_context.add_labeled_asset(
+                    "Skin0/InverseBindMatrices".to_string(),
+                    SkinnedMeshInverseBindposes::from(vec![Mat4::IDENTITY; 2]),
+                );
+                let primitive = world
+                    .spawn((
+                        Handle::<Mesh>::default(),
+                        SkinnedMesh {
+                            inverse_bindposes,
+                            joints: vec![node, joint],
+                        },
+                    ))
+                    .id();
+                world.entity_mut(node).add_child(primitive);
+            }
+            _ => {}
+        }
+        world.spawn_empty().add_child(node);
+        Scene::new(world)
+    }
+
+    fn test_app(files: &[(&str, &str)]) -> App {
+        let dir = Dir::default();
+        for (path, layout) in files {
+            dir.insert_asset_text(Path::new(path), layout);
+        }
+
+        let mut app = App::new();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSource::build()
+                .with_reader(move || Box::new(MemoryAssetReader { root: dir.clone() })),
+        )
+        .add_plugins((MinimalPlugins, AssetPlugin::default(), UnpackGltfPlugin))
+        .init_asset::<Gltf>()
+        .init_asset::<Scene>()
+        .init_asset::<SkinnedMeshInverseBindposes>()
+        .init_asset_loader::<TestGltfLoader>()
+        .init_resource::<BackgroundTaskStorage>();
+        app
+    }
+
+    /// Send unpack events and update app until all gltfs leave the queue
+    fn unpack(app: &mut App, paths: &[&str]) {
+        for path in paths {
+            app.world.send_event(EditorUnpackGltf {
+                path: path.to_string(),
+            });
+        }
+        app.update();
+        for _ in 0..10000 {
+            if app.world.resource::<GltfSceneQueue>().0.is_empty() {
+                break;
+            }
+            app.update();
+        }
+        assert!(app.world.resource::<GltfSceneQueue>().0.is_empty());
+        // loaded gltf can be queued for unpack in the last update
+        app.update();
+    }
+
+    fn find_named(app: &mut App, name: &str) -> Option<Entity> {
+        app.world
+            .query_filtered::<(Entity, &Name), With<PrefabMarker>>()
+            .iter(&app.world)
+            .find(|(_, entity_name)| entity_name.as_str() == name)
+            .map(|(entity, _)| entity)
+    }
+
+    #[test]
+    fn failed_load_does_not_block_queue() {
+        let mut app = test_app(&[("node.gltf", "node")]);
+        unpack(&mut app, &["missing.gltf", "node.gltf"]);
+
+        assert!(find_named(&mut app, "Node").is_some());
+    }
+
+    #[test]
+    fn multi_primitive_mesh_spawns_child_per_primitive() {
+        let mut app = test_app(&[("multi.gltf", "multi_primitive")]);
+        unpack(&mut app, &["multi.gltf"]);
+
+        let node = find_named(&mut app, "Node").unwrap();
+        assert!(!app.world.entity(node).contains::<MaterialPrefab>());
+        let children = app.world.get::<Children>(node).unwrap().to_vec();
+        assert_eq!(children.len(), 2);
+        for (idx, child) in children.into_iter().enumerate() {
+            let child = app.world.entity(child);
+            assert!(child.contains::<PrefabMarker>());
+            assert!(child.contains::<MaterialPrefab>());
+            assert_eq!(
+                child.get::<Name>().map(Name::as_str),
+                Some(format!("Primitive{idx}").as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn skin_with_unmapped_joints_is_skipped() {
+        let mut app = test_app(&[("skin.gltf", "unmapped_skin")]);
+        unpack(&mut app, &["skin.gltf"]);
+
+        let node = find_named(&mut app, "Node").unwrap();
+        assert!(app.world.entity(node).contains::<MaterialPrefab>());
+        assert!(app
+            .world
+            .query::<&SkinnedMeshPrefab>()
+            .iter(&app.world)
+            .next()
+            .is_none());
+        assert!(find_named(&mut app, "Joint").is_none());
+    }
+}
@@ -94,6 +94,11 @@ fn editor_event_listener(
                 save_state.set(SaveState::Save);
                 info!("Saving scene to {:?}", path);
             }
+            EditorEvent::Cook(path) => {
+                save_config.path = Some(path.clone());
+                save_state.set(SaveState::Cook);
+                info!("Cooking scene to {:?}", path);
+            }
             EditorEvent::StartGame => {
                 start_game_state.set(EditorState::GamePrepare);
             }
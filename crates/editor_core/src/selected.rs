@@ -9,6 +9,10 @@ use space_shared::EditorSet;
 #[derive(Component, Default, Clone)]
 pub struct Selected;
 
+/// Last selected entity. Used by transform tools as pivot and orientation source
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActiveSelected(pub Option<Entity>);
+
 /// Selection system plugins
 pub struct SelectedPlugin;
 
@@ -17,9 +21,10 @@ impl Plugin for SelectedPlugin {
         if !app.is_plugin_added::<WireframePlugin>() {
             app.add_plugins(WireframePlugin);
         }
+        app.init_resource::<ActiveSelected>();
         app.add_systems(
             Update,
-            selected_entity_wireframe_update.in_set(EditorSet::Editor),
+            (selected_entity_wireframe_update, active_selected_update).in_set(EditorSet::Editor),
         );
     }
 }
@@ -37,3 +42,15 @@ fn selected_entity_wireframe_update(
         cmds.entity(e).insert(Wireframe);
     }
 }
+
+fn active_selected_update(
+    mut active: ResMut<ActiveSelected>,
+    added: Query<Entity, Added<Selected>>,
+    selected: Query<(), With<Selected>>,
+) {
+    if let Some(e) = added.iter().last() {
+        active.0 = Some(e);
+    } else if active.0.is_some_and(|e| !selected.contains(e)) {
+        active.0 = None;
+    }
+}
@@ -1,5 +1,15 @@
-use crate::*;
-use bevy::prelude::*;
+use crate::{tools::align::EntityBounds, viewports::ActiveEditorCamera, *};
+use bevy::{
+    input::mouse::{MouseMotion, MouseWheel},
+    prelude::*,
+    render::camera::ScalingMode,
+};
+use bevy_egui_next::egui;
+use space_prefab::component::{CameraBookmark, CameraBookmarks, SceneDataHolder};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
 
 pub struct EditorDefaultCameraPlugin;
 
@@ -9,8 +19,98 @@ impl Plugin for EditorDefaultCameraPlugin {
         app.add_systems(Update, update_pan_orbit.in_set(UiSystemSet::Last));
 
         app.add_systems(Update, ui_camera_block.in_set(UiSystemSet::AfterShow));
+        app.add_systems(
+            Update,
+            (camera_hotkeys, fly_camera)
+                .chain()
+                .after(ui_camera_block)
+                .in_set(UiSystemSet::AfterShow),
+        );
 
         app.configure_sets(Update, UiSystemSet::Last.before(PanOrbitCameraSystemSet));
+
+        app.init_resource::<EditorCameraView>();
+        app.init_resource::<FlyCameraSettings>()
+            .register_type::<FlyCameraSettings>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<FlyCameraSettings>();
+        }
+
+        app.editor_hotkey(CameraHotkey::FlyMode, vec![KeyCode::Grave]);
+        app.editor_hotkey(CameraHotkey::FlyForward, vec![KeyCode::W]);
+        app.editor_hotkey(CameraHotkey::FlyBack, vec![KeyCode::S]);
+        app.editor_hotkey(CameraHotkey::FlyLeft, vec![KeyCode::A]);
+        app.editor_hotkey(CameraHotkey::FlyRight, vec![KeyCode::D]);
+        app.editor_hotkey(CameraHotkey::FlyUp, vec![KeyCode::E]);
+        app.editor_hotkey(CameraHotkey::FlyDown, vec![KeyCode::Q]);
+        app.editor_hotkey(CameraHotkey::FlyFast, vec![KeyCode::ShiftLeft]);
+        app.editor_hotkey(CameraHotkey::TopView, vec![KeyCode::Numpad7]);
+        app.editor_hotkey(CameraHotkey::FrontView, vec![KeyCode::Numpad1]);
+        app.editor_hotkey(CameraHotkey::SideView, vec![KeyCode::Numpad3]);
+        app.editor_hotkey(CameraHotkey::TogglePerspective, vec![KeyCode::Numpad5]);
+        app.editor_hotkey(CameraHotkey::FrameSelected, vec![KeyCode::F]);
+        app.editor_hotkey(
+            CameraHotkey::Bookmark1,
+            vec![KeyCode::ControlLeft, KeyCode::Key1],
+        );
+        app.editor_hotkey(
+            CameraHotkey::Bookmark2,
+            vec![KeyCode::ControlLeft, KeyCode::Key2],
+        );
+        app.editor_hotkey(
+            CameraHotkey::Bookmark3,
+            vec![KeyCode::ControlLeft, KeyCode::Key3],
+        );
+        app.editor_hotkey(
+            CameraHotkey::Bookmark4,
+            vec![KeyCode::ControlLeft, KeyCode::Key4],
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum CameraHotkey {
+    FlyMode,
+    FlyForward,
+    FlyBack,
+    FlyLeft,
+    FlyRight,
+    FlyUp,
+    FlyDown,
+    FlyFast,
+    TopView,
+    FrontView,
+    SideView,
+    TogglePerspective,
+    FrameSelected,
+    Bookmark1,
+    Bookmark2,
+    Bookmark3,
+    Bookmark4,
+}
+
+impl Hotkey for CameraHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::FlyMode => "Toggle camera fly mode".to_string(),
+            Self::FlyForward => "Fly forward".to_string(),
+            Self::FlyBack => "Fly back".to_string(),
+            Self::FlyLeft => "Fly left".to_string(),
+            Self::FlyRight => "Fly right".to_string(),
+            Self::FlyUp => "Fly up".to_string(),
+            Self::FlyDown => "Fly down".to_string(),
+            Self::FlyFast => "Fly faster".to_string(),
+            Self::TopView => "Top orthographic view".to_string(),
+            Self::FrontView => "Front orthographic view".to_string(),
+            Self::SideView => "Side orthographic view".to_string(),
+            Self::TogglePerspective => "Toggle perspective".to_string(),
+            Self::FrameSelected => "Frame selected".to_string(),
+            Self::Bookmark1 => "Go to camera bookmark 1".to_string(),
+            Self::Bookmark2 => "Go to camera bookmark 2".to_string(),
+            Self::Bookmark3 => "Go to camera bookmark 3".to_string(),
+            Self::Bookmark4 => "Go to camera bookmark 4".to_string(),
+        }
     }
 }
 
@@ -43,7 +143,9 @@ pub fn change_camera_in_play(
 ) {
     if !play_cameras.is_empty() {
         let (mut some_camera, _) = play_cameras.iter_mut().next().unwrap();
-        cameras.single_mut().is_active = false;
+        for mut camera in cameras.iter_mut() {
+            camera.is_active = false;
+        }
         some_camera.is_active = true;
     }
 }
@@ -135,3 +237,508 @@ pub fn draw_camera_gizmo(
         );
     }
 }
+
+/// Runtime state of editor camera navigation
+#[derive(Resource, Default)]
+pub struct EditorCameraView {
+    /// Fly mode is active. Pan orbit input is blocked while flying
+    pub fly: bool,
+    /// Name for new bookmark in camera menu
+    pub bookmark_name: String,
+}
+
+/// Fly mode settings of editor camera
+#[derive(Resource, Reflect, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct FlyCameraSettings {
+    /// Units per second
+    pub speed: f32,
+    /// Speed multiplier while [`CameraHotkey::FlyFast`] is pressed
+    pub fast_multiplier: f32,
+    /// Radians per pixel of mouse motion
+    pub sensitivity: f32,
+}
+
+impl Default for FlyCameraSettings {
+    fn default() -> Self {
+        Self {
+            speed: 5.,
+            fast_multiplier: 4.,
+            sensitivity: 0.004,
+        }
+    }
+}
+
+/// Axis aligned orthographic view of editor camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxisView {
+    Top,
+    Front,
+    Side,
+}
+
+impl AxisView {
+    pub const ALL: [Self; 3] = [Self::Top, Self::Front, Self::Side];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Top => "Top",
+            Self::Front => "Front",
+            Self::Side => "Side",
+        }
+    }
+
+    /// Pan orbit alpha and beta of view
+    const fn angles(&self) -> (f32, f32) {
+        match self {
+            Self::Top => (0., FRAC_PI_2),
+            Self::Front => (0., 0.),
+            Self::Side => (FRAC_PI_2, 0.),
+        }
+    }
+}
+
+/// Field of view of default perspective projection. Used to convert between radius and orthographic scale
+const PERSPECTIVE_FOV: f32 = FRAC_PI_4;
+
+/// Pan orbit state of camera, which is applied directly to transform
+#[derive(Debug, Clone, Copy)]
+struct Orbit {
+    focus: Vec3,
+    alpha: f32,
+    beta: f32,
+    radius: f32,
+}
+
+impl Orbit {
+    fn of(pan_orbit: &PanOrbitCamera) -> Option<Self> {
+        Some(Self {
+            focus: pan_orbit.focus,
+            alpha: pan_orbit.alpha?,
+            beta: pan_orbit.beta?,
+            radius: pan_orbit.radius?,
+        })
+    }
+
+    fn rotation(&self) -> Quat {
+        Quat::from_rotation_y(self.alpha) * Quat::from_rotation_x(-self.beta)
+    }
+
+    fn position(&self) -> Vec3 {
+        self.focus + self.rotation() * Vec3::new(0., 0., self.radius)
+    }
+
+    /// Set current and target values of pan orbit camera, so it does not interpolate to old targets
+    fn apply(&self, pan_orbit: &mut PanOrbitCamera, transform: &mut Transform) {
+        pan_orbit.focus = self.focus;
+        pan_orbit.alpha = Some(self.alpha);
+        pan_orbit.beta = Some(self.beta);
+        pan_orbit.radius = Some(self.radius);
+        pan_orbit.target_focus = self.focus;
+        pan_orbit.target_alpha = self.alpha;
+        pan_orbit.target_beta = self.beta;
+        pan_orbit.target_radius = self.radius;
+        pan_orbit.force_update = true;
+
+        transform.rotation = self.rotation();
+        transform.translation = self.position();
+    }
+}
+
+fn camera_hotkeys(
+    mut commands: Commands,
+    input: Res<Input<CameraHotkey>>,
+    mut view: ResMut<EditorCameraView>,
+) {
+    if input.just_pressed(CameraHotkey::FlyMode) {
+        view.fly = !view.fly;
+    }
+
+    for (key, axis_view) in [
+        (CameraHotkey::TopView, AxisView::Top),
+        (CameraHotkey::FrontView, AxisView::Front),
+        (CameraHotkey::SideView, AxisView::Side),
+    ] {
+        if input.just_pressed(key) {
+            on_active_camera(&mut commands, move |world, camera| {
+                set_axis_view(world, camera, axis_view);
+            });
+        }
+    }
+    if input.just_pressed(CameraHotkey::TogglePerspective) {
+        on_active_camera(&mut commands, |world, camera| {
+            let orthographic = is_orthographic(world, camera);
+            set_orthographic(world, camera, !orthographic);
+        });
+    }
+    if input.just_pressed(CameraHotkey::FrameSelected) {
+        on_active_camera(&mut commands, frame_selected);
+    }
+
+    for (idx, key) in [
+        CameraHotkey::Bookmark1,
+        CameraHotkey::Bookmark2,
+        CameraHotkey::Bookmark3,
+        CameraHotkey::Bookmark4,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if input.just_pressed(key) {
+            on_active_camera(&mut commands, move |world, camera| {
+                if let Some(bookmark) = scene_bookmarks(world).bookmarks.get(idx).cloned() {
+                    go_to_bookmark(world, camera, &bookmark);
+                }
+            });
+        }
+    }
+}
+
+fn on_active_camera(
+    commands: &mut Commands,
+    action: impl FnOnce(&mut World, Entity) + Send + 'static,
+) {
+    commands.add(move |world: &mut World| {
+        if let Some(camera) = active_editor_camera(world) {
+            action(world, camera);
+        }
+    });
+}
+
+/// WASD fly mode. Hold right mouse button to look around, mouse wheel changes speed
+#[allow(clippy::too_many_arguments)]
+fn fly_camera(
+    view: Res<EditorCameraView>,
+    mut settings: ResMut<FlyCameraSettings>,
+    mut state: ResMut<EditorCameraEnabled>,
+    input: Res<Input<CameraHotkey>>,
+    mouse: Res<Input<MouseButton>>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    time: Res<Time>,
+    mut cameras: Query<(&mut PanOrbitCamera, &mut Transform), With<ActiveEditorCamera>>,
+) {
+    let pointer_in_view = state.0;
+    let delta = motion.read().map(|event| event.delta).sum::<Vec2>();
+    let scroll = wheel.read().map(|event| event.y).sum::<f32>();
+    if !view.fly {
+        return;
+    }
+    // pan orbit input is replaced by fly input
+    state.0 = false;
+    if !pointer_in_view {
+        return;
+    }
+
+    if scroll != 0. {
+        settings.speed = (settings.speed * 1.1f32.powf(scroll)).clamp(0.1, 500.);
+    }
+
+    for (mut pan_orbit, mut transform) in cameras.iter_mut() {
+        let Some(mut orbit) = Orbit::of(&pan_orbit) else {
+            continue;
+        };
+        let position = orbit.position();
+
+        if mouse.pressed(MouseButton::Right) {
+            orbit.alpha -= delta.x * settings.sensitivity;
+            orbit.beta = delta
+                .y
+                .mul_add(settings.sensitivity, orbit.beta)
+                .clamp(-FRAC_PI_2 + 0.01, FRAC_PI_2 - 0.01);
+        }
+        let rotation = orbit.rotation();
+
+        let mut direction = Vec3::ZERO;
+        for (key, dir) in [
+            (CameraHotkey::FlyForward, rotation * Vec3::NEG_Z),
+            (CameraHotkey::FlyBack, rotation * Vec3::Z),
+            (CameraHotkey::FlyLeft, rotation * Vec3::NEG_X),
+            (CameraHotkey::FlyRight, rotation * Vec3::X),
+            (CameraHotkey::FlyUp, Vec3::Y),
+            (CameraHotkey::FlyDown, Vec3::NEG_Y),
+        ] {
+            if input.pressed(key) {
+                direction += dir;
+            }
+        }
+        let mut speed = settings.speed;
+        if input.pressed(CameraHotkey::FlyFast) {
+            speed *= settings.fast_multiplier;
+        }
+        let position = position + direction.normalize_or_zero() * speed * time.delta_seconds();
+
+        // camera rotates around its own position, so focus is moved instead
+        orbit.focus = position - rotation * Vec3::new(0., 0., orbit.radius);
+        orbit.apply(&mut pan_orbit, &mut transform);
+    }
+}
+
+/// Pan orbit editor camera of view under pointer
+pub fn active_editor_camera(world: &mut World) -> Option<Entity> {
+    world
+        .query_filtered::<Entity, (With<ActiveEditorCamera>, With<PanOrbitCamera>)>()
+        .iter(world)
+        .next()
+}
+
+pub fn is_orthographic(world: &World, camera: Entity) -> bool {
+    world
+        .get::<Projection>(camera)
+        .is_some_and(|projection| matches!(projection, Projection::Orthographic(_)))
+}
+
+fn orbit_of(world: &World, camera: Entity) -> Option<Orbit> {
+    Orbit::of(world.get::<PanOrbitCamera>(camera)?)
+}
+
+fn apply_orbit(world: &mut World, camera: Entity, orbit: Orbit) {
+    let mut entity = world.entity_mut(camera);
+    let transform = entity.get::<Transform>().copied();
+    let (Some(mut transform), Some(mut pan_orbit)) =
+        (transform, entity.get_mut::<PanOrbitCamera>())
+    else {
+        return;
+    };
+    orbit.apply(&mut pan_orbit, &mut transform);
+    entity.insert(transform);
+}
+
+/// Switch editor camera between perspective and orthographic projection with same visible size at focus
+pub fn set_orthographic(world: &mut World, camera: Entity, orthographic: bool) {
+    let Some(mut orbit) = orbit_of(world, camera) else {
+        return;
+    };
+    let height_per_radius = 2. * (PERSPECTIVE_FOV * 0.5).tan();
+    let projection = match world.get::<Projection>(camera) {
+        Some(Projection::Orthographic(ortho)) if !orthographic => {
+            orbit.radius = ortho.scale / height_per_radius;
+            Projection::Perspective(PerspectiveProjection {
+                fov: PERSPECTIVE_FOV,
+                ..default()
+            })
+        }
+        Some(Projection::Perspective(_)) if orthographic => {
+            Projection::Orthographic(orthographic_projection(orbit.radius * height_per_radius))
+        }
+        _ => return,
+    };
+    world.entity_mut(camera).insert(projection);
+    apply_orbit(world, camera, orbit);
+}
+
+fn orthographic_projection(scale: f32) -> OrthographicProjection {
+    OrthographicProjection {
+        scale,
+        scaling_mode: ScalingMode::FixedVertical(1.),
+        // camera is placed on orbit radius, so anything behind it must stay visible
+        near: -1000.,
+        far: 1000.,
+        ..default()
+    }
+}
+
+/// Look at focus along world axis with orthographic projection
+pub fn set_axis_view(world: &mut World, camera: Entity, view: AxisView) {
+    let (alpha, beta) = view.angles();
+    set_view_angles(world, camera, alpha, beta);
+    set_orthographic(world, camera, true);
+}
+
+/// Rotate editor camera around its focus to pan orbit `alpha` (yaw) and `beta` (pitch)
+pub fn set_view_angles(world: &mut World, camera: Entity, alpha: f32, beta: f32) {
+    let Some(mut orbit) = orbit_of(world, camera) else {
+        return;
+    };
+    (orbit.alpha, orbit.beta) = (alpha, beta);
+    apply_orbit(world, camera, orbit);
+}
+
+/// Move focus to bounds of selected entities and zoom to fit them
+pub fn frame_selected(world: &mut World, camera: Entity) {
+    let Some(mut orbit) = orbit_of(world, camera) else {
+        return;
+    };
+    let selected = world
+        .query_filtered::<Entity, With<Selected>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    let mut bounds: Option<EntityBounds> = None;
+    for entity in selected {
+        if let Some(entity_bounds) = EntityBounds::of_entity(world, entity) {
+            bounds = Some(bounds.map_or(entity_bounds, |b| b.union(&entity_bounds)));
+        }
+    }
+    let Some(bounds) = bounds else {
+        return;
+    };
+
+    let size = (bounds.size().length() * 0.5).max(0.5) * 1.2;
+    orbit.focus = bounds.center();
+    orbit.radius = size / (PERSPECTIVE_FOV * 0.5).sin();
+    if let Some(Projection::Orthographic(ortho)) = world.get::<Projection>(camera) {
+        let ortho = ortho.clone();
+        world
+            .entity_mut(camera)
+            .insert(Projection::Orthographic(OrthographicProjection {
+                scale: size * 2.,
+                ..ortho
+            }));
+    }
+    apply_orbit(world, camera, orbit);
+}
+
+/// Bookmark with current editor camera position
+pub fn current_bookmark(world: &World, camera: Entity, name: &str) -> Option<CameraBookmark> {
+    let orbit = orbit_of(world, camera)?;
+    let (orthographic, scale) = match world.get::<Projection>(camera) {
+        Some(Projection::Orthographic(ortho)) => (true, ortho.scale),
+        _ => (false, orbit.radius * 2. * (PERSPECTIVE_FOV * 0.5).tan()),
+    };
+    Some(CameraBookmark {
+        name: name.to_string(),
+        focus: orbit.focus,
+        alpha: orbit.alpha,
+        beta: orbit.beta,
+        radius: orbit.radius,
+        orthographic,
+        scale,
+    })
+}
+
+pub fn go_to_bookmark(world: &mut World, camera: Entity, bookmark: &CameraBookmark) {
+    let projection = if bookmark.orthographic {
+        Projection::Orthographic(orthographic_projection(bookmark.scale))
+    } else {
+        Projection::Perspective(PerspectiveProjection {
+            fov: PERSPECTIVE_FOV,
+            ..default()
+        })
+    };
+    world.entity_mut(camera).insert(projection);
+    apply_orbit(
+        world,
+        camera,
+        Orbit {
+            focus: bookmark.focus,
+            alpha: bookmark.alpha,
+            beta: bookmark.beta,
+            radius: bookmark.radius,
+        },
+    );
+}
+
+/// Bookmarks of current scene
+pub fn scene_bookmarks(world: &mut World) -> CameraBookmarks {
+    world
+        .query_filtered::<&CameraBookmarks, With<PrefabMarker>>()
+        .iter(world)
+        .next()
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Store bookmarks in scene. Bookmarks are kept on separate prefab entity, which is spawned on demand
+pub fn set_scene_bookmarks(world: &mut World, bookmarks: CameraBookmarks) {
+    let holder = world
+        .query_filtered::<Entity, (With<CameraBookmarks>, With<PrefabMarker>)>()
+        .iter(world)
+        .next();
+    match holder {
+        Some(entity) => {
+            world
+                .entity_mut(entity)
+                .insert((bookmarks, SceneDataHolder));
+        }
+        None => {
+            world.spawn((
+                bookmarks,
+                SceneDataHolder,
+                PrefabMarker,
+                Name::new("Camera Bookmarks"),
+            ));
+        }
+    }
+}
+
+/// Camera menu of view with navigation modes, views and bookmarks
+pub fn camera_menu(ui: &mut egui::Ui, world: &mut World, camera: Entity) {
+    let mut fly = world.resource::<EditorCameraView>().fly;
+    if ui
+        .checkbox(&mut fly, "Fly mode")
+        .on_hover_text("WASD to move, Q/E down/up, hold right mouse button to look around")
+        .changed()
+    {
+        world.resource_mut::<EditorCameraView>().fly = fly;
+    }
+    let mut settings = world.resource::<FlyCameraSettings>().clone();
+    ui.horizontal(|ui| {
+        ui.label("Fly speed");
+        ui.add(
+            egui::DragValue::new(&mut settings.speed)
+                .speed(0.1)
+                .clamp_range(0.1..=500.),
+        );
+    });
+    if *world.resource::<FlyCameraSettings>() != settings {
+        world.insert_resource(settings);
+    }
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        for view in AxisView::ALL {
+            if ui.button(view.name()).clicked() {
+                set_axis_view(world, camera, view);
+            }
+        }
+    });
+    let mut orthographic = is_orthographic(world, camera);
+    if ui.checkbox(&mut orthographic, "Orthographic").changed() {
+        set_orthographic(world, camera, orthographic);
+    }
+    if ui.button("Frame selected").clicked() {
+        frame_selected(world, camera);
+    }
+
+    ui.separator();
+    ui.label("Bookmarks");
+    let mut bookmarks = scene_bookmarks(world);
+    let mut changed = false;
+    let mut removed = None;
+    for (idx, bookmark) in bookmarks.bookmarks.iter().enumerate() {
+        ui.push_id(idx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button(bookmark.name.as_str()).clicked() {
+                    go_to_bookmark(world, camera, bookmark);
+                }
+                if ui.button("🗑").on_hover_text("Delete bookmark").clicked() {
+                    removed = Some(idx);
+                }
+            });
+        });
+    }
+    if let Some(idx) = removed {
+        bookmarks.bookmarks.remove(idx);
+        changed = true;
+    }
+
+    let mut name = std::mem::take(&mut world.resource_mut::<EditorCameraView>().bookmark_name);
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut name).hint_text("bookmark name"));
+        if ui.button("Add").clicked() {
+            if name.is_empty() {
+                name = format!("Bookmark {}", bookmarks.bookmarks.len() + 1);
+            }
+            if let Some(bookmark) = current_bookmark(world, camera, &name) {
+                bookmarks.bookmarks.push(bookmark);
+                changed = true;
+            }
+            name.clear();
+        }
+    });
+    world.resource_mut::<EditorCameraView>().bookmark_name = name;
+
+    if changed {
+        set_scene_bookmarks(world, bookmarks);
+    }
+}
@@ -18,15 +18,20 @@ pub trait EditorTab {
 
 #[derive(Clone, Hash, PartialEq, Eq, Debug, PartialOrd, Ord)]
 pub enum EditorTabName {
+    AssetReferences,
     CameraView,
     EventDispatcher,
     GameView,
     Hierarchy,
     Inspector,
+    Layers,
     Resource,
     RuntimeAssets,
+    SelectionQuery,
     Settings,
     ToolBox,
+    /// Additional view with own editor camera, see [`crate::viewports`]
+    Viewport(usize),
     Other(String),
 }
 
@@ -102,7 +107,7 @@ impl<'a, 'w, 's> egui_dock::TabViewer for EditorTabViewer<'a, 'w, 's> {
     }
 
     fn clear_background(&self, window: &Self::Tab) -> bool {
-        !matches!(window, EditorTabName::GameView)
+        !matches!(window, EditorTabName::GameView | EditorTabName::Viewport(_))
     }
 
     fn add_popup(
@@ -122,6 +127,8 @@ impl<'a, 'w, 's> egui_dock::TabViewer for EditorTabViewer<'a, 'w, 's> {
                 let format_name;
                 if let EditorTabName::Other(name) = registry.0 {
                     format_name = name.clone();
+                } else if let EditorTabName::Viewport(idx) = registry.0 {
+                    format_name = format!("Viewport {}", idx + 2);
                 } else {
                     format_name = format!("{:?}", registry.0)
                         .from_case(Case::Pascal)
@@ -5,7 +5,13 @@ use space_undo::UndoRedo;
 
 use space_shared::*;
 
-use crate::{colors::TEXT_COLOR, prelude::EditorTabName, EditorUiAppExt};
+use crate::{
+    camera_plugin::camera_menu,
+    colors::TEXT_COLOR,
+    prelude::EditorTabName,
+    viewports::{viewport_layout_menu, ActiveEditorCamera, EditorViewports, ViewportCamera},
+    EditorUiAppExt, PanOrbitCamera,
+};
 
 use super::{editor_tab::EditorTab, tool::EditorTool};
 
@@ -56,21 +62,53 @@ impl EditorTab for GameViewTab {
         }
 
         self.viewport_rect = Some(ui.clip_rect());
+        let camera = world
+            .query_filtered::<Entity, (
+                With<EditorCameraMarker>,
+                With<PanOrbitCamera>,
+                Without<ViewportCamera>,
+            )>()
+            .iter(world)
+            .next();
+        let active = world.resource::<EditorViewports>().active.is_none();
 
         ui.vertical(|ui| {
             ui.style_mut().visuals.override_text_color = Some(TEXT_COLOR);
 
-            //Tool processing
-            if self.tools.is_empty() {
-                return;
-            }
+            self.view_ui(ui, commands, world, camera, active, true);
+
+            ui.spacing();
+            //Draw FPS
+            let dt = world.get_resource::<Time>().unwrap().delta_seconds();
+            self.smoothed_dt = self.smoothed_dt.mul_add(0.98, dt * 0.02);
+            ui.colored_label(TEXT_COLOR, format!("FPS: {:.0}", 1.0 / self.smoothed_dt));
+        });
+    }
 
-            let selected_tool_name = if let Some(tool_id) = self.active_tool {
-                self.tools[tool_id].name()
-            } else {
-                "None"
-            };
+    fn title(&self) -> bevy_egui_next::egui::WidgetText {
+        "Game view".into()
+    }
+}
+
+impl GameViewTab {
+    /// Tool selector, camera menu and active tool of view.
+    /// Tool is processed only in active view (view under pointer), so picking and gizmos use its camera
+    pub fn view_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        commands: &mut Commands,
+        world: &mut World,
+        camera: Option<Entity>,
+        active: bool,
+        layout_menu: bool,
+    ) {
+        let selected_tool_name = if let Some(tool_id) = self.active_tool {
+            self.tools[tool_id].name()
+        } else {
+            "None"
+        };
 
+        ui.horizontal(|ui| {
             if self.tools.len() > 1 {
                 egui::ComboBox::new("tool", "")
                     .selected_text(selected_tool_name)
@@ -85,27 +123,26 @@ impl EditorTab for GameViewTab {
                         }
                     });
             }
-
-            if let Some(tool_id) = self.active_tool {
-                self.tools[tool_id].ui(ui, commands, world);
+            if let Some(camera) = camera {
+                ui.menu_button("📷 Camera", |ui| camera_menu(ui, world, camera));
+            }
+            if layout_menu {
+                ui.menu_button("⊞ Views", |ui| viewport_layout_menu(ui, world));
             }
-
-            ui.spacing();
-            //Draw FPS
-            let dt = world.get_resource::<Time>().unwrap().delta_seconds();
-            self.smoothed_dt = self.smoothed_dt.mul_add(0.98, dt * 0.02);
-            ui.colored_label(TEXT_COLOR, format!("FPS: {:.0}", 1.0 / self.smoothed_dt));
         });
-    }
 
-    fn title(&self) -> bevy_egui_next::egui::WidgetText {
-        "Game view".into()
+        if !active {
+            return;
+        }
+        if let Some(tool_id) = self.active_tool {
+            self.tools[tool_id].ui(ui, commands, world);
+        }
     }
 }
 
 pub fn reset_camera_viewport(
     primary_window: Query<&mut Window, With<PrimaryWindow>>,
-    mut cameras: Query<&mut Camera, With<EditorCameraMarker>>,
+    mut cameras: Query<&mut Camera, (With<EditorCameraMarker>, Without<ViewportCamera>)>,
     mut game_view_tab: ResMut<GameViewTab>,
 ) {
     let mut cam = cameras.single_mut();
@@ -123,6 +160,24 @@ pub fn reset_camera_viewport(
     });
 }
 
+/// Ray from active editor camera through pointer position in view. Must be called inside view ui (from [`EditorTool::ui`])
+pub fn pointer_ray(ui: &egui::Ui, world: &mut World) -> Option<Ray> {
+    let pointer = ui.input(|s| s.pointer.hover_pos())?;
+    let viewport_rect = ui.clip_rect();
+    if !viewport_rect.contains(pointer) {
+        return None;
+    }
+    let scale_factor = world
+        .resource::<bevy_egui_next::EguiSettings>()
+        .scale_factor as f32;
+    let position = (pointer - viewport_rect.left_top()) * scale_factor;
+
+    let mut cameras =
+        world.query_filtered::<(&Camera, &GlobalTransform), With<ActiveEditorCamera>>();
+    let (camera, camera_transform) = cameras.get_single(world).ok()?;
+    camera.viewport_to_world(camera_transform, Vec2::new(position.x, position.y))
+}
+
 pub fn has_window_changed(mut events: EventReader<bevy::window::WindowResized>) -> bool {
     events.read().next().is_some()
 }
@@ -135,7 +190,7 @@ pub fn set_camera_viewport(
     ui_state: Res<GameViewTab>,
     primary_window: Query<&mut Window, With<PrimaryWindow>>,
     egui_settings: Res<bevy_egui_next::EguiSettings>,
-    mut cameras: Query<&mut Camera, With<EditorCameraMarker>>,
+    mut cameras: Query<&mut Camera, (With<EditorCameraMarker>, Without<ViewportCamera>)>,
 ) {
     let Ok(mut cam) = cameras.get_single_mut() else {
         return;
@@ -1,15 +1,29 @@
 #![allow(clippy::too_many_arguments)]
 use std::sync::Arc;
 
-use bevy::{ecs::query::ReadOnlyWorldQuery, prelude::*, utils::HashMap};
+use bevy::{
+    ecs::query::ReadOnlyWorldQuery,
+    prelude::*,
+    render::view::VisibilitySystems,
+    utils::{HashMap, HashSet},
+};
 use bevy_egui_next::{egui::collapsing_header::CollapsingState, *};
 use space_editor_core::prelude::*;
-use space_prefab::editor_registry::EditorRegistry;
+use space_prefab::{
+    component::{EditorFlags, EditorHidden, EditorLocked, SceneDataHolder},
+    editor_registry::EditorRegistry,
+    query::EntityFilter,
+};
 use space_undo::{AddedEntity, NewChange, RemovedEntity, UndoSet};
 
 use space_shared::*;
 
-use super::{editor_tab::EditorTabName, EditorUiAppExt, EditorUiRef};
+use super::{
+    editor_tab::EditorTabName,
+    inspector::entity_picker::EntityPicker,
+    selection::{selection_modifiers, SelectionOp},
+    EditorUiAppExt, EditorUiRef,
+};
 
 /// Event to clone entity with clone all registered components
 #[derive(Event)]
@@ -38,6 +52,12 @@ impl Plugin for SpaceHierarchyPlugin {
                 .in_set(EditorSet::Editor)
                 .before(UndoSet::PerType),
         );
+        app.add_systems(
+            PostUpdate,
+            hide_editor_hidden
+                .after(VisibilitySystems::CheckVisibility)
+                .in_set(EditorSet::Editor),
+        );
         app.add_event::<CloneEvent>();
     }
 }
@@ -46,7 +66,14 @@ impl Plugin for SpaceHierarchyPlugin {
 pub struct HierarchyTabState {
     pub show_editor_entities: bool,
     pub show_spawnable_bundles: bool,
+    /// Name words and component conditions, see [`EntityFilter`]
     pub entity_filter: String,
+    /// Entity renamed in hierarchy and its edited name
+    pub rename: Option<(Entity, String)>,
+    /// Last clicked entity, start of `Shift` range selection
+    pub selection_anchor: Option<Entity>,
+    /// Entities dragged in hierarchy
+    pub dragged: Vec<Entity>,
 }
 
 pub type HierarchyQueryIter<'a> = (
@@ -56,34 +83,136 @@ pub type HierarchyQueryIter<'a> = (
     Option<&'a Parent>,
 );
 
+/// Where dragged entities are dropped relative to hovered row
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DropPosition {
+    Before,
+    After,
+    Child,
+}
+
+impl DropPosition {
+    /// Top and bottom quarters of row reorder, middle reparents
+    fn new(rect: egui::Rect, pointer: egui::Pos2) -> Self {
+        let edge = rect.height() / 4.;
+        if pointer.y < rect.top() + edge {
+            Self::Before
+        } else if pointer.y > rect.bottom() - edge {
+            Self::After
+        } else {
+            Self::Child
+        }
+    }
+}
+
+/// Interactions with hierarchy rows on current frame. They are applied after whole tree is drawn
+#[derive(Default)]
+struct TreeInteraction {
+    /// Rows in drawing order
+    rows: Vec<Entity>,
+    clicked: Option<Entity>,
+    drag_started: Option<Entity>,
+    drop: Option<(Entity, DropPosition)>,
+}
+
+/// Entities found by hierarchy filter on current frame
+struct FilterMatches {
+    matched: HashSet<Entity>,
+    /// Ancestors of matched entities, shown to keep tree structure
+    ancestors: HashSet<Entity>,
+    /// Open ancestors, set when filter text is changed
+    expand: bool,
+}
+
+impl FilterMatches {
+    fn is_shown(&self, entity: Entity) -> bool {
+        self.matched.contains(&entity) || self.ancestors.contains(&entity)
+    }
+}
+
 /// System to show hierarchy
 pub fn show_hierarchy(
     mut commands: Commands,
     query: Query<HierarchyQueryIter, With<PrefabMarker>>,
     all_entites: Query<HierarchyQueryIter>,
+    entity_refs: Query<EntityRef>,
+    data_holders: Query<(), With<SceneDataHolder>>,
+    editor_registry: Res<EditorRegistry>,
     mut selected: Query<Entity, With<Selected>>,
     mut clone_events: EventWriter<CloneEvent>,
     mut ui: NonSendMut<EditorUiRef>,
     mut changes: EventWriter<NewChange>,
     mut state: ResMut<HierarchyTabState>,
+    mut picker: ResMut<EntityPicker>,
+    keyboard: Res<Input<KeyCode>>,
+    flags: EditorFlags,
 ) {
     let mut all: Vec<_> = if state.show_editor_entities {
         all_entites.iter().collect()
     } else {
         query.iter().collect()
     };
+    // scene data is edited in its own tabs
+    all.retain(|(entity, ..)| !data_holders.contains(*entity));
     all.sort_by_key(|a| a.0);
     let ui = &mut ui.0;
-    ui.text_edit_singleline(&mut state.entity_filter);
+    let filter_changed = ui
+        .text_edit_singleline(&mut state.entity_filter)
+        .on_hover_text(
+            "Name words, `t:Component` or `c:Component`, `!Component` \
+            and `field=value` of previous component with `=`, `!=`, `<`, `>` or `~` (contains)",
+        )
+        .changed();
+
+    let registry = editor_registry.registry.read();
+    let matches = match EntityFilter::parse(&state.entity_filter, &registry) {
+        Ok(filter) if filter.is_empty() => None,
+        Ok(filter) => {
+            let matched = all
+                .iter()
+                .map(|(entity, ..)| *entity)
+                .filter(|entity| {
+                    entity_refs
+                        .get(*entity)
+                        .is_ok_and(|entity| filter.matches(entity, &registry))
+                })
+                .collect::<HashSet<_>>();
+            let mut ancestors = HashSet::new();
+            for entity in matched.iter() {
+                let mut entity = *entity;
+                while let Ok((.., Some(parent))) = all_entites.get(entity) {
+                    if !ancestors.insert(parent.get()) {
+                        break;
+                    }
+                    entity = parent.get();
+                }
+            }
+            Some(FilterMatches {
+                matched,
+                ancestors,
+                expand: filter_changed,
+            })
+        }
+        Err(err) => {
+            ui.colored_label(ui.visuals().error_fg_color, err);
+            None
+        }
+    };
+    drop(registry);
+
+    if let Some(matches) = &matches {
+        ui.horizontal(|ui| {
+            ui.label(format!("Found: {}", matches.matched.len()));
+            if ui.button("Select all results").clicked() {
+                SelectionOp::Replace.apply(&mut commands, selected.iter(), &matches.matched);
+            }
+        });
+    }
     ui.spacing();
-    let lower_filter = state.entity_filter.to_lowercase();
 
+    let mut interaction = TreeInteraction::default();
     egui::ScrollArea::vertical().show(ui, |ui| {
-        for (entity, _name, _children, parent) in all.iter().filter(|(_, name, _, _)| {
-            name.map(|n| n.to_lowercase())
-                .unwrap_or_else(|| "entity".to_string())
-                .contains(&lower_filter)
-        }) {
+        for (entity, _name, _children, parent) in all.iter() {
             if parent.is_none() {
                 if state.show_editor_entities {
                     draw_entity::<()>(
@@ -94,6 +223,11 @@ pub fn show_hierarchy(
                         &mut selected,
                         &mut clone_events,
                         &mut changes,
+                        &mut picker,
+                        &mut state,
+                        &mut interaction,
+                        &flags,
+                        matches.as_ref(),
                     );
                 } else {
                     draw_entity::<With<PrefabMarker>>(
@@ -104,11 +238,73 @@ pub fn show_hierarchy(
                         &mut selected,
                         &mut clone_events,
                         &mut changes,
+                        &mut picker,
+                        &mut state,
+                        &mut interaction,
+                        &flags,
+                        matches.as_ref(),
                     );
                 }
             }
         }
     });
+
+    if let Some(entity) = interaction.clicked {
+        let (shift, ctrl) = selection_modifiers(&keyboard);
+        // modifiers mean the same as in viewport, `Shift` also extends click to range from anchor
+        let range = state
+            .selection_anchor
+            .filter(|_| shift)
+            .and_then(|anchor| row_range(&interaction.rows, anchor, entity));
+        SelectionOp::from_modifiers(shift, ctrl).apply(
+            &mut commands,
+            selected.iter(),
+            &range.unwrap_or_else(|| HashSet::from([entity])),
+        );
+        if !shift || state.selection_anchor.is_none() {
+            state.selection_anchor = Some(entity);
+        }
+    }
+
+    if let Some(entity) = interaction.drag_started {
+        state.dragged = if selected.contains(entity) {
+            let mut dragged = selected.iter().collect::<Vec<_>>();
+            dragged.sort_by_key(|e| interaction.rows.iter().position(|row| row == e));
+            dragged
+        } else {
+            vec![entity]
+        };
+    }
+
+    if !state.dragged.is_empty() {
+        let text = match state.dragged.len() {
+            1 => "Move entity".to_string(),
+            count => format!("Move {} entities", count),
+        };
+        egui::show_tooltip_at_pointer(ui.ctx(), egui::Id::new("hierarchy_drag"), |ui| {
+            ui.label(text);
+        });
+
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            state.dragged.clear();
+        } else if ui.input(|i| i.pointer.any_released()) {
+            let dragged = std::mem::take(&mut state.dragged);
+            match interaction.drop {
+                Some((target, position)) => {
+                    move_entities(&mut commands, &all_entites, &dragged, target, position);
+                }
+                // drop on empty space of hierarchy makes entities root
+                None if ui.rect_contains_pointer(ui.max_rect()) => {
+                    for entity in top_entities(&all_entites, &dragged) {
+                        if matches!(all_entites.get(entity), Ok((.., Some(_)))) {
+                            commands.entity(entity).remove_parent();
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+    }
 }
 
 type DrawIter<'a> = (
@@ -126,94 +322,333 @@ fn draw_entity<F: ReadOnlyWorldQuery>(
     selected: &mut Query<Entity, With<Selected>>,
     clone_events: &mut EventWriter<CloneEvent>,
     changes: &mut EventWriter<NewChange>,
+    picker: &mut EntityPicker,
+    state: &mut HierarchyTabState,
+    interaction: &mut TreeInteraction,
+    flags: &EditorFlags,
+    matches: Option<&FilterMatches>,
 ) {
     let Ok((_, name, children, parent)) = query.get(entity) else {
         return;
     };
+    if matches.is_some_and(|matches| !matches.is_shown(entity)) {
+        return;
+    }
 
     let entity_name = name.map_or_else(
         || format!("Entity ({:?})", entity),
         |name| format!("{} ({:?})", name.as_str(), entity),
     );
 
+    // ancestors shown only to reach filter results are dimmed
+    let label = if matches.is_some_and(|matches| !matches.matched.contains(&entity)) {
+        egui::RichText::new(entity_name.as_str()).weak()
+    } else {
+        egui::RichText::new(entity_name.as_str())
+    };
     let is_selected = selected.contains(entity);
+    interaction.rows.push(entity);
 
-    if children.is_some_and(|children| children.iter().any(|child| query.get(*child).is_ok())) {
-        CollapsingState::load_with_default_open(
+    if children.is_some_and(|children| {
+        children.iter().any(|child| {
+            query.get(*child).is_ok() && matches.map_or(true, |matches| matches.is_shown(*child))
+        })
+    }) {
+        let mut collapsing = CollapsingState::load_with_default_open(
             ui.ctx(),
-            ui.make_persistent_id(entity_name.clone()),
+            ui.make_persistent_id(entity_name),
             true,
-        )
-        .show_header(ui, |ui| {
-            let responce = ui.selectable_label(is_selected, entity_name);
-            let is_clicked = responce.clicked();
-            responce.context_menu(|ui| {
-                hierarchy_entity_context(
+        );
+        if matches.is_some_and(|matches| matches.expand && matches.ancestors.contains(&entity)) {
+            collapsing.set_open(true);
+            collapsing.store(ui.ctx());
+        }
+        collapsing
+            .show_header(ui, |ui| {
+                entity_row(
                     ui,
                     commands,
                     entity,
-                    changes,
-                    clone_events,
-                    selected,
+                    name,
+                    label,
+                    is_selected,
                     parent,
+                    selected,
+                    clone_events,
+                    changes,
+                    picker,
+                    state,
+                    interaction,
+                    flags,
                 );
-            });
-
-            if is_clicked {
-                if is_selected {
-                    commands.entity(entity).remove::<Selected>();
-                    info!("Removed selected: {:?}", entity);
-                } else {
-                    commands.entity(entity).insert(Selected);
-
-                    //check shift pressed
-                    if !ui.input(|i| i.modifiers.shift) {
-                        selected.for_each(|e| {
-                            commands.entity(e).remove::<Selected>();
-                        })
-                    }
-                    info!("Added selected: {:?}", entity);
+            })
+            .body(|ui| {
+                for child in children.unwrap().iter() {
+                    draw_entity(
+                        commands,
+                        ui,
+                        query,
+                        *child,
+                        selected,
+                        clone_events,
+                        changes,
+                        picker,
+                        state,
+                        interaction,
+                        flags,
+                        matches,
+                    );
                 }
-            }
-        })
-        .body(|ui| {
-            for child in children.unwrap().iter() {
-                draw_entity(commands, ui, query, *child, selected, clone_events, changes);
-            }
-        });
+            });
     } else {
-        let selectable = ui.selectable_label(is_selected, format!("      {}", entity_name));
-        let is_clicked = selectable.clicked();
-
-        selectable.context_menu(|ui| {
-            hierarchy_entity_context(
+        ui.horizontal(|ui| {
+            // align with rows which have collapsing button
+            ui.add_space(ui.spacing().indent);
+            entity_row(
                 ui,
                 commands,
                 entity,
-                changes,
-                clone_events,
-                selected,
+                name,
+                label,
+                is_selected,
                 parent,
+                selected,
+                clone_events,
+                changes,
+                picker,
+                state,
+                interaction,
+                flags,
             );
         });
+    };
+}
 
-        if is_clicked {
-            if is_selected {
-                commands.entity(entity).remove::<Selected>();
-                info!("Removed selected: {:?}", entity);
-            } else {
-                commands.entity(entity).insert(Selected);
+/// Label of entity, which can be clicked, dragged, dropped on and renamed by double click
+fn entity_row(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    entity: Entity,
+    name: Option<&Name>,
+    label: egui::RichText,
+    is_selected: bool,
+    parent: Option<&Parent>,
+    selected: &mut Query<Entity, With<Selected>>,
+    clone_events: &mut EventWriter<CloneEvent>,
+    changes: &mut EventWriter<NewChange>,
+    picker: &mut EntityPicker,
+    state: &mut HierarchyTabState,
+    interaction: &mut TreeInteraction,
+    flags: &EditorFlags,
+) {
+    flag_toggle::<EditorHidden>(
+        ui,
+        commands,
+        entity,
+        flags.hidden.contains(entity),
+        flags.is_hidden(entity),
+        ("◌", "👁"),
+        "Hide in editor",
+    );
+    flag_toggle::<EditorLocked>(
+        ui,
+        commands,
+        entity,
+        flags.locked.contains(entity),
+        flags.is_locked(entity),
+        ("🔒", "🔓"),
+        "Lock",
+    );
+
+    if let Some((_, new_name)) = state
+        .rename
+        .as_mut()
+        .filter(|(renamed, _)| *renamed == entity)
+    {
+        let response = ui.text_edit_singleline(new_name);
+        if response.lost_focus() {
+            // Name change is recorded by auto undo
+            if !ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                commands.entity(entity).insert(Name::new(new_name.clone()));
+            }
+            state.rename = None;
+        } else if !response.has_focus() {
+            response.request_focus();
+        }
+        return;
+    }
+
+    let response = ui
+        .selectable_label(is_selected, label)
+        .interact(egui::Sense::drag());
+    if response.double_clicked() {
+        let name = name.map_or_else(String::new, |name| name.to_string());
+        state.rename = Some((entity, name));
+    }
+    // click sets inspector entity field while picking
+    if response.clicked() && !picker.pick(entity) {
+        interaction.clicked = Some(entity);
+    }
+    if response.drag_started() {
+        interaction.drag_started = Some(entity);
+    }
 
-                //check shift pressed
-                if !ui.input(|i| i.modifiers.shift) {
-                    selected.for_each(|e| {
-                        commands.entity(e).remove::<Selected>();
-                    })
+    if !state.dragged.is_empty() {
+        if let Some(pointer) = ui
+            .ctx()
+            .pointer_hover_pos()
+            .filter(|pointer| response.rect.contains(*pointer))
+        {
+            let position = DropPosition::new(response.rect, pointer);
+            if !state.dragged.contains(&entity) {
+                let rect = response.rect;
+                let stroke = egui::Stroke::new(2., ui.visuals().selection.stroke.color);
+                match position {
+                    DropPosition::Before => {
+                        ui.painter().hline(rect.x_range(), rect.top(), stroke);
+                    }
+                    DropPosition::After => {
+                        ui.painter().hline(rect.x_range(), rect.bottom(), stroke);
+                    }
+                    DropPosition::Child => {
+                        ui.painter().rect_stroke(rect, 2., stroke);
+                    }
                 }
-                info!("Added selected: {:?}", entity);
+            }
+            interaction.drop = Some((entity, position));
+        }
+    }
+
+    response.context_menu(|ui| {
+        if ui.button("Rename").clicked() {
+            let name = name.map_or_else(String::new, |name| name.to_string());
+            state.rename = Some((entity, name));
+            ui.close_menu();
+        }
+        hierarchy_entity_context(
+            ui,
+            commands,
+            entity,
+            changes,
+            clone_events,
+            selected,
+            parent,
+        );
+    });
+}
+
+/// Button to add or remove editor flag of entity. Flag inherited from ancestor is shown weak
+fn flag_toggle<T: Component + Default>(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    entity: Entity,
+    has: bool,
+    inherited: bool,
+    (on, off): (&str, &str),
+    hover: &str,
+) {
+    let mut text = egui::RichText::new(if inherited { on } else { off });
+    if inherited && !has {
+        text = text.weak();
+    }
+    // flag changes are recorded by auto undo
+    if ui
+        .add(egui::Button::new(text).frame(false))
+        .on_hover_text(hover)
+        .clicked()
+    {
+        if has {
+            commands.entity(entity).remove::<T>();
+        } else {
+            commands.entity(entity).insert(T::default());
+        }
+    }
+}
+
+/// Rows between `from` and `to`, including both of them
+fn row_range(rows: &[Entity], from: Entity, to: Entity) -> Option<HashSet<Entity>> {
+    let from = rows.iter().position(|row| *row == from)?;
+    let to = rows.iter().position(|row| *row == to)?;
+    Some(rows[from.min(to)..=from.max(to)].iter().copied().collect())
+}
+
+fn is_descendant(query: &Query<HierarchyQueryIter>, mut entity: Entity, ancestor: Entity) -> bool {
+    while let Ok((.., Some(parent))) = query.get(entity) {
+        if parent.get() == ancestor {
+            return true;
+        }
+        entity = parent.get();
+    }
+    false
+}
+
+/// Dragged entities without dragged ancestors. Their children are moved with them
+fn top_entities(query: &Query<HierarchyQueryIter>, dragged: &[Entity]) -> Vec<Entity> {
+    dragged
+        .iter()
+        .copied()
+        .filter(|entity| {
+            !dragged
+                .iter()
+                .any(|other| is_descendant(query, *entity, *other))
+        })
+        .collect()
+}
+
+/// Reparent or reorder dragged entities relative to target entity.
+/// Changes of [`Parent`] and [`Children`] are recorded by auto undo as one undo step
+fn move_entities(
+    commands: &mut Commands,
+    query: &Query<HierarchyQueryIter>,
+    dragged: &[Entity],
+    target: Entity,
+    position: DropPosition,
+) {
+    // entity can not be moved into itself
+    if dragged
+        .iter()
+        .any(|entity| *entity == target || is_descendant(query, target, *entity))
+    {
+        return;
+    }
+    let moved = top_entities(query, dragged);
+    if position == DropPosition::Child {
+        commands.entity(target).push_children(&moved);
+        return;
+    }
+
+    let Ok((.., Some(parent))) = query.get(target) else {
+        // root entities have no order, so they are only detached
+        for entity in moved {
+            if matches!(query.get(entity), Ok((.., Some(_)))) {
+                commands.entity(entity).remove_parent();
             }
         }
+        return;
     };
+    let siblings = query
+        .get(parent.get())
+        .ok()
+        .and_then(|(_, _, children, _)| children)
+        .map(|children| {
+            children
+                .iter()
+                .filter(|child| !moved.contains(child))
+                .copied()
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let index = siblings
+        .iter()
+        .position(|sibling| *sibling == target)
+        .map_or(siblings.len(), |index| {
+            if position == DropPosition::After {
+                index + 1
+            } else {
+                index
+            }
+        });
+    commands.entity(parent.get()).insert_children(index, &moved);
 }
 
 fn hierarchy_entity_context(
@@ -254,6 +689,16 @@ fn hierarchy_entity_context(
     }
 }
 
+/// Entities hidden in editor or by hidden layer are not rendered by editor cameras.
+/// [`ViewVisibility`] is computed every frame, so entities are shown again after flag is removed
+fn hide_editor_hidden(mut visibility: Query<(Entity, &mut ViewVisibility)>, flags: EditorFlags) {
+    for (entity, mut visibility) in visibility.iter_mut() {
+        if visibility.get() && flags.is_hidden(entity) {
+            *visibility = ViewVisibility::HIDDEN;
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct ClonedEntity;
 
@@ -264,36 +709,59 @@ fn clone_enitites(
     editor_registry: Res<EditorRegistry>,
 ) {
     for event in events.read() {
-        let mut queue = vec![(event.id, commands.spawn_empty().id())];
-        let mut map = HashMap::new();
-
-        while let Some((src_id, dst_id)) = queue.pop() {
-            map.insert(src_id, dst_id);
-            if let Ok(entity) = query.get(src_id) {
-                if entity.contains::<PrefabMarker>() {
-                    let mut cmds = commands.entity(dst_id);
-                    cmds.insert(ClonedEntity);
-
-                    editor_registry.clone_entity_flat(&mut cmds, &entity);
-
-                    if let Some(parent) = entity.get::<Parent>() {
-                        if let Some(new_parent) = map.get(&parent.get()) {
-                            commands.entity(*new_parent).add_child(dst_id);
-                        } else {
-                            commands.entity(parent.get()).add_child(dst_id);
-                        }
+        for id in clone_prefab_entity(
+            &mut commands,
+            &editor_registry,
+            |e| query.get(e).ok(),
+            event.id,
+        ) {
+            commands.entity(id).insert(ClonedEntity);
+        }
+    }
+    events.clear();
+}
+
+/// Clone prefab entity with all prefab children. Clone is attached to the same parent as source.
+/// Returns ids of spawned entities, first one is clone of `src`
+pub fn clone_prefab_entity<'w>(
+    commands: &mut Commands,
+    editor_registry: &EditorRegistry,
+    get: impl Fn(Entity) -> Option<EntityRef<'w>>,
+    src: Entity,
+) -> Vec<Entity> {
+    let mut queue = vec![(src, commands.spawn_empty().id())];
+    let mut map = HashMap::new();
+    let mut spawned = vec![];
+
+    while let Some((src_id, dst_id)) = queue.pop() {
+        map.insert(src_id, dst_id);
+        match get(src_id) {
+            Some(entity) if entity.contains::<PrefabMarker>() => {
+                spawned.push(dst_id);
+                let mut cmds = commands.entity(dst_id);
+
+                editor_registry.clone_entity_flat(&mut cmds, &entity);
+
+                if let Some(parent) = entity.get::<Parent>() {
+                    if let Some(new_parent) = map.get(&parent.get()) {
+                        commands.entity(*new_parent).add_child(dst_id);
+                    } else {
+                        commands.entity(parent.get()).add_child(dst_id);
                     }
+                }
 
-                    if let Some(children) = entity.get::<Children>() {
-                        for id in children {
-                            queue.push((*id, commands.spawn_empty().id()));
-                        }
+                if let Some(children) = entity.get::<Children>() {
+                    for id in children {
+                        queue.push((*id, commands.spawn_empty().id()));
                     }
                 }
             }
+            _ => {
+                commands.entity(dst_id).despawn();
+            }
         }
     }
-    events.clear();
+    spawned
 }
 
 fn detect_cloned_entities(
@@ -0,0 +1,261 @@
+use std::{any::TypeId, collections::BTreeMap};
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_egui_next::egui::{self, collapsing_header::CollapsingState};
+use space_prefab::editor_registry::EditorRegistry;
+
+use super::component_presets::{ComponentPreset, ComponentPresets};
+
+const RECENT_LIMIT: usize = 8;
+const OTHER_CATEGORY: &str = "Other";
+
+/// Recently added and favourite components of add component window, stored by type path
+#[derive(Resource, Reflect, Clone, PartialEq, Eq, Default)]
+#[reflect(Resource)]
+pub struct AddComponentHistory {
+    pub recent: Vec<String>,
+    pub favourites: Vec<String>,
+}
+
+impl AddComponentHistory {
+    pub fn used(&mut self, type_path: &str) {
+        self.recent.retain(|path| path != type_path);
+        self.recent.insert(0, type_path.to_string());
+        self.recent.truncate(RECENT_LIMIT);
+    }
+
+    pub fn is_favourite(&self, type_path: &str) -> bool {
+        self.favourites.iter().any(|path| path == type_path)
+    }
+
+    pub fn toggle_favourite(&mut self, type_path: &str) {
+        if self.is_favourite(type_path) {
+            self.favourites.retain(|path| path != type_path);
+        } else {
+            self.favourites.push(type_path.to_string());
+        }
+    }
+}
+
+/// Component shown in add component window
+pub struct ComponentEntry {
+    pub type_id: TypeId,
+    pub type_path: String,
+    pub name: String,
+    pub category: String,
+    pub description: String,
+    pub icon: String,
+    /// Component is added automatically with other components and has no own category.
+    /// It is shown under its parent components
+    pub relation_only: bool,
+}
+
+impl ComponentEntry {
+    /// Entry described by [`ComponentInfo`](space_prefab::editor_registry::ComponentInfo) or by type name and doc comment
+    pub fn new(
+        type_id: TypeId,
+        type_path: &str,
+        pretty_name: &str,
+        docs: Option<&str>,
+        registry: &EditorRegistry,
+    ) -> Self {
+        let info = registry
+            .component_info
+            .get(&type_id)
+            .cloned()
+            .unwrap_or_default();
+        let or_else = |value: String, default: &str| {
+            if value.is_empty() {
+                default.to_string()
+            } else {
+                value
+            }
+        };
+        Self {
+            type_id,
+            type_path: type_path.to_string(),
+            name: or_else(info.display_name, pretty_name),
+            category: or_else(info.category, OTHER_CATEGORY),
+            description: or_else(info.description, docs.unwrap_or_default().trim()),
+            icon: info.icon,
+            relation_only: !registry.component_info.contains_key(&type_id)
+                && !registry.relation_parents(&type_id).is_empty(),
+        }
+    }
+
+    fn label(&self) -> String {
+        if self.icon.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.icon, self.name)
+        }
+    }
+
+    fn search_score(&self, query: &str) -> Option<i32> {
+        let short_path = self.type_path.rsplit("::").next().unwrap_or_default();
+        fuzzy_score(query, &self.name).max(fuzzy_score(query, short_path))
+    }
+}
+
+/// Fuzzy match score of query in text. Query characters must be found in text in same order.
+/// Consecutive characters and word starts give higher score, `None` if text does not match
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text = text.chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut start = 0;
+    let mut last_match = None;
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let query_char = query_char.to_ascii_lowercase();
+        let idx = (start..text.len()).find(|idx| text[*idx].to_ascii_lowercase() == query_char)?;
+        score += 2;
+        if idx > 0 && last_match == Some(idx - 1) {
+            score += 4;
+        }
+        let word_start = idx == 0
+            || !text[idx - 1].is_alphanumeric()
+            || (text[idx].is_uppercase() && text[idx - 1].is_lowercase());
+        if word_start {
+            score += 3;
+        }
+        last_match = Some(idx);
+        start = idx + 1;
+    }
+    // shorter names are closer matches
+    Some(score * 4 - text.len() as i32)
+}
+
+/// Component or preset chosen in add component window
+pub enum AddComponentAction {
+    Add(TypeId),
+    Preset(ComponentPreset),
+}
+
+/// Content of add component window: fuzzy search, favourites, recently used and categories.
+/// Components added by relations are grouped under their parent component
+pub fn add_component_ui(
+    ui: &mut egui::Ui,
+    entries: &[ComponentEntry],
+    relations: &HashMap<TypeId, Vec<TypeId>>,
+    search: &mut String,
+    history: &mut AddComponentHistory,
+    presets: &ComponentPresets,
+) -> Option<AddComponentAction> {
+    let mut action = None;
+    ui.add(egui::TextEdit::singleline(search).hint_text("Search"));
+
+    let query = search.trim();
+    if !query.is_empty() {
+        let mut found = entries
+            .iter()
+            .filter_map(|entry| entry.search_score(query).map(|score| (score, entry)))
+            .collect::<Vec<_>>();
+        found.sort_by(|(score_a, a), (score_b, b)| score_b.cmp(score_a).then(a.name.cmp(&b.name)));
+        for (_, entry) in found {
+            entry_ui(ui, entry, history, presets, &mut action);
+        }
+        return action;
+    }
+
+    let by_path = |paths: &[String]| {
+        paths
+            .iter()
+            .filter_map(|path| entries.iter().find(|entry| &entry.type_path == path))
+            .collect::<Vec<_>>()
+    };
+    for (title, listed) in [
+        ("★ Favourites", by_path(&history.favourites)),
+        ("Recent", by_path(&history.recent)),
+    ] {
+        if listed.is_empty() {
+            continue;
+        }
+        ui.label(title);
+        for entry in listed {
+            entry_ui(ui, entry, history, presets, &mut action);
+        }
+        ui.separator();
+    }
+
+    let mut categories = BTreeMap::<&str, Vec<&ComponentEntry>>::new();
+    for entry in entries.iter().filter(|entry| !entry.relation_only) {
+        categories.entry(&entry.category).or_default().push(entry);
+    }
+    for (category, mut category_entries) in categories {
+        category_entries.sort_by(|a, b| a.name.cmp(&b.name));
+        egui::CollapsingHeader::new(category).show(ui, |ui| {
+            for entry in category_entries {
+                let children = relations
+                    .get(&entry.type_id)
+                    .map(|relations| {
+                        entries
+                            .iter()
+                            .filter(|child| {
+                                child.relation_only && relations.contains(&child.type_id)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                if children.is_empty() {
+                    entry_ui(ui, entry, history, presets, &mut action);
+                    continue;
+                }
+                CollapsingState::load_with_default_open(
+                    ui.ctx(),
+                    ui.make_persistent_id(&entry.type_path),
+                    false,
+                )
+                .show_header(ui, |ui| {
+                    entry_ui(ui, entry, history, presets, &mut action);
+                })
+                .body(|ui| {
+                    for child in children {
+                        entry_ui(ui, child, history, presets, &mut action);
+                    }
+                });
+            }
+        });
+    }
+    action
+}
+
+fn entry_ui(
+    ui: &mut egui::Ui,
+    entry: &ComponentEntry,
+    history: &mut AddComponentHistory,
+    presets: &ComponentPresets,
+    action: &mut Option<AddComponentAction>,
+) {
+    ui.vertical(|ui| {
+        ui.horizontal(|ui| {
+            let favourite = history.is_favourite(&entry.type_path);
+            if ui
+                .small_button(if favourite { "★" } else { "☆" })
+                .on_hover_text("Favourite")
+                .clicked()
+            {
+                history.toggle_favourite(&entry.type_path);
+            }
+            let mut button = ui.button(entry.label());
+            if !entry.description.is_empty() {
+                button = button.on_hover_text(entry.description.as_str());
+            }
+            if button.clicked() {
+                history.used(&entry.type_path);
+                *action = Some(AddComponentAction::Add(entry.type_id));
+            }
+        });
+        for preset in presets.for_component(&entry.type_path) {
+            ui.horizontal(|ui| {
+                ui.add_space(24.);
+                if ui
+                    .button(format!("↳ {}", preset.name))
+                    .on_hover_text(format!("{} preset", entry.name))
+                    .clicked()
+                {
+                    history.used(&entry.type_path);
+                    *action = Some(AddComponentAction::Preset(preset.clone()));
+                }
+            });
+        }
+    });
+}
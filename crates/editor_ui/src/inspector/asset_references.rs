@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use bevy_egui_next::*;
+use space_editor_core::prelude::*;
+use space_prefab::asset_refs::{find_broken_references, relink_asset_path, AssetReference};
+
+use crate::{
+    colors::{ERROR_COLOR, WARM_COLOR},
+    prelude::*,
+};
+
+/// Tab with list of prefab asset paths which point to missing files
+#[derive(Resource, Default)]
+pub struct AssetReferencesTab {
+    broken: Option<BTreeMap<String, Vec<AssetReference>>>,
+    relink_targets: HashMap<String, String>,
+}
+
+impl EditorTab for AssetReferencesTab {
+    fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
+        let asset_folder = world.resource::<AssetFolder>().0.clone();
+
+        if ui
+            .button("Scan")
+            .on_hover_text("Check asset paths of all prefab entities")
+            .clicked()
+            || self.broken.is_none()
+        {
+            self.broken = Some(find_broken_references(world, &asset_folder));
+        }
+
+        let Some(broken) = &self.broken else {
+            return;
+        };
+        if broken.is_empty() {
+            ui.label("No broken asset references");
+            return;
+        }
+
+        let mut relinked = false;
+        for (path, references) in broken.iter() {
+            ui.push_id(path, |ui| {
+                egui::CollapsingHeader::new(
+                    egui::RichText::new(format!("{} ({} references)", path, references.len()))
+                        .color(ERROR_COLOR),
+                )
+                .show(ui, |ui| {
+                    for reference in references.iter() {
+                        let name = world
+                            .get::<Name>(reference.entity)
+                            .map_or_else(|| format!("{:?}", reference.entity), |n| n.to_string());
+                        ui.label(format!(
+                            "{}: {}.{}",
+                            name, reference.component_name, reference.field
+                        ));
+                    }
+
+                    if ui.button("Select entities").clicked() {
+                        for entity in world.query_filtered::<Entity, With<Selected>>().iter(world) {
+                            commands.entity(entity).remove::<Selected>();
+                        }
+                        for reference in references.iter() {
+                            commands.entity(reference.entity).insert(Selected);
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    let target = self.relink_targets.entry(path.clone()).or_default();
+                    ui.add(egui::TextEdit::singleline(target).hint_text("new path"));
+                    let target_exists = asset_folder.join(target.as_str()).exists();
+                    if !target.is_empty() && !target_exists {
+                        ui.colored_label(WARM_COLOR, "not found");
+                    }
+                    if ui
+                        .add_enabled(!target.is_empty(), egui::Button::new("Relink"))
+                        .on_hover_text("Replace this path in all prefab entities")
+                        .clicked()
+                    {
+                        let changed = relink_asset_path(world, path, target);
+                        info!(
+                            "Relinked {} asset paths from {} to {}",
+                            changed, path, target
+                        );
+                        relinked = true;
+                    }
+                });
+            });
+        }
+
+        if relinked {
+            self.broken = Some(find_broken_references(world, &asset_folder));
+        }
+    }
+
+    fn title(&self) -> egui::WidgetText {
+        "Asset References".into()
+    }
+}
@@ -0,0 +1,224 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    prelude::*,
+    reflect::{
+        serde::{TypedReflectDeserializer, TypedReflectSerializer},
+        TypeRegistry,
+    },
+};
+use ron::ser::PrettyConfig;
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+use space_shared::AssetFolder;
+
+/// Project file with component presets, relative to asset folder.
+/// It is stored next to scenes, so presets are shared with project
+pub const COMPONENT_PRESETS_FILE: &str = "component_presets.ron";
+
+/// Path of [`COMPONENT_PRESETS_FILE`] in asset folder of project
+pub fn component_presets_path(asset_folder: &AssetFolder) -> PathBuf {
+    asset_folder.0.join(COMPONENT_PRESETS_FILE)
+}
+
+/// Component copied in inspector. Its values can be pasted to other entities
+#[derive(Resource, Default)]
+pub struct ComponentClipboard {
+    pub value: Option<Box<dyn Reflect>>,
+}
+
+impl ComponentClipboard {
+    /// Type path of copied component
+    pub fn type_path(&self) -> Option<&str> {
+        self.value
+            .as_ref()
+            .and_then(|value| value.get_represented_type_info())
+            .map(|info| info.type_path())
+    }
+}
+
+/// Named values of component
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ComponentPreset {
+    pub name: String,
+    /// Type path of component
+    pub component: String,
+    /// Component values in ron format
+    pub value: String,
+}
+
+/// All component presets of project, loaded from [`component_presets_path`]
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
+pub struct ComponentPresets {
+    pub presets: Vec<ComponentPreset>,
+    /// File exists but can not be parsed. It is not overwritten, so presets in it are not lost
+    #[serde(skip)]
+    pub parse_failed: bool,
+}
+
+impl ComponentPresets {
+    pub fn load(path: &Path) -> Self {
+        let Ok(data) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        ron::from_str(&data).unwrap_or_else(|err| {
+            error!(
+                "Failed to parse component presets {}: {}",
+                path.display(),
+                err
+            );
+            Self {
+                parse_failed: true,
+                ..default()
+            }
+        })
+    }
+
+    pub fn save(&self, path: &Path) {
+        if self.parse_failed {
+            error!(
+                "Component presets are not saved: fix or remove {} which can not be parsed",
+                path.display()
+            );
+            return;
+        }
+        let data = match ron::ser::to_string_pretty(self, PrettyConfig::default()) {
+            Ok(data) => data,
+            Err(err) => {
+                error!("Failed to serialize component presets: {}", err);
+                return;
+            }
+        };
+        if let Err(err) = std::fs::write(path, data) {
+            error!(
+                "Failed to save component presets {}: {}",
+                path.display(),
+                err
+            );
+        }
+    }
+
+    /// Presets of component with type path
+    pub fn for_component<'a>(
+        &'a self,
+        type_path: &'a str,
+    ) -> impl Iterator<Item = &'a ComponentPreset> + 'a {
+        self.presets
+            .iter()
+            .filter(move |preset| preset.component == type_path)
+    }
+
+    /// Save component values as preset. Preset with same name and component is replaced
+    pub fn add(
+        &mut self,
+        name: &str,
+        value: &dyn Reflect,
+        registry: &TypeRegistry,
+    ) -> Result<(), String> {
+        let component = value
+            .get_represented_type_info()
+            .ok_or_else(|| "component type is unknown".to_string())?
+            .type_path()
+            .to_string();
+        let value = ron::to_string(&TypedReflectSerializer::new(value, registry))
+            .map_err(|err| err.to_string())?;
+
+        let preset = ComponentPreset {
+            name: name.to_string(),
+            component,
+            value,
+        };
+        if let Some(existing) = self
+            .presets
+            .iter_mut()
+            .find(|p| p.name == preset.name && p.component == preset.component)
+        {
+            *existing = preset;
+        } else {
+            self.presets.push(preset);
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str, component: &str) {
+        self.presets
+            .retain(|preset| preset.name != name || preset.component != component);
+    }
+}
+
+impl ComponentPreset {
+    /// Deserialize preset values
+    pub fn value(&self, registry: &TypeRegistry) -> Result<Box<dyn Reflect>, String> {
+        let registration = registry
+            .get_with_type_path(&self.component)
+            .ok_or_else(|| format!("{} is not registered", self.component))?;
+        let mut deserializer =
+            ron::Deserializer::from_str(&self.value).map_err(|err| err.to_string())?;
+        TypedReflectDeserializer::new(registration, registry)
+            .deserialize(&mut deserializer)
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Apply component values to entity or insert component if entity does not have it.
+/// Change is detected by auto undo, so paste is undoable
+pub fn paste_component(world: &mut World, entity: Entity, value: &dyn Reflect) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let Some(reflect_component) = value
+        .get_represented_type_info()
+        .and_then(|info| type_registry.get_type_data::<ReflectComponent>(info.type_id()))
+    else {
+        warn!("Pasted value is not reflected component");
+        return;
+    };
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    reflect_component.apply_or_insert(&mut entity_mut, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("space_presets_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn presets_are_saved_and_loaded() {
+        let path = temp_path("round_trip.ron");
+        let mut registry = TypeRegistry::default();
+        registry.register::<Transform>();
+        let mut presets = ComponentPresets::default();
+        presets
+            .add("lifted", &Transform::from_xyz(0., 1., 0.), &registry)
+            .unwrap();
+
+        presets.save(&path);
+        let loaded = ComponentPresets::load(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.presets, presets.presets);
+        assert!(!loaded.parse_failed);
+    }
+
+    #[test]
+    fn broken_file_is_not_overwritten() {
+        let path = temp_path("broken.ron");
+        std::fs::write(&path, "(presets: [").unwrap();
+
+        let mut presets = ComponentPresets::load(&path);
+        presets.presets.push(ComponentPreset {
+            name: "new".to_string(),
+            component: "bevy_transform::components::transform::Transform".to_string(),
+            value: "()".to_string(),
+        });
+        presets.save(&path);
+        let data = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(presets.parse_failed);
+        assert_eq!(data.unwrap(), "(presets: [");
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::{
+    ecs::system::SystemState,
+    prelude::*,
+    reflect::{ReflectRef, VariantField},
+};
+use bevy_egui_next::egui;
+use space_editor_core::prelude::*;
+use space_prefab::{component::EntityLink, editor_registry::EditorRegistry};
+
+/// Eyedropper of [`EntityLink`] and [`Entity`] fields in inspector.
+/// While picking, next viewport or hierarchy click sets clicked entity to field instead of selecting it
+#[derive(Resource, Default)]
+pub struct EntityPicker {
+    /// Inspector id of field which waits for pick
+    field: Option<egui::Id>,
+    picked: Option<Entity>,
+}
+
+impl EntityPicker {
+    pub const fn is_picking(&self) -> bool {
+        self.field.is_some() && self.picked.is_none()
+    }
+
+    pub fn is_picking_field(&self, field: egui::Id) -> bool {
+        self.is_picking() && self.field == Some(field)
+    }
+
+    /// Toggle picking for field
+    pub fn toggle(&mut self, field: egui::Id) {
+        if self.is_picking_field(field) {
+            self.cancel();
+        } else {
+            self.field = Some(field);
+            self.picked = None;
+        }
+    }
+
+    pub const fn cancel(&mut self) {
+        self.field = None;
+        self.picked = None;
+    }
+
+    /// Returns true if click was taken by picking and must not change selection
+    pub const fn pick(&mut self, entity: Entity) -> bool {
+        if self.is_picking() {
+            self.picked = Some(entity);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Take picked entity for field
+    pub fn take(&mut self, field: egui::Id) -> Option<Entity> {
+        if self.field != Some(field) {
+            return None;
+        }
+        let picked = self.picked.take();
+        if picked.is_some() {
+            self.field = None;
+        }
+        picked
+    }
+}
+
+/// Picking is canceled by `Escape` and by selection change, because picking field is not shown anymore
+pub(crate) fn cancel_entity_picking(
+    mut picker: ResMut<EntityPicker>,
+    keyboard: Res<Input<KeyCode>>,
+    added: Query<(), Added<Selected>>,
+    mut removed: RemovedComponents<Selected>,
+) {
+    let selection_changed = !added.is_empty() || removed.read().next().is_some();
+    if picker.is_picking() && (keyboard.just_pressed(KeyCode::Escape) || selection_changed) {
+        picker.cancel();
+    }
+}
+
+/// Entities referenced by [`EntityLink`] and [`Entity`] fields of reflected value
+pub fn linked_entities(value: &dyn Reflect, entities: &mut Vec<Entity>) {
+    if let Some(link) = value.downcast_ref::<EntityLink>() {
+        entities.push(link.entity);
+        return;
+    }
+    if let Some(entity) = value.downcast_ref::<Entity>() {
+        entities.push(*entity);
+        return;
+    }
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => value
+            .iter_fields()
+            .for_each(|field| linked_entities(field, entities)),
+        ReflectRef::TupleStruct(value) => value
+            .iter_fields()
+            .for_each(|field| linked_entities(field, entities)),
+        ReflectRef::Tuple(value) => value
+            .iter_fields()
+            .for_each(|field| linked_entities(field, entities)),
+        ReflectRef::List(value) => value
+            .iter()
+            .for_each(|item| linked_entities(item, entities)),
+        ReflectRef::Array(value) => value
+            .iter()
+            .for_each(|item| linked_entities(item, entities)),
+        ReflectRef::Map(value) => value
+            .iter()
+            .for_each(|(_, item)| linked_entities(item, entities)),
+        ReflectRef::Enum(value) => value.iter_fields().for_each(|field| match field {
+            VariantField::Struct(_, field) | VariantField::Tuple(field) => {
+                linked_entities(field, entities);
+            }
+        }),
+        ReflectRef::Value(_) => {}
+    }
+}
+
+/// Draw lines from selected entities to entities linked by their components
+pub(crate) fn draw_entity_links(
+    world: &mut World,
+    mut gizmos_state: Local<Option<SystemState<Gizmos>>>,
+) {
+    let editor_registry = world.resource::<EditorRegistry>().clone();
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let selected = world
+        .query_filtered::<Entity, With<Selected>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+
+    let mut lines = vec![];
+    for owner in selected {
+        let Some(start) = world.get::<GlobalTransform>(owner) else {
+            continue;
+        };
+        let mut linked = vec![];
+        for registration in editor_registry.registry.read().iter() {
+            let Some(component) = type_registry
+                .get_type_data::<ReflectComponent>(registration.type_id())
+                .and_then(|reflect_component| reflect_component.reflect(world.entity(owner)))
+            else {
+                continue;
+            };
+            linked_entities(component, &mut linked);
+        }
+        for target in linked {
+            if target == owner || target == Entity::PLACEHOLDER {
+                continue;
+            }
+            if let Some(end) = world.get::<GlobalTransform>(target) {
+                lines.push((start.translation(), end.translation()));
+            }
+        }
+    }
+
+    let gizmos_state = gizmos_state.get_or_insert_with(|| SystemState::new(world));
+    let mut gizmos = gizmos_state.get_mut(world);
+    for (start, end) in lines {
+        gizmos.line(start, end, Color::CYAN);
+        gizmos.sphere(end, Quat::IDENTITY, 0.1, Color::CYAN);
+    }
+    gizmos_state.apply(world);
+}
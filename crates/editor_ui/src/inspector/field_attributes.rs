@@ -0,0 +1,503 @@
+use std::any::TypeId;
+
+use bevy::{
+    prelude::*,
+    reflect::{ReflectMut, TypeInfo},
+    utils::HashMap,
+};
+use bevy_egui_next::egui;
+use space_shared::ext::bevy_inspector_egui::reflect_inspector::InspectorUi;
+
+/// Widget to edit numeric field
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumberWidget {
+    #[default]
+    Drag,
+    /// Used only if field has range
+    Slider,
+}
+
+/// Inspector attributes of one field. Can be registered for any reflected type, including third-party types
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FieldAttributes {
+    pub range: Option<(f64, f64)>,
+    /// Drag speed or slider step
+    pub step: Option<f64>,
+    pub widget: NumberWidget,
+    /// Suffix of numeric value
+    pub unit: Option<String>,
+    /// Hover text of field name. Doc comment of field is used if not set
+    pub tooltip: Option<String>,
+    pub read_only: bool,
+    pub hidden: bool,
+    /// Fields of same group are shown under collapsing header after ungrouped fields
+    pub group: Option<String>,
+}
+
+impl FieldAttributes {
+    pub const fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    pub const fn step(mut self, step: f64) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    pub const fn slider(mut self) -> Self {
+        self.widget = NumberWidget::Slider;
+        self
+    }
+
+    pub fn unit(mut self, unit: &str) -> Self {
+        self.unit = Some(unit.to_string());
+        self
+    }
+
+    pub fn tooltip(mut self, tooltip: &str) -> Self {
+        self.tooltip = Some(tooltip.to_string());
+        self
+    }
+
+    pub const fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    pub const fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    pub fn group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+
+    /// Attributes change numeric widget
+    const fn is_number(&self) -> bool {
+        self.range.is_some() || self.step.is_some() || self.unit.is_some()
+    }
+}
+
+/// Inspector attributes of fields, keyed by type and field path (`"intensity"`, `"translation.y"`)
+#[derive(Resource, Default, Clone)]
+pub struct FieldAttributesRegistry {
+    pub types: HashMap<TypeId, HashMap<String, FieldAttributes>>,
+}
+
+impl FieldAttributesRegistry {
+    pub fn insert(&mut self, type_id: TypeId, path: &str, attributes: FieldAttributes) {
+        self.types
+            .entry(type_id)
+            .or_default()
+            .insert(path.to_string(), attributes);
+    }
+
+    pub fn get(&self, type_id: TypeId, path: &str) -> Option<&FieldAttributes> {
+        self.types.get(&type_id)?.get(path)
+    }
+}
+
+pub trait FieldAttributesExt {
+    /// registers inspector attributes for field of reflected type
+    fn editor_field_attributes<T: Reflect>(
+        &mut self,
+        path: &str,
+        attributes: FieldAttributes,
+    ) -> &mut Self;
+}
+
+impl FieldAttributesExt for App {
+    fn editor_field_attributes<T: Reflect>(
+        &mut self,
+        path: &str,
+        attributes: FieldAttributes,
+    ) -> &mut Self {
+        if !self.world.contains_resource::<FieldAttributesRegistry>() {
+            self.insert_resource(FieldAttributesRegistry::default());
+        }
+        self.world.resource_mut::<FieldAttributesRegistry>().insert(
+            TypeId::of::<T>(),
+            path,
+            attributes,
+        );
+        self
+    }
+}
+
+/// Attributes of bevy types shown in inspector
+pub(crate) fn register_default_attributes(app: &mut App) {
+    let shadows = FieldAttributes::default().group("Shadows");
+    let distance = FieldAttributes::default()
+        .range(0., f64::MAX)
+        .step(0.1)
+        .unit("m");
+    let angle = FieldAttributes::default()
+        .range(0., std::f64::consts::FRAC_PI_2)
+        .slider()
+        .unit("rad");
+
+    app.editor_field_attributes::<PointLight>(
+        "intensity",
+        FieldAttributes::default()
+            .range(0., f64::MAX)
+            .step(10.)
+            .unit("lm"),
+    )
+    .editor_field_attributes::<PointLight>("range", distance.clone())
+    .editor_field_attributes::<PointLight>("radius", distance.clone())
+    .editor_field_attributes::<PointLight>("shadows_enabled", shadows.clone())
+    .editor_field_attributes::<PointLight>("shadow_depth_bias", shadows.clone())
+    .editor_field_attributes::<PointLight>("shadow_normal_bias", shadows.clone());
+
+    app.editor_field_attributes::<SpotLight>(
+        "intensity",
+        FieldAttributes::default()
+            .range(0., f64::MAX)
+            .step(10.)
+            .unit("lm"),
+    )
+    .editor_field_attributes::<SpotLight>("range", distance.clone())
+    .editor_field_attributes::<SpotLight>("radius", distance)
+    .editor_field_attributes::<SpotLight>("outer_angle", angle.clone())
+    .editor_field_attributes::<SpotLight>("inner_angle", angle)
+    .editor_field_attributes::<SpotLight>("shadows_enabled", shadows.clone())
+    .editor_field_attributes::<SpotLight>("shadow_depth_bias", shadows.clone())
+    .editor_field_attributes::<SpotLight>("shadow_normal_bias", shadows.clone());
+
+    app.editor_field_attributes::<DirectionalLight>(
+        "illuminance",
+        FieldAttributes::default()
+            .range(0., f64::MAX)
+            .step(100.)
+            .unit("lx"),
+    )
+    .editor_field_attributes::<DirectionalLight>("shadows_enabled", shadows.clone())
+    .editor_field_attributes::<DirectionalLight>("shadow_depth_bias", shadows.clone())
+    .editor_field_attributes::<DirectionalLight>("shadow_normal_bias", shadows);
+}
+
+/// Draw component fields with registered attributes and doc comment tooltips.
+/// All values must have same type; when there are several, edits are applied to all of them.
+/// Values which are not structs are drawn by default inspector ui
+pub fn component_ui(
+    env: &mut InspectorUi<'_, '_>,
+    values: &mut [&mut dyn Reflect],
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    registry: &FieldAttributesRegistry,
+) -> bool {
+    let Some(type_id) = values
+        .first()
+        .and_then(|value| value.get_represented_type_info())
+        .map(|info| info.type_id())
+    else {
+        return reflect_ui(env, values, ui, id);
+    };
+    let empty = HashMap::new();
+    let attributes = registry.types.get(&type_id).unwrap_or(&empty);
+    if let Some(changed) = struct_ui(env, values, ui, id, attributes, "") {
+        return changed;
+    }
+    reflect_ui(env, values, ui, id)
+}
+
+/// Draw values of same type by default inspector ui
+pub fn reflect_ui(
+    env: &mut InspectorUi<'_, '_>,
+    values: &mut [&mut dyn Reflect],
+    ui: &mut egui::Ui,
+    id: egui::Id,
+) -> bool {
+    match values {
+        [] => false,
+        [value] => env.ui_for_reflect_with_options(*value, ui, id, &()),
+        _ => {
+            let type_id = values[0].as_any().type_id();
+            let name = values[0].reflect_short_type_path().to_string();
+            env.ui_for_reflect_many_with_options(type_id, &name, ui, id, &(), values, &|value| {
+                value
+            })
+        }
+    }
+}
+
+/// Some of values are not equal to the first one. Values which can not be compared are treated as equal
+pub fn values_differ<'a>(values: impl IntoIterator<Item = &'a dyn Reflect>) -> bool {
+    let mut values = values.into_iter();
+    let Some(first) = values.next() else {
+        return false;
+    };
+    values.any(|value| value.reflect_partial_eq(first) == Some(false))
+}
+
+fn field_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{prefix}.{name}")
+    }
+}
+
+/// Fields with differing values are shown as mixed.
+/// Returns `None` if values are not structs
+fn struct_ui(
+    env: &mut InspectorUi<'_, '_>,
+    values: &mut [&mut dyn Reflect],
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    attributes: &HashMap<String, FieldAttributes>,
+    prefix: &str,
+) -> Option<bool> {
+    let mut structs = Vec::with_capacity(values.len());
+    for value in values.iter_mut() {
+        let ReflectMut::Struct(value) = value.reflect_mut() else {
+            return None;
+        };
+        structs.push(value);
+    }
+    let structs_len = structs.len();
+    let value = structs.first()?;
+    let info = match value.get_represented_type_info() {
+        Some(TypeInfo::Struct(info)) => Some(info),
+        _ => None,
+    };
+    let names = (0..value.field_len())
+        .map(|idx| value.name_at(idx).unwrap_or_default().to_string())
+        .collect::<Vec<_>>();
+    let default_attributes = FieldAttributes::default();
+    let attributes_of = |idx: usize| {
+        attributes
+            .get(&field_path(prefix, &names[idx]))
+            .unwrap_or(&default_attributes)
+    };
+
+    // ungrouped fields go first
+    let mut groups: Vec<(Option<String>, Vec<usize>)> = vec![(None, vec![])];
+    for idx in 0..names.len() {
+        let field_attributes = attributes_of(idx);
+        if field_attributes.hidden {
+            continue;
+        }
+        if let Some((_, fields)) = groups
+            .iter_mut()
+            .find(|(group, _)| *group == field_attributes.group)
+        {
+            fields.push(idx);
+        } else {
+            groups.push((field_attributes.group.clone(), vec![idx]));
+        }
+    }
+
+    let mut changed = false;
+    for (group, fields) in groups {
+        if fields.is_empty() {
+            continue;
+        }
+        let group_id = id.with(&group);
+        let fields_ui = |ui: &mut egui::Ui| {
+            egui::Grid::new(group_id).show(ui, |ui| {
+                for idx in fields {
+                    let name = &names[idx];
+                    let path = field_path(prefix, name);
+                    let field_attributes = attributes_of(idx);
+                    let tooltip = field_attributes.tooltip.as_deref().or_else(|| {
+                        info.and_then(|info| info.field_at(idx))
+                            .and_then(|field| field.docs())
+                    });
+
+                    let label = ui.label(name);
+                    if let Some(tooltip) = tooltip {
+                        label.on_hover_text(tooltip.trim());
+                    }
+                    let mut fields = structs
+                        .iter_mut()
+                        .filter_map(|value| value.field_at_mut(idx))
+                        .collect::<Vec<_>>();
+                    if fields.len() == structs_len {
+                        let field_changed = ui
+                            .add_enabled_ui(!field_attributes.read_only, |ui| {
+                                field_ui(
+                                    env,
+                                    &mut fields,
+                                    ui,
+                                    group_id.with(idx),
+                                    attributes,
+                                    &path,
+                                    field_attributes,
+                                )
+                            })
+                            .inner;
+                        changed |= field_changed && !field_attributes.read_only;
+                    }
+                    ui.end_row();
+                }
+            });
+        };
+        match &group {
+            Some(group) => {
+                egui::CollapsingHeader::new(group.as_str())
+                    .id_source(group_id)
+                    .show(ui, fields_ui);
+            }
+            None => fields_ui(ui),
+        }
+    }
+    Some(changed)
+}
+
+fn field_ui(
+    env: &mut InspectorUi<'_, '_>,
+    fields: &mut [&mut dyn Reflect],
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    attributes: &HashMap<String, FieldAttributes>,
+    path: &str,
+    field_attributes: &FieldAttributes,
+) -> bool {
+    // nested fields have own attributes
+    let nested_prefix = format!("{path}.");
+    if attributes.keys().any(|key| key.starts_with(&nested_prefix)) {
+        if let Some(changed) = struct_ui(env, fields, ui, id, attributes, path) {
+            return changed;
+        }
+    }
+    let mixed = values_differ(fields.iter().map(|field| &**field));
+    if field_attributes.is_number() || mixed {
+        if let Some(changed) = number_ui(fields, ui, field_attributes, mixed) {
+            return changed;
+        }
+    }
+    if mixed {
+        return ui
+            .horizontal(|ui| {
+                ui.label(MIXED_PLACEHOLDER)
+                    .on_hover_text("Values of selected entities differ");
+                reflect_ui(env, fields, ui, id)
+            })
+            .inner;
+    }
+    reflect_ui(env, fields, ui, id)
+}
+
+/// Shown instead of value when selected entities have different values
+const MIXED_PLACEHOLDER: &str = "—";
+
+/// Widget edits first value, edited value is copied to other values.
+/// Returns `None` if fields are not numbers
+fn number_ui(
+    fields: &mut [&mut dyn Reflect],
+    ui: &mut egui::Ui,
+    attributes: &FieldAttributes,
+    mixed: bool,
+) -> Option<bool> {
+    let (first, others) = fields.split_first_mut()?;
+    let changed = number_value_ui(&mut **first, ui, attributes, mixed)?;
+    if changed {
+        for other in others.iter_mut() {
+            other.apply(&**first);
+        }
+    }
+    Some(changed)
+}
+
+/// Returns `None` if field is not number
+fn number_value_ui(
+    field: &mut dyn Reflect,
+    ui: &mut egui::Ui,
+    attributes: &FieldAttributes,
+    mixed: bool,
+) -> Option<bool> {
+    let field = field.as_any_mut();
+    macro_rules! numbers {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(value) = field.downcast_mut::<$ty>() {
+                    return Some(number_widget(value, ui, attributes, mixed));
+                }
+            )*
+        };
+    }
+    numbers!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+    None
+}
+
+/// Mixed value is shown as [`MIXED_PLACEHOLDER`] until it is edited
+fn number_widget<T: egui::emath::Numeric>(
+    value: &mut T,
+    ui: &mut egui::Ui,
+    attributes: &FieldAttributes,
+    mixed: bool,
+) -> bool {
+    let suffix = attributes
+        .unit
+        .as_ref()
+        .map(|unit| format!(" {unit}"))
+        .unwrap_or_default();
+    let response = match attributes.range {
+        Some((min, max)) if attributes.widget == NumberWidget::Slider => {
+            let mut slider =
+                egui::Slider::new(value, T::from_f64(min)..=T::from_f64(max)).suffix(suffix);
+            if let Some(step) = attributes.step {
+                slider = slider.step_by(step);
+            }
+            if mixed {
+                slider = slider.custom_formatter(|_, _| MIXED_PLACEHOLDER.to_string());
+            }
+            ui.add(slider)
+        }
+        range => {
+            let mut drag = egui::DragValue::new(value).suffix(suffix);
+            if let Some((min, max)) = range {
+                drag = drag.clamp_range(min..=max);
+            }
+            if let Some(step) = attributes.step {
+                drag = drag.speed(step);
+            }
+            if mixed {
+                drag = drag.custom_formatter(|_, _| MIXED_PLACEHOLDER.to_string());
+            }
+            ui.add(drag)
+        }
+    };
+    response.changed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn differing_fields_of_entities_are_mixed() {
+        let mut world = World::new();
+        let first = world
+            .spawn(PointLight {
+                intensity: 100.,
+                ..default()
+            })
+            .id();
+        let second = world
+            .spawn(PointLight {
+                intensity: 200.,
+                ..default()
+            })
+            .id();
+        let lights = [first, second].map(|entity| world.get::<PointLight>(entity).unwrap());
+        let field = |name: &str| {
+            lights
+                .iter()
+                .map(|light| light.field(name).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(values_differ(
+            lights.iter().map(|light| *light as &dyn Reflect)
+        ));
+        assert!(values_differ(field("intensity")));
+        assert!(!values_differ(field("range")));
+        assert!(!values_differ(field("color")));
+        assert!(!values_differ(std::iter::empty()));
+    }
+}
@@ -1,8 +1,14 @@
+pub mod add_component;
+pub mod asset_references;
+pub mod component_presets;
 pub mod components_order;
+pub mod entity_picker;
 pub mod events_dispatcher;
+pub mod field_attributes;
 pub mod refl_impl;
 pub mod resources;
 pub mod runtime_assets;
+pub mod selection_query;
 
 use std::any::TypeId;
 
@@ -17,9 +23,15 @@ use bevy::{
 use bevy_egui_next::*;
 
 use space_editor_core::prelude::*;
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
 use space_prefab::{component::EntityLink, editor_registry::EditorRegistry};
-use space_shared::ext::bevy_inspector_egui::{
-    self, inspector_egui_impls::InspectorEguiImpl, reflect_inspector::InspectorUi,
+use space_shared::{
+    ext::bevy_inspector_egui::{
+        self, inspector_egui_impls::InspectorEguiImpl, inspector_options::ReflectInspectorOptions,
+        reflect_inspector::InspectorUi,
+    },
+    AssetFolder, EditorSet,
 };
 
 use crate::{
@@ -29,11 +41,24 @@ use crate::{
 };
 
 use self::{
+    add_component::{add_component_ui, AddComponentAction, AddComponentHistory, ComponentEntry},
+    asset_references::AssetReferencesTab,
+    component_presets::{
+        component_presets_path, paste_component, ComponentClipboard, ComponentPresets,
+    },
     components_order::{ComponentsOrder, ComponentsPriority},
+    entity_picker::{cancel_entity_picking, draw_entity_links, EntityPicker},
     events_dispatcher::EventDispatcherTab,
-    refl_impl::{entity_ref_ui, entity_ref_ui_readonly, many_unimplemented},
+    field_attributes::{
+        component_ui, reflect_ui, register_default_attributes, values_differ,
+        FieldAttributesRegistry,
+    },
+    refl_impl::{
+        entity_ref_ui, entity_ref_ui_readonly, entity_ui, entity_ui_readonly, many_unimplemented,
+    },
     resources::ResourceTab,
     runtime_assets::RuntimeAssetsTab,
+    selection_query::SelectionQueryTab,
 };
 
 use super::{
@@ -53,6 +78,18 @@ impl Plugin for SpaceInspectorPlugin {
         app.init_resource::<InspectState>();
         app.init_resource::<FilterComponentState>();
         app.init_resource::<ComponentsOrder>();
+        app.init_resource::<ComponentClipboard>();
+        app.insert_resource(ComponentPresets::load(&component_presets_path(
+            &AssetFolder::from_app(app),
+        )));
+        app.init_resource::<FieldAttributesRegistry>();
+        app.init_resource::<EntityPicker>();
+        app.init_resource::<AddComponentHistory>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<AddComponentHistory>();
+        }
+        register_default_attributes(app);
         app.editor_component_priority::<Name>(0);
         app.editor_component_priority::<Transform>(1);
 
@@ -63,8 +100,17 @@ impl Plugin for SpaceInspectorPlugin {
             EventDispatcherTab::default(),
         );
         app.editor_tab_by_trait(EditorTabName::RuntimeAssets, RuntimeAssetsTab::default());
+        app.editor_tab_by_trait(
+            EditorTabName::AssetReferences,
+            AssetReferencesTab::default(),
+        );
+        app.editor_tab_by_trait(EditorTabName::SelectionQuery, SelectionQueryTab::default());
 
         app.add_systems(Update, execute_inspect_command);
+        app.add_systems(
+            Update,
+            (cancel_entity_picking, draw_entity_links).in_set(EditorSet::Editor),
+        );
 
         app.add_systems(Startup, register_custom_impls);
     }
@@ -95,6 +141,13 @@ fn register_custom_impls(registry: Res<AppTypeRegistry>) {
             entity_ref_ui_readonly,
             many_unimplemented::<EntityRef>,
         ));
+    if let Some(registration) = registry.get_mut(TypeId::of::<Entity>()) {
+        registration.insert(InspectorEguiImpl::new(
+            entity_ui,
+            entity_ui_readonly,
+            many_unimplemented::<Entity>,
+        ));
+    }
 }
 
 /// Function form `bevy_inspector_egui` to split component to data ptr and "set changed" function
@@ -112,16 +165,20 @@ pub fn mut_untyped_split(mut mut_untyped: MutUntyped<'_>) -> (PtrMut<'_>, impl F
 struct InspectState {
     commands: Vec<InspectCommand>,
     show_add_component_window: bool,
+    new_preset_name: String,
 }
 
 #[derive(Resource, Default)]
 struct FilterComponentState {
     component_add_filter: String,
+    add_component_search: String,
 }
 
 enum InspectCommand {
     AddComponent(Entity, TypeId),
     RemoveComponent(Entity, TypeId),
+    /// Apply values to component or insert it
+    PasteComponent(Entity, Box<dyn Reflect>),
 }
 
 fn execute_inspect_command(
@@ -129,32 +186,39 @@ fn execute_inspect_command(
     mut state: ResMut<InspectState>,
     registration: Res<EditorRegistry>,
 ) {
-    for c in &state.commands {
+    for c in state.commands.drain(..) {
         match c {
             InspectCommand::AddComponent(e, id) => {
                 info!("inspector adding component {:?} to entity {:?}", id, e);
-                commands.entity(*e).add(registration.get_spawn_command(id));
+                commands.entity(e).add(registration.get_spawn_command(&id));
             }
             InspectCommand::RemoveComponent(e, id) => {
-                registration.remove_by_id(&mut commands.entity(*e), id);
+                registration.remove_by_id(&mut commands.entity(e), &id);
+            }
+            InspectCommand::PasteComponent(e, value) => {
+                commands.add(move |world: &mut World| paste_component(world, e, &*value));
             }
         }
     }
-    state.commands.clear();
 }
 
-/// System to show inspector panel
+/// System to show inspector panel. When several entities are selected, only their shared components are shown,
+/// differing values are shown as mixed and edits are applied to all selected entities.
+/// All entities are changed in the same frame, so auto undo records the edit as one change
 pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashMap<String, bool>) {
     let sizing = world.resource::<Sizing>().clone();
-    let selected_entity = world
+    let mut selected_entities = world
         .query_filtered::<Entity, With<Selected>>()
-        .get_single(world);
+        .iter(world)
+        .collect::<Vec<_>>();
+    selected_entities.sort();
 
-    let Ok(selected_entity) = selected_entity else {
+    if selected_entities.is_empty() {
         return;
-    };
+    }
 
     let editor_registry = world.resource::<EditorRegistry>().clone();
+    let field_attributes = world.resource::<FieldAttributesRegistry>().clone();
     let all_registry = editor_registry.registry.clone();
     let registry = all_registry.read();
     let app_registry = world.resource::<AppTypeRegistry>().clone();
@@ -179,11 +243,18 @@ pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashM
 
     // println!("{:#?}\n", components_id);
 
+    let presets_path = component_presets_path(world.resource::<AssetFolder>());
     let cell = world.as_unsafe_world_cell();
+    // SAFETY: InspectState, ComponentClipboard and ComponentPresets are different resources and each of them
+    // is borrowed only once. `world` is not used directly while these borrows are alive
     let mut state = unsafe { cell.get_resource_mut::<InspectState>().unwrap() };
+    let mut clipboard = unsafe { cell.get_resource_mut::<ComponentClipboard>().unwrap() };
+    let mut presets = unsafe { cell.get_resource_mut::<ComponentPresets>().unwrap() };
 
     let mut commands: Vec<InspectCommand> = vec![];
     let mut queue = CommandQueue::default();
+    // SAFETY: inspector context only reads and writes components of inspected entities and assets,
+    // inspector resources borrowed above are not reachable from reflected values
     let mut cx = unsafe {
         bevy_inspector_egui::reflect_inspector::Context {
             world: Some(cell.world_mut().into()),
@@ -201,9 +272,15 @@ pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashM
         state.show_add_component_window = true;
     }
 
+    let entities = selected_entities
+        .iter()
+        .filter_map(|entity| cell.get_entity(*entity))
+        .collect::<Vec<_>>();
+
     let components_area = egui::ScrollArea::vertical().show(ui, |ui| {
-        if let Some(e) = cell.get_entity(selected_entity) {
+        if let [e] = entities.as_slice() {
             let mut name;
+            // SAFETY: Name is only read here, no mutable borrow of it is alive yet
             if let Some(name_struct) = unsafe { e.get::<Name>() } {
                 name = name_struct.as_str().to_string();
                 if name.is_empty() {
@@ -213,81 +290,184 @@ pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashM
                 name = format!("{:?}", e.id());
             }
             ui.heading(&name);
-            let mut state = unsafe { cell.get_resource_mut::<FilterComponentState>().unwrap() };
-            ui.text_edit_singleline(&mut state.component_add_filter);
-            let lower_filter = state.component_add_filter.to_lowercase();
-            let e_id = e.id().index();
+        } else if !entities.is_empty() {
+            ui.heading(format!("{} entities", entities.len()));
+        }
+        let Some(e) = entities.first() else {
+            return;
+        };
+
+        // SAFETY: FilterComponentState is not borrowed anywhere else while components are drawn
+        let mut filter = unsafe { cell.get_resource_mut::<FilterComponentState>().unwrap() };
+        ui.text_edit_singleline(&mut filter.component_add_filter);
+        let lower_filter = filter.component_add_filter.to_lowercase();
+        let e_id = e.id().index();
+        if entities.len() > 1 {
+            ui.label("Shared components:");
+        } else {
             ui.label("Components:");
-            egui::Grid::new(format!("{e_id}")).show(ui, |ui| {
-                for (c_id, t_id, name, _) in &components_id {
-                    if name.to_lowercase().contains(&lower_filter) {
-                        if let Some(data) = unsafe { e.get_mut_by_id(*c_id) } {
-                            let registration = registry.get(*t_id).unwrap();
-                            if let Some(reflect_from_ptr) = registration.data::<ReflectFromPtr>() {
-                                let (ptr, mut set_changed) = mut_untyped_split(data);
-
-                                let value = unsafe { reflect_from_ptr.from_ptr_mut()(ptr) };
-
-                                if !editor_registry.silent.contains(&registration.type_id()) {
-                                    ui.push_id(format!("{:?}-{}", &e.id(), &name), |ui| {
-                                        let header = egui::CollapsingHeader::new(name)
-                                            .default_open(
-                                                *open_components.get(name).unwrap_or(&false),
-                                            )
-                                            .show(ui, |ui| {
-                                                ui.push_id(
-                                                    format!("content-{:?}-{}", &e.id(), &name),
-                                                    |ui| {
-                                                        if env.ui_for_reflect_with_options(
-                                                            value,
-                                                            ui,
-                                                            ui.id(),
-                                                            &(),
-                                                        ) {
-                                                            set_changed();
-                                                        }
-                                                    },
-                                                );
-                                            });
-                                        if header.header_response.clicked() {
-                                            let open_name =
-                                                open_components.entry(name.clone()).or_default();
-                                            //At click header not opened simultaneously so its need to check percent of opened
-                                            *open_name = header.openness < 0.5;
-                                        }
-                                    });
-
-                                    ui.push_id(
-                                        format!("del component {:?}-{}", &e.id(), &name),
-                                        |ui| {
-                                            //must be on top
-                                            ui.with_layout(
-                                                egui::Layout::top_down(egui::Align::Min),
-                                                |ui| {
-                                                    let button = egui::Button::new("🗙")
-                                                        .fill(DEFAULT_BG_COLOR);
-                                                    if ui.add(button).clicked() {
-                                                        commands.push(
-                                                            InspectCommand::RemoveComponent(
-                                                                e.id(),
-                                                                *t_id,
-                                                            ),
-                                                        );
-                                                    }
-                                                },
-                                            );
-                                        },
-                                    );
-                                    ui.end_row();
+        }
+        egui::Grid::new(format!("{e_id}")).show(ui, |ui| {
+            for (c_id, t_id, name, _) in &components_id {
+                if !name.to_lowercase().contains(&lower_filter) {
+                    continue;
+                }
+                let registration = registry.get(*t_id).unwrap();
+                if editor_registry.silent.contains(&registration.type_id()) {
+                    continue;
+                }
+                let Some(reflect_from_ptr) = registration.data::<ReflectFromPtr>() else {
+                    continue;
+                };
+                // SAFETY: every selected entity is unique, so mutable access to their components does not alias.
+                // Borrows of previous component are dropped at the end of previous iteration
+                let datas = entities
+                    .iter()
+                    .filter_map(|e| unsafe { e.get_mut_by_id(*c_id) })
+                    .collect::<Vec<_>>();
+                if datas.len() != entities.len() {
+                    continue;
+                }
+                let (ptrs, mut set_changed): (Vec<_>, Vec<_>) =
+                    datas.into_iter().map(mut_untyped_split).unzip();
+                // SAFETY: ptrs point to component with type of this registration
+                let mut values = ptrs
+                    .into_iter()
+                    .map(|ptr| unsafe { reflect_from_ptr.from_ptr_mut()(ptr) })
+                    .collect::<Vec<&mut dyn Reflect>>();
+                let mixed = values_differ(values.iter().map(|value| &**value));
+
+                ui.push_id(format!("{:?}-{}", &e.id(), &name), |ui| {
+                    let title = if mixed {
+                        format!("{name} (mixed)")
+                    } else {
+                        name.clone()
+                    };
+                    let header = egui::CollapsingHeader::new(title)
+                        .id_source(name)
+                        .default_open(*open_components.get(name).unwrap_or(&false))
+                        .show(ui, |ui| {
+                            ui.push_id(format!("content-{:?}-{}", &e.id(), &name), |ui| {
+                                // types with own inspector ui keep it
+                                let custom_ui = world_registry.get(*t_id).is_some_and(|reg| {
+                                    reg.data::<InspectorEguiImpl>().is_some()
+                                        || reg.data::<ReflectInspectorOptions>().is_some()
+                                });
+                                let changed = if custom_ui {
+                                    reflect_ui(&mut env, values.as_mut_slice(), ui, ui.id())
+                                } else {
+                                    component_ui(
+                                        &mut env,
+                                        values.as_mut_slice(),
+                                        ui,
+                                        ui.id(),
+                                        &field_attributes,
+                                    )
+                                };
+                                if changed {
+                                    set_changed.iter_mut().for_each(|set_changed| set_changed());
+                                }
+                            });
+                        });
+                    let type_path = registration.type_info().type_path();
+                    header.header_response.context_menu(|ui| {
+                        if ui.button("Copy").clicked() {
+                            clipboard.value = Some(values[0].clone_value());
+                            ui.close_menu();
+                        }
+                        let paste = egui::Button::new("Paste values");
+                        if ui
+                            .add_enabled(clipboard.type_path() == Some(type_path), paste)
+                            .clicked()
+                        {
+                            if let Some(value) = &clipboard.value {
+                                for e in entities.iter() {
+                                    commands.push(InspectCommand::PasteComponent(
+                                        e.id(),
+                                        value.clone_value(),
+                                    ));
                                 }
                             }
+                            ui.close_menu();
                         }
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut state.new_preset_name)
+                                    .hint_text("Preset name")
+                                    .desired_width(100.),
+                            );
+                            let save = egui::Button::new("Save preset");
+                            if ui
+                                .add_enabled(!state.new_preset_name.is_empty(), save)
+                                .clicked()
+                            {
+                                match presets.add(
+                                    &state.new_preset_name,
+                                    &*values[0],
+                                    &world_registry,
+                                ) {
+                                    Ok(()) => presets.save(&presets_path),
+                                    Err(err) => error!("Failed to save preset: {}", err),
+                                }
+                                state.new_preset_name.clear();
+                                ui.close_menu();
+                            }
+                        });
+                        let mut removed = None;
+                        for preset in presets.for_component(type_path) {
+                            ui.horizontal(|ui| {
+                                if ui.button(&preset.name).clicked() {
+                                    match preset.value(&world_registry) {
+                                        Ok(value) => {
+                                            for e in entities.iter() {
+                                                commands.push(InspectCommand::PasteComponent(
+                                                    e.id(),
+                                                    value.clone_value(),
+                                                ));
+                                            }
+                                        }
+                                        Err(err) => error!("Failed to apply preset: {}", err),
+                                    }
+                                    ui.close_menu();
+                                }
+                                if ui
+                                    .small_button("🗙")
+                                    .on_hover_text("Delete preset")
+                                    .clicked()
+                                {
+                                    removed = Some(preset.name.clone());
+                                }
+                            });
+                        }
+                        if let Some(removed) = removed {
+                            presets.remove(&removed, type_path);
+                            presets.save(&presets_path);
+                        }
+                    });
+                    if header.header_response.clicked() {
+                        let open_name = open_components.entry(name.clone()).or_default();
+                        //At click header not opened simultaneously so its need to check percent of opened
+                        *open_name = header.openness < 0.5;
                     }
-                }
-            });
+                });
+
+                ui.push_id(format!("del component {:?}-{}", &e.id(), &name), |ui| {
+                    //must be on top
+                    ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                        let button = egui::Button::new("🗙").fill(DEFAULT_BG_COLOR);
+                        if ui.add(button).clicked() {
+                            for e in entities.iter() {
+                                commands.push(InspectCommand::RemoveComponent(e.id(), *t_id));
+                            }
+                        }
+                    });
+                });
+                ui.end_row();
+            }
+        });
 
-            ui.separator();
-        }
+        ui.separator();
     });
 
     let width = ui.available_width();
@@ -314,31 +494,67 @@ pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashM
         .open(&mut state.show_add_component_window)
         .resizable(true)
         .scroll2([false, true])
-        .default_width(120.)
+        .default_width(200.)
         .default_height(300.)
         .default_pos(components_area.inner_rect.center_bottom())
         .show(ui.ctx(), |ui: &mut egui::Ui| {
-            let mut state = unsafe { cell.get_resource_mut::<FilterComponentState>().unwrap() };
-            ui.text_edit_singleline(&mut state.component_add_filter);
-            let lower_filter = state.component_add_filter.to_lowercase();
-            egui::Grid::new("Component grid").show(ui, |ui| {
-                let _counter = 0;
-                for (c_id, _t_id, name, _) in &components_id {
-                    if name.to_lowercase().contains(&lower_filter) {
-                        ui.label(name);
-                        if ui.button("+").clicked() {
-                            let id = cell
-                                .components()
-                                .get_info(*c_id)
-                                .unwrap()
-                                .type_id()
-                                .unwrap();
-                            commands.push(InspectCommand::AddComponent(selected_entity, id));
-                        }
-                        ui.end_row();
+            // SAFETY: borrow of FilterComponentState from components list is already dropped,
+            // AddComponentHistory is borrowed only here
+            let mut filter = unsafe { cell.get_resource_mut::<FilterComponentState>().unwrap() };
+            let mut history = unsafe { cell.get_resource_mut::<AddComponentHistory>().unwrap() };
+            if let (Some(value), Some(type_path)) = (&clipboard.value, clipboard.type_path()) {
+                let name = pretty_type_name::pretty_type_name_str(type_path);
+                if ui
+                    .button(format!("Paste {name}"))
+                    .on_hover_text("Add copied component or replace its values")
+                    .clicked()
+                {
+                    for entity in selected_entities.iter() {
+                        commands.push(InspectCommand::PasteComponent(*entity, value.clone_value()));
+                    }
+                }
+                ui.separator();
+            }
+
+            let entries = components_id
+                .iter()
+                .filter(|(_, t_id, ..)| !editor_registry.silent.contains(t_id))
+                .filter_map(|(_, t_id, name, _)| {
+                    let registration = world_registry.get(*t_id)?;
+                    Some(ComponentEntry::new(
+                        *t_id,
+                        registration.type_info().type_path(),
+                        name,
+                        registration.type_info().docs(),
+                        &editor_registry,
+                    ))
+                })
+                .collect::<Vec<_>>();
+            let action = add_component_ui(
+                ui,
+                &entries,
+                &editor_registry.relations,
+                &mut filter.add_component_search,
+                &mut history,
+                &presets,
+            );
+            match action {
+                Some(AddComponentAction::Add(id)) => {
+                    for entity in selected_entities.iter() {
+                        commands.push(InspectCommand::AddComponent(*entity, id));
                     }
                 }
-            });
+                Some(AddComponentAction::Preset(preset)) => match preset.value(&world_registry) {
+                    Ok(value) => {
+                        for entity in selected_entities.iter() {
+                            commands
+                                .push(InspectCommand::PasteComponent(*entity, value.clone_value()));
+                        }
+                    }
+                    Err(err) => error!("Failed to apply preset: {}", err),
+                },
+                None => {}
+            }
         });
 
     //All works with this if statement. Dont change it and dont add is_using_pointer() method
@@ -1,7 +1,7 @@
 use std::any::{Any, TypeId};
 
 use bevy::{
-    prelude::{AppTypeRegistry, ResMut},
+    prelude::{AppTypeRegistry, Entity, ResMut},
     reflect::Reflect,
 };
 use bevy_egui_next::egui;
@@ -11,6 +11,8 @@ use space_shared::ext::bevy_inspector_egui::{
 
 use space_prefab::component::EntityLink;
 
+use super::entity_picker::EntityPicker;
+
 /// Method from `bevy_inspector_egui` to make dummy reflection ui
 pub fn many_unimplemented<T: Any>(
     _ui: &mut egui::Ui,
@@ -44,28 +46,82 @@ pub fn entity_ref_ui(
     env: InspectorUi<'_, '_>,
 ) -> bool {
     if let Some(value) = value.downcast_mut::<EntityLink>() {
-        if let Some(world) = &env.context.world {
-            egui::ComboBox::new(id, "")
-                .selected_text(format!("{:?}", value.entity))
-                .show_ui(ui, |ui| {
-                    let world_ref = unsafe { world.world().world() };
-                    for e in world_ref.iter_entities() {
-                        if ui
-                            .selectable_value(&mut value.entity, e.id(), format!("{:?}", e.id()))
-                            .clicked()
-                        {
-                            return true;
-                        }
-                    }
-                    false
-                });
-        } else {
-            ui.label(format!("{:?}", &value.entity));
-        }
+        return entity_pick_ui(&mut value.entity, ui, id, &env);
+    }
+    false
+}
+
+/// Custom UI for [`Entity`] fields
+pub fn entity_ui(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    id: egui::Id,
+    env: InspectorUi<'_, '_>,
+) -> bool {
+    if let Some(value) = value.downcast_mut::<Entity>() {
+        return entity_pick_ui(value, ui, id, &env);
     }
     false
 }
 
+/// Entity list with eyedropper button, which picks entity by next viewport or hierarchy click
+fn entity_pick_ui(
+    entity: &mut Entity,
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    env: &InspectorUi<'_, '_>,
+) -> bool {
+    let Some(world) = &env.context.world else {
+        ui.label(format!("{:?}", entity));
+        return false;
+    };
+    let world = world.world();
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        changed = egui::ComboBox::new(id, "")
+            .selected_text(format!("{:?}", entity))
+            .show_ui(ui, |ui| {
+                let world_ref = unsafe { world.world() };
+                world_ref.iter_entities().any(|e| {
+                    ui.selectable_value(entity, e.id(), format!("{:?}", e.id()))
+                        .clicked()
+                })
+            })
+            .inner
+            .unwrap_or(false);
+
+        let Some(mut picker) = (unsafe { world.get_resource_mut::<EntityPicker>() }) else {
+            return;
+        };
+        if let Some(picked) = picker.take(id) {
+            *entity = picked;
+            changed = true;
+        }
+        if ui
+            .selectable_label(picker.is_picking_field(id), "🎯")
+            .on_hover_text("Pick from viewport or hierarchy (Esc to cancel)")
+            .clicked()
+        {
+            picker.toggle(id);
+        }
+    });
+    changed
+}
+
+/// Read only UI for [`Entity`] fields
+pub fn entity_ui_readonly(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    _: egui::Id,
+    _: InspectorUi<'_, '_>,
+) {
+    if let Some(value) = value.downcast_ref::<Entity>() {
+        ui.label(format!("{:?}", value));
+    }
+}
+
 /// Custom UI for [`EntityLink`] struct
 pub fn entity_ref_ui_readonly(
     _value: &dyn Any,
@@ -0,0 +1,231 @@
+use std::{any::TypeId, collections::BTreeMap};
+
+use bevy::{prelude::*, utils::HashSet};
+
+use bevy_egui_next::*;
+use space_editor_core::prelude::*;
+use space_prefab::{
+    component::SelectionSets,
+    editor_registry::EditorRegistry,
+    query::{query_entities, CompareOp, QueryCondition},
+};
+use space_shared::PrefabMarker;
+
+use crate::prelude::*;
+
+/// Tab to select prefab entities by components and field values, and to save named selection sets
+#[derive(Resource, Default)]
+pub struct SelectionQueryTab {
+    conditions: Vec<QueryCondition>,
+    last_matches: Option<usize>,
+    new_set_name: String,
+}
+
+impl EditorTab for SelectionQueryTab {
+    fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
+        let components = registered_components(world);
+        let component_name = |type_id: TypeId| {
+            components
+                .iter()
+                .find(|(id, _)| *id == type_id)
+                .map_or("Unknown", |(_, name)| name.as_str())
+        };
+
+        ui.label("Query");
+        let mut removed = None;
+        for (idx, condition) in self.conditions.iter_mut().enumerate() {
+            ui.push_id(idx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut condition.without, "Not")
+                        .on_hover_text("Entity must not have component");
+                    egui::ComboBox::from_id_source("component")
+                        .selected_text(component_name(condition.component))
+                        .show_ui(ui, |ui| {
+                            for (type_id, name) in components.iter() {
+                                ui.selectable_value(&mut condition.component, *type_id, name);
+                            }
+                        });
+                    if !condition.without {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut condition.field)
+                                .hint_text("field")
+                                .desired_width(80.),
+                        );
+                        if !condition.field.is_empty() {
+                            egui::ComboBox::from_id_source("op")
+                                .selected_text(condition.op.symbol())
+                                .width(40.)
+                                .show_ui(ui, |ui| {
+                                    for op in CompareOp::ALL {
+                                        ui.selectable_value(&mut condition.op, op, op.symbol());
+                                    }
+                                });
+                            ui.add(
+                                egui::TextEdit::singleline(&mut condition.value)
+                                    .hint_text("value")
+                                    .desired_width(80.),
+                            );
+                        }
+                    }
+                    if ui.button("🗑").clicked() {
+                        removed = Some(idx);
+                    }
+                });
+            });
+        }
+        if let Some(idx) = removed {
+            self.conditions.remove(idx);
+        }
+
+        if let Some((type_id, _)) = components.first() {
+            if ui.button("➕ Condition").clicked() {
+                self.conditions.push(QueryCondition::new(*type_id, false));
+            }
+        }
+
+        ui.horizontal(|ui| {
+            let enabled = !self.conditions.is_empty();
+            let mut op = None;
+            if ui
+                .add_enabled(enabled, egui::Button::new("Select"))
+                .clicked()
+            {
+                op = Some(SelectionOp::Replace);
+            }
+            if ui
+                .add_enabled(enabled, egui::Button::new("Add to selection"))
+                .clicked()
+            {
+                op = Some(SelectionOp::Add);
+            }
+            if let Some(op) = op {
+                let found = query_entities(world, &self.conditions)
+                    .into_iter()
+                    .collect::<HashSet<_>>();
+                self.last_matches = Some(found.len());
+                apply_selection(commands, world, op, &found);
+            }
+            if let Some(matches) = self.last_matches {
+                ui.label(format!("{} matches", matches));
+            }
+        });
+
+        ui.separator();
+        ui.label("Selection sets");
+
+        let mut sets: BTreeMap<String, HashSet<Entity>> = BTreeMap::new();
+        for (entity, entity_sets) in world
+            .query_filtered::<(Entity, &SelectionSets), With<PrefabMarker>>()
+            .iter(world)
+        {
+            for set in entity_sets.sets.iter() {
+                sets.entry(set.clone()).or_default().insert(entity);
+            }
+        }
+
+        let mut deleted = None;
+        for (name, members) in sets.iter() {
+            ui.push_id(name, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} ({})", name, members.len()));
+                    if ui.button("Select").clicked() {
+                        apply_selection(commands, world, SelectionOp::Replace, members);
+                    }
+                    if ui.button("Add").clicked() {
+                        apply_selection(commands, world, SelectionOp::Add, members);
+                    }
+                    if ui.button("🗑").on_hover_text("Delete set").clicked() {
+                        deleted = Some(name.clone());
+                    }
+                });
+            });
+        }
+        if let Some(name) = deleted {
+            save_selection_set(world, &name, &HashSet::new());
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_set_name).hint_text("set name"));
+            if ui
+                .add_enabled(
+                    !self.new_set_name.is_empty(),
+                    egui::Button::new("Save selection"),
+                )
+                .on_hover_text("Replace set members with selected entities")
+                .clicked()
+            {
+                let selected = world
+                    .query_filtered::<Entity, (With<Selected>, With<PrefabMarker>)>()
+                    .iter(world)
+                    .collect::<HashSet<_>>();
+                save_selection_set(world, &self.new_set_name, &selected);
+            }
+        });
+    }
+
+    fn title(&self) -> egui::WidgetText {
+        "Select by Query".into()
+    }
+}
+
+/// Components registered in editor, sorted by short name
+fn registered_components(world: &World) -> Vec<(TypeId, String)> {
+    let registry = world.resource::<EditorRegistry>().registry.clone();
+    let registry = registry.read();
+    let mut components = registry
+        .iter()
+        .filter(|registration| registration.data::<ReflectComponent>().is_some())
+        .map(|registration| {
+            (
+                registration.type_id(),
+                registration
+                    .type_info()
+                    .type_path_table()
+                    .short_path()
+                    .to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    components.sort_by(|a, b| a.1.cmp(&b.1));
+    components
+}
+
+fn apply_selection(
+    commands: &mut Commands,
+    world: &mut World,
+    op: SelectionOp,
+    entities: &HashSet<Entity>,
+) {
+    let selected = world
+        .query_filtered::<Entity, With<Selected>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    op.apply(commands, selected, entities);
+}
+
+/// Set members of named selection set. Empty members delete set
+fn save_selection_set(world: &mut World, name: &str, members: &HashSet<Entity>) {
+    let entities = world
+        .query_filtered::<Entity, With<SelectionSets>>()
+        .iter(world)
+        .chain(members.iter().copied())
+        .collect::<HashSet<_>>();
+    for entity in entities {
+        let mut sets = world
+            .get::<SelectionSets>(entity)
+            .cloned()
+            .unwrap_or_default();
+        if members.contains(&entity) {
+            sets.add(name);
+        } else {
+            sets.remove(name);
+        }
+
+        let mut entity_mut = world.entity_mut(entity);
+        if sets.sets.is_empty() {
+            entity_mut.remove::<SelectionSets>();
+        } else if entity_mut.get::<SelectionSets>() != Some(&sets) {
+            entity_mut.insert(sets);
+        }
+    }
+}
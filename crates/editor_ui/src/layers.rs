@@ -0,0 +1,197 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_egui_next::*;
+use space_editor_core::prelude::*;
+use space_prefab::component::{
+    scene_layers, EntityLayer, SceneDataHolder, SceneLayer, SceneLayers,
+};
+use space_shared::PrefabMarker;
+
+use crate::{prelude::*, LAST_RENDER_LAYER};
+
+/// Plugin to activate layers tab in editor UI
+pub struct LayersPlugin;
+
+impl Plugin for LayersPlugin {
+    fn build(&self, app: &mut App) {
+        app.editor_tab_by_trait(EditorTabName::Layers, LayersTab::default());
+    }
+}
+
+/// Tab to create scene layers, assign entities to them and toggle their visibility and lock
+#[derive(Resource, Default)]
+pub struct LayersTab {
+    new_layer_name: String,
+}
+
+impl EditorTab for LayersTab {
+    fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
+        let mut layers = scene_layers(world);
+        let mut members: HashMap<String, HashSet<Entity>> = HashMap::new();
+        for (entity, layer) in world
+            .query_filtered::<(Entity, &EntityLayer), With<PrefabMarker>>()
+            .iter(world)
+        {
+            members
+                .entry(layer.layer.clone())
+                .or_default()
+                .insert(entity);
+        }
+        let selected = world
+            .query_filtered::<Entity, (With<Selected>, With<PrefabMarker>)>()
+            .iter(world)
+            .collect::<Vec<_>>();
+
+        let mut changed = false;
+        let mut assigned = None;
+        let mut deleted = None;
+        for (idx, layer) in layers.layers.iter_mut().enumerate() {
+            let layer_members = members.get(&layer.name);
+            ui.push_id(idx, |ui| {
+                ui.horizontal(|ui| {
+                    let eye = if layer.visible { "👁" } else { "◌" };
+                    if ui
+                        .add(egui::Button::new(eye).frame(false))
+                        .on_hover_text("Show in editor")
+                        .clicked()
+                    {
+                        layer.visible = !layer.visible;
+                        changed = true;
+                    }
+                    let lock = if layer.locked { "🔒" } else { "🔓" };
+                    if ui
+                        .add(egui::Button::new(lock).frame(false))
+                        .on_hover_text("Lock")
+                        .clicked()
+                    {
+                        layer.locked = !layer.locked;
+                        changed = true;
+                    }
+                    ui.label(format!(
+                        "{} ({})",
+                        layer.name,
+                        layer_members.map_or(0, HashSet::len)
+                    ));
+
+                    if ui.button("Select").clicked() {
+                        SelectionOp::Replace.apply(
+                            commands,
+                            selected.iter().copied(),
+                            layer_members.unwrap_or(&HashSet::new()),
+                        );
+                    }
+                    if ui
+                        .add_enabled(!selected.is_empty(), egui::Button::new("Assign"))
+                        .on_hover_text("Move selected entities to layer")
+                        .clicked()
+                    {
+                        assigned = Some(layer.name.clone());
+                    }
+
+                    let mut mapped = layer.render_layer.is_some();
+                    if ui
+                        .checkbox(&mut mapped, "Render layer")
+                        .on_hover_text("RenderLayers of layer entities in game")
+                        .changed()
+                    {
+                        layer.render_layer = mapped.then_some(1);
+                        changed = true;
+                    }
+                    if let Some(render_layer) = layer.render_layer.as_mut() {
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(render_layer)
+                                    .clamp_range(0..=LAST_RENDER_LAYER),
+                            )
+                            .changed();
+                    }
+
+                    if ui.button("🗑").on_hover_text("Delete layer").clicked() {
+                        deleted = Some(idx);
+                    }
+                });
+            });
+        }
+        if layers.layers.is_empty() {
+            ui.label("No layers");
+        }
+
+        if let Some(layer) = assigned {
+            for entity in selected.iter() {
+                commands.entity(*entity).insert(EntityLayer {
+                    layer: layer.clone(),
+                });
+            }
+        }
+        if let Some(idx) = deleted {
+            let layer = layers.layers.remove(idx);
+            for entity in members.get(&layer.name).into_iter().flatten() {
+                commands.entity(*entity).remove::<EntityLayer>();
+            }
+            changed = true;
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_layer_name).hint_text("layer name"));
+            let name = self.new_layer_name.trim();
+            if ui
+                .add_enabled(
+                    !name.is_empty() && layers.get(name).is_none(),
+                    egui::Button::new("➕ Layer"),
+                )
+                .clicked()
+            {
+                layers.layers.push(SceneLayer {
+                    name: name.to_string(),
+                    ..default()
+                });
+                self.new_layer_name.clear();
+                changed = true;
+            }
+        });
+        if ui
+            .add_enabled(
+                !selected.is_empty(),
+                egui::Button::new("Remove selected from layers"),
+            )
+            .clicked()
+        {
+            for entity in selected.iter() {
+                commands.entity(*entity).remove::<EntityLayer>();
+            }
+        }
+
+        if changed {
+            set_scene_layers(world, layers);
+        }
+    }
+
+    fn title(&self) -> egui::WidgetText {
+        "Layers".into()
+    }
+}
+
+/// Store layers in scene. Layers are kept on separate prefab entity, which is spawned on demand
+/// and hidden from hierarchy
+pub fn set_scene_layers(world: &mut World, layers: SceneLayers) {
+    let holder = world
+        .query_filtered::<Entity, (With<SceneLayers>, With<PrefabMarker>)>()
+        .iter(world)
+        .next();
+    match holder {
+        Some(entity) => {
+            world.entity_mut(entity).insert((layers, SceneDataHolder));
+        }
+        None => {
+            world.spawn((
+                layers,
+                SceneDataHolder,
+                PrefabMarker,
+                Name::new("Scene Layers"),
+            ));
+        }
+    }
+}
@@ -21,12 +21,18 @@ pub mod editor_tab;
 /// This module contains Game view tab logic
 pub mod game_view;
 
+/// This module contains additional view tabs with own editor cameras
+pub mod viewports;
+
 /// This module contains Hierarchy tab logic
 pub mod hierarchy;
 
 /// This module contains Inspector tab logic
 pub mod inspector;
 
+/// This module contains Layers tab logic
+pub mod layers;
+
 /// This module contains methods to visualize entities without a mesh attached
 pub mod meshless_visualizer;
 
@@ -100,15 +106,22 @@ use ui_registration::BundleReg;
 use camera_plugin::*;
 use ui_plugin::*;
 
-use self::{mouse_check::MouseCheck, tools::gizmo::GizmoToolPlugin};
+use self::{
+    mouse_check::MouseCheck,
+    tools::{
+        array::ArrayToolPlugin, gizmo::GizmoToolPlugin, scatter::ScatterToolPlugin,
+        toolbox::ToolBoxPlugin,
+    },
+    viewports::{EditorViewports, ViewportsPlugin},
+};
 
 pub const LAST_RENDER_LAYER: u8 = RenderLayers::TOTAL_LAYERS as u8 - 1;
 
 pub mod prelude {
     pub use super::{
         asset_inspector::*, change_chain::*, debug_panels::*, editor_tab::*, game_view::*,
-        hierarchy::*, inspector::*, menu_toolbars::*, meshless_visualizer::*, settings::*, tool::*,
-        tools::*, ui_registration::*,
+        hierarchy::*, inspector::*, layers::*, menu_toolbars::*, meshless_visualizer::*,
+        settings::*, tool::*, tools::*, ui_registration::*, viewports::*,
     };
 
     pub use space_editor_core::prelude::*;
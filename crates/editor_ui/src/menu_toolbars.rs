@@ -15,6 +15,7 @@ use crate::{
     hierarchy::{HierarchyQueryIter, HierarchyTabState},
     icons::{add_bundle_icon, add_entity_icon, delete_entity_icon, prefab_icon},
     sizing::{to_label, to_richtext, Sizing},
+    tools::placement::{PlaceOnSurface, SurfacePlacement},
     ui_registration::{BundleReg, EditorBundleUntyped},
 };
 
@@ -127,6 +128,7 @@ pub struct MenuToolbarState {
     pub file_dialog: Option<egui_file::FileDialog>,
     pub gltf_dialog: Option<egui_file::FileDialog>,
     pub save_dialog: Option<egui_file::FileDialog>,
+    pub cook_dialog: Option<egui_file::FileDialog>,
     pub load_dialog: Option<egui_file::FileDialog>,
     pub path: String,
 }
@@ -141,6 +143,7 @@ pub fn bottom_menu(
     ui_reg: Res<BundleReg>,
     menu_state: Res<MenuToolbarState>,
     sizing: Res<Sizing>,
+    placement: Option<Res<SurfacePlacement>>,
 ) {
     let ctx = ctxs.ctx_mut();
     egui::TopBottomPanel::bottom("bottom_menu")
@@ -225,6 +228,12 @@ pub fn bottom_menu(
                                             let button = egui::Button::new(name).ui(ui);
                                             if button.clicked() {
                                                 let entity = dyn_bundle.spawn(&mut commands);
+                                                if placement
+                                                    .as_ref()
+                                                    .is_some_and(|p| p.place_new_bundles)
+                                                {
+                                                    commands.entity(entity).insert(PlaceOnSurface);
+                                                }
                                                 changes.send(NewChange {
                                                     change: Arc::new(AddedEntity { entity }),
                                                 });
@@ -362,6 +371,46 @@ pub fn top_menu(
                 }
                 // End Save File
 
+                // Cook scene
+                let cook_button = egui::Button::new(to_richtext("📦", &sizing.icon))
+                    .stroke(stroke_default_color());
+                if ui
+                    .add(cook_button)
+                    .on_hover_text("Cook scene for runtime (inline prefabs, strip editor data)")
+                    .clicked()
+                {
+                    let mut cook_dialog =
+                        egui_file::FileDialog::save_file(Some("./assets/scenes".into()))
+                            .default_filename("Scene0.cooked.scn.ron")
+                            .title("Cook Scene");
+                    cook_dialog.open();
+                    menu_state.cook_dialog = Some(cook_dialog);
+                }
+
+                if let Some(cook_dialog) = &mut menu_state.cook_dialog {
+                    if cook_dialog.show(ctx).selected() {
+                        if let Some(file) = cook_dialog.path() {
+                            let path = file.to_str().unwrap().to_string();
+                            if path.ends_with(".scn.ron") {
+                                editor_events.send(EditorEvent::Cook(EditorPrefabPath::File(path)));
+                            }
+                        }
+                    } else {
+                        let mut need_move_to_default_dir = false;
+                        if let Some(path) = cook_dialog.directory().to_str() {
+                            if !path.contains("assets") {
+                                need_move_to_default_dir = true;
+                            }
+                        } else {
+                            need_move_to_default_dir = true;
+                        }
+                        if need_move_to_default_dir {
+                            cook_dialog.set_path("assets/");
+                        }
+                    }
+                }
+                // End Cook scene
+
                 // Load Scene
                 let load_button = egui::Button::new(to_richtext("📤", &sizing.icon))
                     .stroke(stroke_default_color());
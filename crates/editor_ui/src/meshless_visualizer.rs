@@ -42,7 +42,8 @@ impl Plugin for MeshlessVisualizerPlugin {
             Update,
             (visualize_meshless, visualize_custom_meshless).in_set(EditorSet::Editor),
         )
-        .editor_registry::<CustomMeshless>();
+        .editor_registry::<CustomMeshless>()
+        .editor_only::<CustomMeshless>();
     }
 }
 
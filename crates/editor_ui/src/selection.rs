@@ -1,5 +1,6 @@
-use crate::*;
-use bevy::prelude::*;
+use crate::{inspector::entity_picker::EntityPicker, *};
+use bevy::{prelude::*, utils::HashSet};
+use space_prefab::component::{EditorFlags, EditorLocked, EntityLayer, SceneLayers};
 
 pub struct EditorPickingPlugin;
 
@@ -15,6 +16,7 @@ impl Plugin for EditorPickingPlugin {
             Update,
             (auto_add_picking, select_listener).after(UiSystemSet::Last),
         );
+        app.add_systems(Update, sync_locked_picking.in_set(EditorSet::Editor));
         app.add_systems(PostUpdate, auto_add_picking_dummy);
     }
 }
@@ -50,6 +52,57 @@ pub fn auto_add_picking_dummy(
     }
 }
 
+/// [`Pickable`] of locked entity before lock. It is restored when entity is unlocked
+#[derive(Component, Default)]
+pub struct LockedPickingOverride(Pickable);
+
+/// Locked entities and their children are not hit by picking, so clicks pass through them.
+///
+/// Only entities whose lock could change are checked: flagged, reparented or moved between layers entities
+/// with their descendants, and just added pickables. All entities are checked when scene layers change
+pub fn sync_locked_picking(
+    mut commands: Commands,
+    flags: EditorFlags,
+    changed: Query<Entity, Or<(Changed<EditorLocked>, Changed<Parent>, Changed<EntityLayer>)>>,
+    mut unlocked: RemovedComponents<EditorLocked>,
+    mut unparented: RemovedComponents<Parent>,
+    mut left_layer: RemovedComponents<EntityLayer>,
+    changed_layers: Query<(), (Changed<SceneLayers>, With<PrefabMarker>)>,
+    children: Query<&Children>,
+    mut pickables: Query<(Entity, &mut Pickable, Option<&mut LockedPickingOverride>)>,
+) {
+    let mut dirty = HashSet::new();
+    for root in changed
+        .iter()
+        .chain(unlocked.read())
+        .chain(unparented.read())
+        .chain(left_layer.read())
+    {
+        dirty.insert(root);
+        dirty.extend(children.iter_descendants(root));
+    }
+    let all_dirty = !changed_layers.is_empty();
+
+    for (entity, mut pickable, locked_override) in pickables.iter_mut() {
+        if !all_dirty && !dirty.contains(&entity) && !pickable.is_added() {
+            continue;
+        }
+        match (flags.is_locked(entity), locked_override) {
+            (true, None) => {
+                let previous = std::mem::replace(&mut *pickable, Pickable::IGNORE);
+                commands
+                    .entity(entity)
+                    .insert(LockedPickingOverride(previous));
+            }
+            (false, Some(mut locked_override)) => {
+                *pickable = std::mem::take(&mut locked_override.0);
+                commands.entity(entity).remove::<LockedPickingOverride>();
+            }
+            _ => {}
+        }
+    }
+}
+
 pub fn select_listener(
     mut commands: Commands,
     query: Query<Entity, With<Selected>>,
@@ -58,6 +111,7 @@ pub fn select_listener(
     mut events: EventReader<SelectEvent>,
     pan_orbit_state: ResMut<EditorCameraEnabled>,
     keyboard: Res<Input<KeyCode>>,
+    mut picker: ResMut<EntityPicker>,
 ) {
     if !pan_orbit_state.0 {
         events.clear();
@@ -68,12 +122,12 @@ pub fn select_listener(
         let entity = query_parent.get(event.e).map_or(event.e, |a| a.parent);
         match event.event.button {
             PointerButton::Primary => {
-                commands.entity(entity).insert(Selected);
-                if !keyboard.pressed(KeyCode::ShiftLeft) {
-                    for e in query.iter() {
-                        commands.entity(e).remove::<Selected>();
-                    }
+                // click sets inspector entity field while picking
+                if picker.pick(entity) {
+                    continue;
                 }
+                let op = SelectionOp::from_keyboard(&keyboard);
+                op.apply(&mut commands, query.iter(), &HashSet::from([entity]));
             }
             PointerButton::Secondary => { /*Show context menu?*/ }
             PointerButton::Middle => {}
@@ -81,13 +135,77 @@ pub fn select_listener(
     }
 }
 
+/// Pressed Shift and Ctrl, left and right keys are the same.
+/// Viewport, box selection and hierarchy read selection modifiers with it
+pub fn selection_modifiers(keyboard: &Input<KeyCode>) -> (bool, bool) {
+    (
+        keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+        keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+    )
+}
+
+/// How picked entities change current selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionOp {
+    /// Select only picked entities
+    Replace,
+    /// Add picked entities to selection (Shift)
+    Add,
+    /// Remove picked entities from selection (Ctrl)
+    Subtract,
+    /// Invert selection of picked entities (Shift+Ctrl)
+    Toggle,
+}
+
+impl SelectionOp {
+    pub const fn from_modifiers(shift: bool, ctrl: bool) -> Self {
+        match (shift, ctrl) {
+            (false, false) => Self::Replace,
+            (true, false) => Self::Add,
+            (false, true) => Self::Subtract,
+            (true, true) => Self::Toggle,
+        }
+    }
+
+    pub fn from_keyboard(keyboard: &Input<KeyCode>) -> Self {
+        let (shift, ctrl) = selection_modifiers(keyboard);
+        Self::from_modifiers(shift, ctrl)
+    }
+
+    /// Change [`Selected`] markers of `selected` (current selection) and `picked` entities
+    pub fn apply(
+        self,
+        commands: &mut Commands,
+        selected: impl IntoIterator<Item = Entity>,
+        picked: &HashSet<Entity>,
+    ) {
+        let selected = selected.into_iter().collect::<HashSet<_>>();
+        if self == Self::Replace {
+            for e in selected.difference(picked) {
+                commands.entity(*e).remove::<Selected>();
+            }
+        }
+        for e in picked.iter() {
+            let select = match self {
+                Self::Replace | Self::Add => true,
+                Self::Subtract => false,
+                Self::Toggle => !selected.contains(e),
+            };
+            if select && !selected.contains(e) {
+                commands.entity(*e).insert(Selected);
+            } else if !select && selected.contains(e) {
+                commands.entity(*e).remove::<Selected>();
+            }
+        }
+    }
+}
+
 pub fn delete_selected(
     mut commands: Commands,
     query: Query<Entity, With<Selected>>,
     keyboard: Res<Input<KeyCode>>,
 ) {
-    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
-    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let (shift, ctrl) = selection_modifiers(&keyboard);
     let delete = keyboard.just_pressed(KeyCode::Back) || keyboard.just_pressed(KeyCode::Delete);
 
     if ctrl && shift && delete {
@@ -114,3 +232,101 @@ pub struct SelectEvent {
     e: Entity,
     event: ListenerInput<Pointer<Down>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::CommandQueue;
+
+    /// Apply op to world where `selected` entities are selected and return indices of selected entities
+    fn apply(op: SelectionOp, selected: &[usize], picked: &[usize]) -> Vec<usize> {
+        let mut world = World::new();
+        let entities = (0..4).map(|_| world.spawn_empty().id()).collect::<Vec<_>>();
+        for idx in selected {
+            world.entity_mut(entities[*idx]).insert(Selected);
+        }
+
+        let mut queue = CommandQueue::default();
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            op.apply(
+                &mut commands,
+                selected.iter().map(|idx| entities[*idx]),
+                &picked.iter().map(|idx| entities[*idx]).collect(),
+            );
+        }
+        queue.apply(&mut world);
+
+        (0..entities.len())
+            .filter(|idx| world.get::<Selected>(entities[*idx]).is_some())
+            .collect()
+    }
+
+    #[test]
+    fn ops_change_selection() {
+        assert_eq!(apply(SelectionOp::Replace, &[0, 1], &[1, 2]), vec![1, 2]);
+        assert_eq!(apply(SelectionOp::Add, &[0, 1], &[1, 2]), vec![0, 1, 2]);
+        assert_eq!(apply(SelectionOp::Subtract, &[0, 1], &[1, 2]), vec![0]);
+        assert_eq!(apply(SelectionOp::Toggle, &[0, 1], &[1, 2]), vec![0, 2]);
+    }
+
+    #[test]
+    fn modifiers_map_to_ops() {
+        assert_eq!(
+            SelectionOp::from_modifiers(false, false),
+            SelectionOp::Replace
+        );
+        assert_eq!(SelectionOp::from_modifiers(true, false), SelectionOp::Add);
+        assert_eq!(
+            SelectionOp::from_modifiers(false, true),
+            SelectionOp::Subtract
+        );
+        assert_eq!(SelectionOp::from_modifiers(true, true), SelectionOp::Toggle);
+    }
+
+    #[test]
+    fn right_modifier_keys_are_same_as_left() {
+        let mut keyboard = Input::<KeyCode>::default();
+        keyboard.press(KeyCode::ShiftRight);
+        assert_eq!(SelectionOp::from_keyboard(&keyboard), SelectionOp::Add);
+        keyboard.press(KeyCode::ControlRight);
+        assert_eq!(SelectionOp::from_keyboard(&keyboard), SelectionOp::Toggle);
+        keyboard.release(KeyCode::ShiftRight);
+        keyboard.press(KeyCode::ControlLeft);
+        assert_eq!(SelectionOp::from_keyboard(&keyboard), SelectionOp::Subtract);
+    }
+
+    fn pickable(app: &App, entity: Entity) -> (bool, bool) {
+        let pickable = app.world.get::<Pickable>(entity).unwrap();
+        (pickable.should_block_lower, pickable.should_emit_events)
+    }
+
+    #[test]
+    fn lock_keeps_own_pickable_of_entity() {
+        let mut app = App::new();
+        app.add_systems(Update, sync_locked_picking);
+        let root = app.world.spawn((EditorLocked, Pickable::default())).id();
+        let helper = app.world.spawn(Pickable::IGNORE).id();
+        let child = app
+            .world
+            .spawn(Pickable {
+                should_block_lower: false,
+                should_emit_events: true,
+            })
+            .id();
+        app.world.entity_mut(root).add_child(child);
+
+        app.update();
+
+        assert_eq!(pickable(&app, root), (false, false));
+        assert_eq!(pickable(&app, child), (false, false));
+
+        app.world.entity_mut(root).remove::<EditorLocked>();
+        app.update();
+
+        assert_eq!(pickable(&app, root), (true, true));
+        assert_eq!(pickable(&app, child), (false, true));
+        assert_eq!(pickable(&app, helper), (false, false));
+        assert!(app.world.get::<LockedPickingOverride>(child).is_none());
+    }
+}
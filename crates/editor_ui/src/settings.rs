@@ -10,7 +10,10 @@ use space_undo::ChangeChainSettings;
 #[cfg(feature = "persistence_editor")]
 use space_persistence::*;
 
-use crate::sizing::{IconSize, Sizing};
+use crate::{
+    sizing::{IconSize, Sizing},
+    tools::gizmo::GizmoSnapping,
+};
 
 use super::{
     editor_tab::{EditorTab, EditorTabName},
@@ -196,6 +199,12 @@ impl EditorTab for SettingsWindow {
             );
         });
 
+        ui.add_space(12.);
+        ui.heading("Gizmo Snapping");
+        if world.contains_resource::<GizmoSnapping>() {
+            world.resource_mut::<GizmoSnapping>().ui(ui);
+        }
+
         ui.add_space(12.);
         ui.heading("New Tab Behaviour");
         let new_window_settings = &mut world.resource_mut::<NewWindowSettings>();
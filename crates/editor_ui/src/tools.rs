@@ -1 +1,7 @@
+pub mod align;
+pub mod array;
 pub mod gizmo;
+pub mod marquee;
+pub mod placement;
+pub mod scatter;
+pub mod toolbox;
@@ -0,0 +1,491 @@
+use bevy::{prelude::*, render::primitives::Aabb, utils::HashSet};
+use bevy_egui_next::egui;
+use space_editor_core::prelude::*;
+use space_shared::*;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+use super::placement::with_descendants;
+
+/// Plugin with align and distribute operations for selected entities
+pub struct AlignPlugin;
+
+impl Plugin for AlignPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AlignSettings>()
+            .register_type::<AlignSettings>()
+            .register_type::<AlignMode>()
+            .register_type::<AlignTarget>()
+            .register_type::<DistributeMode>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<AlignSettings>();
+        }
+
+        app.editor_hotkey(AlignHotkey::AlignX, vec![KeyCode::AltLeft, KeyCode::Key1]);
+        app.editor_hotkey(AlignHotkey::AlignY, vec![KeyCode::AltLeft, KeyCode::Key2]);
+        app.editor_hotkey(AlignHotkey::AlignZ, vec![KeyCode::AltLeft, KeyCode::Key3]);
+        app.editor_hotkey(
+            AlignHotkey::DistributeX,
+            vec![KeyCode::AltLeft, KeyCode::Key4],
+        );
+        app.editor_hotkey(
+            AlignHotkey::DistributeY,
+            vec![KeyCode::AltLeft, KeyCode::Key5],
+        );
+        app.editor_hotkey(
+            AlignHotkey::DistributeZ,
+            vec![KeyCode::AltLeft, KeyCode::Key6],
+        );
+
+        app.add_systems(Update, align_hotkeys.in_set(EditorSet::Editor));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum AlignHotkey {
+    AlignX,
+    AlignY,
+    AlignZ,
+    DistributeX,
+    DistributeY,
+    DistributeZ,
+}
+
+impl Hotkey for AlignHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::AlignX => "Align selected on X".to_string(),
+            Self::AlignY => "Align selected on Y".to_string(),
+            Self::AlignZ => "Align selected on Z".to_string(),
+            Self::DistributeX => "Distribute selected on X".to_string(),
+            Self::DistributeY => "Distribute selected on Y".to_string(),
+            Self::DistributeZ => "Distribute selected on Z".to_string(),
+        }
+    }
+}
+
+/// Side of bounds which is aligned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum AlignMode {
+    Min,
+    #[default]
+    Center,
+    Max,
+}
+
+impl AlignMode {
+    pub const ALL: [Self; 3] = [Self::Min, Self::Center, Self::Max];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Min => "Min",
+            Self::Center => "Center",
+            Self::Max => "Max",
+        }
+    }
+}
+
+/// Bounds to which selected entities are aligned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum AlignTarget {
+    /// Bounds of all selected entities
+    #[default]
+    Selection,
+    /// Bounds of active selected entity
+    ActiveEntity,
+}
+
+impl AlignTarget {
+    pub const ALL: [Self; 2] = [Self::Selection, Self::ActiveEntity];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Selection => "Selection bounds",
+            Self::ActiveEntity => "Active entity",
+        }
+    }
+}
+
+/// Rule of spreading entities between first and last one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum DistributeMode {
+    /// Equal distance between centers
+    #[default]
+    Spacing,
+    /// Equal empty space between bounds
+    Gaps,
+}
+
+impl DistributeMode {
+    pub const ALL: [Self; 2] = [Self::Spacing, Self::Gaps];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Spacing => "Equal spacing",
+            Self::Gaps => "Equal gaps",
+        }
+    }
+}
+
+/// Settings of align and distribute operations. Hotkeys use the same settings
+#[derive(Resource, Reflect, Clone, PartialEq, Eq, Default)]
+#[reflect(Resource)]
+pub struct AlignSettings {
+    pub mode: AlignMode,
+    pub target: AlignTarget,
+    pub distribute: DistributeMode,
+}
+
+impl AlignSettings {
+    /// Show settings and operation buttons. Operations are applied to world immediately
+    pub fn ui(&mut self, ui: &mut egui::Ui, world: &mut World) {
+        egui::Grid::new("align_settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Align");
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("align_mode")
+                        .selected_text(self.mode.name())
+                        .show_ui(ui, |ui| {
+                            for mode in AlignMode::ALL {
+                                ui.selectable_value(&mut self.mode, mode, mode.name());
+                            }
+                        });
+                    egui::ComboBox::from_id_source("align_target")
+                        .selected_text(self.target.name())
+                        .show_ui(ui, |ui| {
+                            for target in AlignTarget::ALL {
+                                ui.selectable_value(&mut self.target, target, target.name());
+                            }
+                        });
+                    for (axis, name) in ["X", "Y", "Z"].into_iter().enumerate() {
+                        if ui.button(name).clicked() {
+                            align_selected(world, axis, self.mode, self.target);
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Distribute");
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("distribute_mode")
+                        .selected_text(self.distribute.name())
+                        .show_ui(ui, |ui| {
+                            for mode in DistributeMode::ALL {
+                                ui.selectable_value(&mut self.distribute, mode, mode.name());
+                            }
+                        });
+                    for (axis, name) in ["X", "Y", "Z"].into_iter().enumerate() {
+                        if ui.button(name).clicked() {
+                            distribute_selected(world, axis, self.distribute);
+                        }
+                    }
+                });
+                ui.end_row();
+            });
+    }
+}
+
+fn align_hotkeys(
+    mut commands: Commands,
+    input: Res<Input<AlignHotkey>>,
+    settings: Res<AlignSettings>,
+) {
+    let axes = [
+        (AlignHotkey::AlignX, AlignHotkey::DistributeX),
+        (AlignHotkey::AlignY, AlignHotkey::DistributeY),
+        (AlignHotkey::AlignZ, AlignHotkey::DistributeZ),
+    ];
+    let AlignSettings {
+        mode,
+        target,
+        distribute: distribute_mode,
+    } = settings.clone();
+    for (axis, (align, distribute)) in axes.into_iter().enumerate() {
+        if input.just_pressed(align) {
+            commands.add(move |world: &mut World| {
+                align_selected(world, axis, mode, target);
+            });
+        }
+        if input.just_pressed(distribute) {
+            commands.add(move |world: &mut World| {
+                distribute_selected(world, axis, distribute_mode);
+            });
+        }
+    }
+}
+
+/// World space bounds of entity with its descendants. Entities without [`Aabb`] are used as points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityBounds {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl EntityBounds {
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    fn side(&self, axis: usize, mode: AlignMode) -> f32 {
+        match mode {
+            AlignMode::Min => self.min[axis],
+            AlignMode::Center => self.center()[axis],
+            AlignMode::Max => self.max[axis],
+        }
+    }
+
+    pub fn of_entity(world: &World, entity: Entity) -> Option<Self> {
+        let origin = world.get::<GlobalTransform>(entity)?.translation();
+        let mut bounds = Self {
+            min: origin,
+            max: origin,
+        };
+        let mut has_aabb = false;
+        for part in with_descendants(world, [entity]) {
+            let (Some(aabb), Some(global)) =
+                (world.get::<Aabb>(part), world.get::<GlobalTransform>(part))
+            else {
+                continue;
+            };
+            let (min, max) = (Vec3::from(aabb.min()), Vec3::from(aabb.max()));
+            for i in 0..8 {
+                let corner = global.transform_point(Vec3::select(
+                    BVec3::new(i & 1 != 0, i & 2 != 0, i & 4 != 0),
+                    max,
+                    min,
+                ));
+                if has_aabb {
+                    bounds.min = bounds.min.min(corner);
+                    bounds.max = bounds.max.max(corner);
+                } else {
+                    bounds = Self {
+                        min: corner,
+                        max: corner,
+                    };
+                    has_aabb = true;
+                }
+            }
+        }
+        Some(bounds)
+    }
+}
+
+/// Offsets along axis, which align bounds to reference bounds
+pub fn align_offsets(
+    bounds: &[EntityBounds],
+    reference: &EntityBounds,
+    axis: usize,
+    mode: AlignMode,
+) -> Vec<f32> {
+    bounds
+        .iter()
+        .map(|b| reference.side(axis, mode) - b.side(axis, mode))
+        .collect()
+}
+
+/// Offsets along axis, which spread bounds evenly between the lowest and the highest ones
+pub fn distribute_offsets(bounds: &[EntityBounds], axis: usize, mode: DistributeMode) -> Vec<f32> {
+    let mut offsets = vec![0.; bounds.len()];
+    if bounds.len() < 3 {
+        return offsets;
+    }
+    let mut order = (0..bounds.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| {
+        bounds[*a].center()[axis]
+            .partial_cmp(&bounds[*b].center()[axis])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let first = bounds[order[0]];
+    let last = bounds[order[order.len() - 1]];
+    let steps = (order.len() - 1) as f32;
+
+    match mode {
+        DistributeMode::Spacing => {
+            let step = (last.center()[axis] - first.center()[axis]) / steps;
+            for (i, idx) in order.iter().enumerate() {
+                offsets[*idx] =
+                    first.center()[axis] + step * i as f32 - bounds[*idx].center()[axis];
+            }
+        }
+        DistributeMode::Gaps => {
+            let sizes = order
+                .iter()
+                .map(|idx| bounds[*idx].size()[axis])
+                .sum::<f32>();
+            let gap = (last.max[axis] - first.min[axis] - sizes) / steps;
+            let mut min = first.min[axis];
+            for idx in order.iter() {
+                offsets[*idx] = min - bounds[*idx].min[axis];
+                min += bounds[*idx].size()[axis] + gap;
+            }
+        }
+    }
+    offsets
+}
+
+/// Selected entities, which are not moved together with selected ancestor
+fn selected_roots(world: &mut World) -> Vec<Entity> {
+    let mut query = world.query_filtered::<Entity, (With<Selected>, With<Transform>)>();
+    let selected = query.iter(world).collect::<HashSet<_>>();
+    let mut roots = selected
+        .iter()
+        .copied()
+        .filter(|entity| {
+            let mut current = *entity;
+            while let Some(parent) = world.get::<Parent>(current) {
+                current = parent.get();
+                if selected.contains(&current) {
+                    return false;
+                }
+            }
+            true
+        })
+        .collect::<Vec<_>>();
+    roots.sort();
+    roots
+}
+
+fn selected_bounds(world: &mut World) -> Vec<(Entity, EntityBounds)> {
+    selected_roots(world)
+        .into_iter()
+        .filter_map(|entity| Some((entity, EntityBounds::of_entity(world, entity)?)))
+        .collect()
+}
+
+/// Align selected entities on world axis (0 - X, 1 - Y, 2 - Z).
+/// All transforms are changed in one frame, so undo records them as one change
+pub fn align_selected(world: &mut World, axis: usize, mode: AlignMode, target: AlignTarget) {
+    let selected = selected_bounds(world);
+    let reference = match target {
+        AlignTarget::Selection => selected
+            .iter()
+            .map(|(_, bounds)| *bounds)
+            .reduce(|a, b| a.union(&b)),
+        AlignTarget::ActiveEntity => world
+            .resource::<ActiveSelected>()
+            .0
+            .and_then(|active| EntityBounds::of_entity(world, active)),
+    };
+    let Some(reference) = reference else {
+        return;
+    };
+    let bounds = selected
+        .iter()
+        .map(|(_, bounds)| *bounds)
+        .collect::<Vec<_>>();
+    let offsets = align_offsets(&bounds, &reference, axis, mode);
+    move_entities(world, &selected, &offsets, axis);
+}
+
+/// Distribute selected entities on world axis (0 - X, 1 - Y, 2 - Z). Outermost entities keep their positions
+pub fn distribute_selected(world: &mut World, axis: usize, mode: DistributeMode) {
+    let selected = selected_bounds(world);
+    let bounds = selected
+        .iter()
+        .map(|(_, bounds)| *bounds)
+        .collect::<Vec<_>>();
+    let offsets = distribute_offsets(&bounds, axis, mode);
+    move_entities(world, &selected, &offsets, axis);
+}
+
+fn move_entities(
+    world: &mut World,
+    entities: &[(Entity, EntityBounds)],
+    offsets: &[f32],
+    axis: usize,
+) {
+    for ((entity, _), offset) in entities.iter().zip(offsets) {
+        if offset.abs() < f32::EPSILON {
+            continue;
+        }
+        let Some(global) = world.get::<GlobalTransform>(*entity).copied() else {
+            continue;
+        };
+        let mut delta = Vec3::ZERO;
+        delta[axis] = *offset;
+        let new_global = GlobalTransform::from_translation(delta) * global;
+        let parent_global = world
+            .get::<Parent>(*entity)
+            .and_then(|parent| world.get::<GlobalTransform>(parent.get()))
+            .copied();
+        if let Some(mut transform) = world.get_mut::<Transform>(*entity) {
+            *transform = parent_global.map_or_else(
+                || new_global.compute_transform(),
+                |parent_global| new_global.reparented_to(&parent_global),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bounds with given extent along x axis
+    fn x_bounds(min: f32, max: f32) -> EntityBounds {
+        EntityBounds {
+            min: Vec3::new(min, 0., 0.),
+            max: Vec3::new(max, 1., 1.),
+        }
+    }
+
+    #[test]
+    fn align_to_reference_sides() {
+        let bounds = [x_bounds(0., 1.), x_bounds(3., 5.)];
+        let reference = x_bounds(-2., 2.);
+
+        assert_eq!(
+            align_offsets(&bounds, &reference, 0, AlignMode::Min),
+            vec![-2., -5.]
+        );
+        assert_eq!(
+            align_offsets(&bounds, &reference, 0, AlignMode::Center),
+            vec![-0.5, -4.]
+        );
+        assert_eq!(
+            align_offsets(&bounds, &reference, 0, AlignMode::Max),
+            vec![1., -3.]
+        );
+        // other axes are not touched by offsets along x
+        assert_eq!(
+            align_offsets(&bounds, &reference, 1, AlignMode::Center),
+            vec![0., 0.]
+        );
+    }
+
+    #[test]
+    fn distribute_unequal_bounds() {
+        // selection order differs from order along axis
+        let bounds = [x_bounds(9., 13.), x_bounds(0., 2.), x_bounds(3., 4.)];
+
+        // centers 11, 1 and 3.5 become 11, 1 and 6
+        assert_eq!(
+            distribute_offsets(&bounds, 0, DistributeMode::Spacing),
+            vec![0., 0., 2.5]
+        );
+        // 7 units of bounds in 13 units of span leave two gaps of 3
+        assert_eq!(
+            distribute_offsets(&bounds, 0, DistributeMode::Gaps),
+            vec![0., 0., 2.]
+        );
+        // two entities are already distributed
+        assert_eq!(
+            distribute_offsets(&bounds[..2], 0, DistributeMode::Spacing),
+            vec![0., 0.]
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use std::{any::Any, sync::Arc};
+
+use bevy::{ecs::system::CommandQueue, prelude::*};
+use bevy_egui_next::egui;
+use space_editor_core::prelude::*;
+use space_prefab::{component::*, editor_registry::EditorRegistry};
+use space_shared::*;
+use space_undo::{AddedEntity, NewChange, OneFrameUndoIgnore, UndoIngnoreStorage};
+
+use crate::{hierarchy::clone_prefab_entity, prelude::EditorTool, tool::ToolExt};
+
+use super::{gizmo::Cursor3d, placement::with_descendants};
+
+/// Plugin with array tool and regeneration of [`ArrayModifier`] copies
+pub struct ArrayToolPlugin;
+
+impl Plugin for ArrayToolPlugin {
+    fn build(&self, app: &mut App) {
+        app.editor_tool(ArrayTool);
+        app.add_systems(Update, detect_array_changes.in_set(EditorSet::Editor));
+    }
+}
+
+/// Tool to make linear, grid, radial or path arrays of selected entity.
+/// Array stays parametric until it is applied
+#[derive(Default)]
+pub struct ArrayTool;
+
+impl EditorTool for ArrayTool {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Array"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _commands: &mut Commands, world: &mut World) {
+        let Some(source) = array_source(world) else {
+            ui.label("Select one entity to make array");
+            return;
+        };
+
+        let Some(modifier) = world.get::<ArrayModifier>(source).cloned() else {
+            if ui.button("Create array").clicked() {
+                world.entity_mut(source).insert(ArrayModifier::default());
+            }
+            return;
+        };
+
+        let mut edited = modifier.clone();
+        egui::Grid::new("array_tool").num_columns(2).show(ui, |ui| {
+            ui.label("Shape");
+            egui::ComboBox::from_id_source("array_shape")
+                .selected_text(shape_name(&edited.shape))
+                .show_ui(ui, |ui| {
+                    for shape in [
+                        ArrayShape::Linear,
+                        ArrayShape::Grid {
+                            counts: UVec3::new(3, 1, 3),
+                        },
+                        ArrayShape::Radial {
+                            center: Vec3::new(0., 0., -2.),
+                            axis: Vec3::Y,
+                            angle: 360.,
+                        },
+                        ArrayShape::Path {
+                            points: vec![],
+                            align: true,
+                        },
+                    ] {
+                        let checked = shape_name(&edited.shape) == shape_name(&shape);
+                        if ui.selectable_label(checked, shape_name(&shape)).clicked() && !checked {
+                            edited.shape = shape;
+                        }
+                    }
+                });
+            ui.end_row();
+
+            match &mut edited.shape {
+                ArrayShape::Grid { counts } => {
+                    ui.label("Counts");
+                    ui.horizontal(|ui| {
+                        for count in [&mut counts.x, &mut counts.y, &mut counts.z] {
+                            ui.add(egui::DragValue::new(count).clamp_range(1..=100));
+                        }
+                    });
+                    ui.end_row();
+                }
+                shape => {
+                    ui.label("Count");
+                    ui.add(egui::DragValue::new(&mut edited.count).clamp_range(1..=1000));
+                    ui.end_row();
+
+                    match shape {
+                        ArrayShape::Radial {
+                            center,
+                            axis,
+                            angle,
+                        } => {
+                            ui.label("Center");
+                            vec3_edit(ui, center, 0.1);
+                            ui.end_row();
+                            ui.label("Axis");
+                            vec3_edit(ui, axis, 0.05);
+                            ui.end_row();
+                            ui.label("Angle");
+                            ui.add(egui::DragValue::new(angle).suffix("°"));
+                            ui.end_row();
+                        }
+                        ArrayShape::Path { points, align } => {
+                            let mut removed = None;
+                            for (idx, point) in points.iter_mut().enumerate() {
+                                ui.label(format!("Point {}", idx + 1));
+                                ui.horizontal(|ui| {
+                                    vec3_edit(ui, point, 0.1);
+                                    if ui.button("🗑").clicked() {
+                                        removed = Some(idx);
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                            if let Some(idx) = removed {
+                                points.remove(idx);
+                            }
+                            ui.label("");
+                            if ui
+                                .button("➕ Point at cursor")
+                                .on_hover_text("Add path point at 3D cursor")
+                                .clicked()
+                            {
+                                let origin = world
+                                    .get::<GlobalTransform>(source)
+                                    .map_or(Vec3::ZERO, |global| global.translation());
+                                points.push(world.resource::<Cursor3d>().position - origin);
+                            }
+                            ui.end_row();
+                            ui.label("Align");
+                            ui.checkbox(align, "Follow path direction");
+                            ui.end_row();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            ui.label("Offset");
+            vec3_edit(ui, &mut edited.offset, 0.1);
+            ui.end_row();
+            ui.label("Rotation");
+            vec3_edit(ui, &mut edited.rotation, 1.);
+            ui.end_row();
+            ui.label("Scale");
+            vec3_edit(ui, &mut edited.scale, 0.01);
+            ui.end_row();
+        });
+
+        if edited != modifier {
+            world.entity_mut(source).insert(edited);
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .button("Apply")
+                .on_hover_text("Keep copies as usual entities")
+                .clicked()
+            {
+                apply_array(world, source);
+            }
+            if ui
+                .button("Refresh")
+                .on_hover_text("Recreate copies from current entity state")
+                .clicked()
+            {
+                let copies = array_copies(world, source);
+                despawn_copies(world, copies);
+                regenerate_array(world, source);
+            }
+            if ui.button("Remove").clicked() {
+                world.entity_mut(source).remove::<ArrayModifier>();
+            }
+        });
+    }
+}
+
+const fn shape_name(shape: &ArrayShape) -> &'static str {
+    match shape {
+        ArrayShape::Linear => "Linear",
+        ArrayShape::Grid { .. } => "Grid",
+        ArrayShape::Radial { .. } => "Radial",
+        ArrayShape::Path { .. } => "Path",
+    }
+}
+
+fn vec3_edit(ui: &mut egui::Ui, value: &mut Vec3, speed: f32) {
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::DragValue::new(&mut value.x)
+                .speed(speed)
+                .prefix("x: "),
+        );
+        ui.add(
+            egui::DragValue::new(&mut value.y)
+                .speed(speed)
+                .prefix("y: "),
+        );
+        ui.add(
+            egui::DragValue::new(&mut value.z)
+                .speed(speed)
+                .prefix("z: "),
+        );
+    });
+}
+
+/// Single selected prefab entity. Selected copy is resolved to its source
+fn array_source(world: &mut World) -> Option<Entity> {
+    let mut selected = world.query_filtered::<Entity, (With<Selected>, With<PrefabMarker>)>();
+    let entity = selected.get_single(world).ok()?;
+    Some(
+        world
+            .get::<ArrayCopy>(entity)
+            .map_or(entity, |copy| copy.source),
+    )
+}
+
+/// Copies of source sorted by index
+fn array_copies(world: &mut World, source: Entity) -> Vec<Entity> {
+    let mut query = world.query::<(Entity, &ArrayCopy)>();
+    let mut copies = query
+        .iter(world)
+        .filter(|(_, copy)| copy.source == source)
+        .map(|(entity, copy)| (copy.index, entity))
+        .collect::<Vec<_>>();
+    copies.sort();
+    copies.into_iter().map(|(_, entity)| entity).collect()
+}
+
+/// Despawn copies without undo records
+fn despawn_copies(world: &mut World, copies: Vec<Entity>) {
+    for entity in with_descendants(world, copies.iter().copied()) {
+        world
+            .resource_mut::<UndoIngnoreStorage>()
+            .storage
+            .insert(entity, OneFrameUndoIgnore::default());
+    }
+    for entity in copies {
+        if let Some(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+}
+
+fn detect_array_changes(
+    mut commands: Commands,
+    changed: Query<
+        Entity,
+        (
+            With<ArrayModifier>,
+            Or<(Changed<ArrayModifier>, Changed<Transform>, Changed<Parent>)>,
+        ),
+    >,
+    mut removed: RemovedComponents<ArrayModifier>,
+) {
+    for entity in changed.iter().chain(removed.read()) {
+        commands.add(move |world: &mut World| regenerate_array(world, entity));
+    }
+}
+
+/// Sync copies with array modifier of source. Copies are only moved if their count and parent are still valid,
+/// otherwise they are recreated. Regeneration is not recorded in undo: it is reproduced from modifier changes
+fn regenerate_array(world: &mut World, source: Entity) {
+    let copies = array_copies(world, source);
+    let Some(modifier) = world
+        .get_entity(source)
+        .and_then(|entity| entity.get::<ArrayModifier>())
+        .cloned()
+    else {
+        despawn_copies(world, copies);
+        return;
+    };
+
+    let transform = world.get::<Transform>(source).copied().unwrap_or_default();
+    let transforms = modifier.transforms(&transform);
+    let parent = world.get::<Parent>(source).map(|parent| parent.get());
+
+    let valid = copies.len() == transforms.len()
+        && copies.iter().enumerate().all(|(idx, copy)| {
+            world.get::<Parent>(*copy).map(|parent| parent.get()) == parent
+                && world.get::<ArrayCopy>(*copy).map(|copy| copy.index) == Some(idx)
+        });
+
+    if valid {
+        for (copy, transform) in copies.into_iter().zip(transforms) {
+            if world.get::<Transform>(copy) != Some(&transform) {
+                world
+                    .entity_mut(copy)
+                    .insert((transform, OneFrameUndoIgnore::default()));
+            }
+        }
+        return;
+    }
+
+    despawn_copies(world, copies);
+    for (index, transform) in transforms.into_iter().enumerate() {
+        let mut queue = CommandQueue::default();
+        let spawned = {
+            let mut commands = Commands::new(&mut queue, world);
+            clone_prefab_entity(
+                &mut commands,
+                world.resource::<EditorRegistry>(),
+                |e| world.get_entity(e),
+                source,
+            )
+        };
+        queue.apply(world);
+
+        let Some(&root) = spawned.first() else {
+            return;
+        };
+        for entity in spawned.iter() {
+            world
+                .entity_mut(*entity)
+                .insert(OneFrameUndoIgnore::default());
+        }
+        world
+            .entity_mut(root)
+            .remove::<(ArrayModifier, Selected)>()
+            .insert((ArrayCopy { source, index }, transform));
+    }
+}
+
+/// Turn copies into usual entities and remove modifier. Recorded as one undo step
+fn apply_array(world: &mut World, source: Entity) {
+    let copies = array_copies(world, source);
+    for copy in copies.iter() {
+        world.entity_mut(*copy).remove::<ArrayCopy>();
+        world.send_event(NewChange {
+            change: Arc::new(AddedEntity { entity: *copy }),
+        });
+    }
+    world.entity_mut(source).remove::<ArrayModifier>();
+}
@@ -1,27 +1,56 @@
 use std::any::Any;
 
-use bevy::{prelude::*, render::camera::CameraProjection};
+use bevy::{
+    prelude::*,
+    render::{camera::CameraProjection, primitives::Aabb},
+    utils::HashSet,
+};
 use bevy_egui_next::egui::{self, Key};
 use egui_gizmo::*;
 use space_editor_core::prelude::*;
+use space_prefab::component::{is_locked, scene_layers};
 use space_shared::*;
 
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
 use crate::{
+    camera_plugin::EditorCameraView,
     colors::SELECTED_ITEM_COLOR,
-    game_view::GameViewTab,
+    game_view::{pointer_ray, GameViewTab},
     icons::{rotation_icon, scale_icon, translate_icon},
     prelude::{CloneEvent, EditorTool},
     sizing::Sizing,
     tool::ToolExt,
+    viewports::ActiveEditorCamera,
+};
+
+use super::marquee::MarqueeSelection;
+use super::placement::{
+    cast_surface_ray_world, place_on_surface, with_descendants, MeshSnapGeometry, SurfacePlacement,
+    SurfacePlacementPlugin,
 };
 pub struct GizmoToolPlugin;
 
 impl Plugin for GizmoToolPlugin {
     fn build(&self, app: &mut App) {
         app.editor_tool(GizmoTool::default());
+        app.add_plugins(SurfacePlacementPlugin);
 
         app.world.resource_mut::<GameViewTab>().active_tool = Some(0);
         app.init_resource::<MultipleCenter>();
+        app.init_resource::<Cursor3d>();
+        app.init_resource::<GizmoSnapping>()
+            .register_type::<GizmoSnapping>();
+        app.init_resource::<GizmoTransformSettings>()
+            .register_type::<TransformOrientation>()
+            .register_type::<PivotMode>()
+            .register_type::<GizmoTransformSettings>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<GizmoSnapping>();
+            app.persistence_resource::<GizmoTransformSettings>();
+        }
 
         app.editor_hotkey(GizmoHotkey::Translate, vec![KeyCode::G]);
         app.editor_hotkey(GizmoHotkey::Rotate, vec![KeyCode::R]);
@@ -29,6 +58,11 @@ impl Plugin for GizmoToolPlugin {
         app.editor_hotkey(GizmoHotkey::Delete, vec![KeyCode::X]);
         app.editor_hotkey(GizmoHotkey::Multiple, vec![KeyCode::ShiftLeft]);
         app.editor_hotkey(GizmoHotkey::Clone, vec![KeyCode::AltLeft]);
+        app.editor_hotkey(GizmoHotkey::Snap, vec![KeyCode::C]);
+        app.editor_hotkey(GizmoHotkey::DropToGround, vec![KeyCode::End]);
+        app.editor_hotkey(GizmoHotkey::Place, vec![KeyCode::V]);
+        app.editor_hotkey(GizmoHotkey::VertexSnap, vec![KeyCode::B]);
+        app.editor_hotkey(GizmoHotkey::EdgeSnap, vec![KeyCode::N]);
 
         app.add_systems(Update, draw_lines_system.in_set(EditorSet::Editor));
     }
@@ -42,6 +76,11 @@ pub enum GizmoHotkey {
     Delete,
     Multiple,
     Clone,
+    Snap,
+    DropToGround,
+    Place,
+    VertexSnap,
+    EdgeSnap,
 }
 
 impl Hotkey for GizmoHotkey {
@@ -53,13 +92,96 @@ impl Hotkey for GizmoHotkey {
             Self::Delete => "Delete entity".to_string(),
             Self::Multiple => "Change multiple entities".to_string(),
             Self::Clone => "Clone entity".to_string(),
+            Self::Snap => "Toggle snapping".to_string(),
+            Self::DropToGround => "Drop entity to ground".to_string(),
+            Self::Place => "Place entity on surface".to_string(),
+            Self::VertexSnap => "Snap vertex to vertex".to_string(),
+            Self::EdgeSnap => "Snap vertex to edge".to_string(),
+        }
+    }
+}
+
+/// Axes of gizmo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum TransformOrientation {
+    #[default]
+    World,
+    Local,
+    Parent,
+    View,
+}
+
+impl TransformOrientation {
+    pub const ALL: [Self; 4] = [Self::World, Self::Local, Self::Parent, Self::View];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::World => "World",
+            Self::Local => "Local",
+            Self::Parent => "Parent",
+            Self::View => "View",
+        }
+    }
+
+    /// Rotation of gizmo axes for entity (active entity for multiple selection)
+    fn rotation(&self, target: Option<&GizmoTarget>, camera: &GlobalTransform) -> Quat {
+        match self {
+            Self::World => Quat::IDENTITY,
+            Self::Local => target.map_or(Quat::IDENTITY, |target| {
+                target.global.to_scale_rotation_translation().1
+            }),
+            Self::Parent => target
+                .and_then(|target| target.parent_global)
+                .map_or(Quat::IDENTITY, |parent| {
+                    parent.to_scale_rotation_translation().1
+                }),
+            Self::View => camera.to_scale_rotation_translation().1,
+        }
+    }
+}
+
+/// Point around which multiple selected entities are rotated and scaled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum PivotMode {
+    #[default]
+    Median,
+    ActiveEntity,
+    IndividualOrigins,
+    Cursor,
+}
+
+impl PivotMode {
+    pub const ALL: [Self; 4] = [
+        Self::Median,
+        Self::ActiveEntity,
+        Self::IndividualOrigins,
+        Self::Cursor,
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Median => "Median",
+            Self::ActiveEntity => "Active entity",
+            Self::IndividualOrigins => "Individual origins",
+            Self::Cursor => "3D cursor",
         }
     }
 }
 
+/// Orientation and pivot of gizmo tool
+#[derive(Resource, Reflect, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct GizmoTransformSettings {
+    pub orientation: TransformOrientation,
+    pub pivot: PivotMode,
+}
+
 pub struct GizmoTool {
     pub gizmo_mode: GizmoMode,
     pub is_move_cloned_entities: bool,
+    /// Picked vertex of selection in space of anchor entity, while vertex snap hotkey is held
+    pub vertex_snap_source: Option<(Entity, Vec3)>,
+    pub marquee: MarqueeSelection,
 }
 
 impl Default for GizmoTool {
@@ -67,6 +189,8 @@ impl Default for GizmoTool {
         Self {
             gizmo_mode: GizmoMode::Translate,
             is_move_cloned_entities: false,
+            vertex_snap_source: None,
+            marquee: MarqueeSelection::default(),
         }
     }
 }
@@ -83,9 +207,17 @@ impl EditorTool for GizmoTool {
     fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
         // GIZMO DRAW
         // Draw gizmo per entity to individual move
-        // If SHIFT pressed draw "mean" gizmo to move all selected entities together
+        // If SHIFT pressed draw gizmo at pivot to move all selected entities together
+        // Gizmo axes follow selected orientation (world, local, parent or view)
         // If ALT pressed, then entity will be cloned at interact
         // If SHIFT+ALT pressed, then all selected entities will be cloned at interact
+        // If C pressed, then snapping will be toggled while it is held (Ctrl is kept for selection modifiers)
+        // If V pressed, then selected entities follow surface under pointer
+        // If END pressed, then selected entities will be dropped to ground
+        // If B pressed, then vertex of selection under pointer snaps to nearest vertex of mesh under pointer
+        // If N pressed, then vertex of selection under pointer snaps to nearest edge of mesh under pointer
+        // Shift + right click in viewport places 3D cursor, if it is used as pivot
+        // Left mouse drag makes box or lasso selection, if enabled in toolbar
         // All hotkeys can be changes in editor ui
 
         let mode2name = vec![
@@ -93,6 +225,11 @@ impl EditorTool for GizmoTool {
             (GizmoMode::Rotate, "Rotate"),
             (GizmoMode::Scale, "Scale"),
         ];
+        let mut snapping = world.resource::<GizmoSnapping>().clone();
+        let mut settings = *world.resource::<GizmoTransformSettings>();
+        let mut placement = world.resource::<SurfacePlacement>().clone();
+        let mut drop_to_ground = false;
+        let mut cursor_action = None;
         let sizing = world.resource::<Sizing>();
 
         ui.spacing();
@@ -115,8 +252,144 @@ impl EditorTool for GizmoTool {
                     self.gizmo_mode = mode;
                 }
             }
+
+            ui.add_space(4.);
+            let snap_button = egui::Button::new("🧲");
+            let snap_button = if snapping.enabled {
+                snap_button.fill(SELECTED_ITEM_COLOR)
+            } else {
+                snap_button
+            };
+            if ui
+                .add(snap_button)
+                .on_hover_text("Snapping (hold Snap hotkey to toggle)")
+                .clicked()
+            {
+                snapping.enabled = !snapping.enabled;
+            }
+            if snapping.enabled {
+                match self.gizmo_mode {
+                    GizmoMode::Translate => ui.add(
+                        egui::DragValue::new(&mut snapping.translate)
+                            .clamp_range(0.001..=f32::MAX)
+                            .speed(0.1)
+                            .suffix(" m"),
+                    ),
+                    GizmoMode::Rotate => ui.add(
+                        egui::DragValue::new(&mut snapping.angle)
+                            .clamp_range(0.1..=180.)
+                            .suffix("°"),
+                    ),
+                    GizmoMode::Scale => ui.add(
+                        egui::DragValue::new(&mut snapping.scale)
+                            .clamp_range(0.001..=f32::MAX)
+                            .speed(0.01),
+                    ),
+                };
+            }
+
+            ui.add_space(4.);
+            egui::ComboBox::from_id_source("gizmo_orientation")
+                .selected_text(settings.orientation.name())
+                .show_ui(ui, |ui| {
+                    for orientation in TransformOrientation::ALL {
+                        ui.selectable_value(
+                            &mut settings.orientation,
+                            orientation,
+                            orientation.name(),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text("Orientation");
+            egui::ComboBox::from_id_source("gizmo_pivot")
+                .selected_text(settings.pivot.name())
+                .show_ui(ui, |ui| {
+                    for pivot in PivotMode::ALL {
+                        ui.selectable_value(&mut settings.pivot, pivot, pivot.name());
+                    }
+                })
+                .response
+                .on_hover_text("Pivot of multiple selected entities");
+            if settings.pivot == PivotMode::Cursor {
+                ui.menu_button("⌖", |ui| {
+                    if ui.button("Cursor to selected").clicked() {
+                        cursor_action = Some(CursorAction::ToSelected);
+                        ui.close_menu();
+                    }
+                    if ui.button("Cursor to world origin").clicked() {
+                        cursor_action = Some(CursorAction::ToOrigin);
+                        ui.close_menu();
+                    }
+                })
+                .response
+                .on_hover_text("3D cursor (Shift + right click in viewport to place it)");
+            }
+
+            ui.add_space(4.);
+            ui.menu_button("⤓", |ui| {
+                if ui.button("Drop to ground").clicked() {
+                    drop_to_ground = true;
+                    ui.close_menu();
+                }
+                placement.ui(ui);
+            })
+            .response
+            .on_hover_text("Surface placement");
+
+            ui.add_space(4.);
+            self.marquee.toolbar(ui);
         });
 
+        let cursor_visible = settings.pivot == PivotMode::Cursor;
+        if world.resource::<Cursor3d>().visible != cursor_visible {
+            world.resource_mut::<Cursor3d>().visible = cursor_visible;
+        }
+
+        match cursor_action {
+            Some(CursorAction::ToSelected) => {
+                let mut query = world.query_filtered::<&GlobalTransform, With<Selected>>();
+                let positions = query
+                    .iter(world)
+                    .map(|global| global.translation())
+                    .collect::<Vec<_>>();
+                if !positions.is_empty() {
+                    world.resource_mut::<Cursor3d>().position =
+                        positions.iter().sum::<Vec3>() / positions.len() as f32;
+                }
+            }
+            Some(CursorAction::ToOrigin) => {
+                world.resource_mut::<Cursor3d>().position = Vec3::ZERO;
+            }
+            None => {}
+        }
+
+        // Shift + right click places cursor on surface under pointer or on ground plane
+        if cursor_visible && ui.input(|i| i.pointer.secondary_clicked() && i.modifiers.shift) {
+            let position = pointer_ray(ui, world).and_then(|ray| {
+                cast_surface_ray_world(world, ray, &HashSet::new())
+                    .map(|hit| hit.position)
+                    .or_else(|| {
+                        ray.intersect_plane(Vec3::ZERO, Vec3::Y)
+                            .map(|distance| ray.get_point(distance))
+                    })
+            });
+            if let Some(position) = position {
+                world.resource_mut::<Cursor3d>().position = position;
+            }
+        }
+
+        if *world.resource::<GizmoTransformSettings>() != settings {
+            world.insert_resource(settings);
+        }
+
+        if *world.resource::<GizmoSnapping>() != snapping {
+            world.insert_resource(snapping.clone());
+        }
+        if *world.resource::<SurfacePlacement>() != placement {
+            world.insert_resource(placement.clone());
+        }
+
         let input = world.resource::<Input<GizmoHotkey>>();
 
         let mut del = false;
@@ -130,8 +403,10 @@ impl EditorTool for GizmoTool {
             (GizmoMode::Scale, GizmoHotkey::Scale),
         ];
 
+        // fly camera uses same keys for movement
+        let flying = world.resource::<EditorCameraView>().fly;
         for (mode, key) in mode2key {
-            if input.just_pressed(key) {
+            if !flying && input.just_pressed(key) {
                 self.gizmo_mode = mode;
             }
         }
@@ -150,240 +425,403 @@ impl EditorTool for GizmoTool {
             multiple_pressed = true;
         }
 
+        if input.just_pressed(GizmoHotkey::DropToGround) {
+            drop_to_ground = true;
+        }
+        let place_pressed = input.pressed(GizmoHotkey::Place);
+        let edge_snap_pressed = input.pressed(GizmoHotkey::EdgeSnap);
+        let vertex_snap_pressed = input.pressed(GizmoHotkey::VertexSnap) || edge_snap_pressed;
+
+        let gizmo_mode = self.gizmo_mode;
+        let snap_active = snapping.enabled != input.pressed(GizmoHotkey::Snap);
+        let snap = |old: Vec3, mut transform: Transform| {
+            if snap_active && gizmo_mode == GizmoMode::Translate {
+                transform.translation = snapping.snap_translation(old, transform.translation);
+            }
+            transform
+        };
+
+        let layers = scene_layers(world);
         if del {
             let mut query = world.query_filtered::<Entity, With<Selected>>();
             for e in query.iter(world) {
-                commands.entity(e).despawn_recursive();
+                if !is_locked(world, &layers, e) {
+                    commands.entity(e).despawn_recursive();
+                }
             }
             return;
         }
 
         let (cam_transform, cam_proj) = {
             let mut cam_query =
-                world.query_filtered::<(&GlobalTransform, &Projection), With<EditorCameraMarker>>();
+                world.query_filtered::<(&GlobalTransform, &Projection), With<ActiveEditorCamera>>();
             let Ok((ref_tr, ref_cam)) = cam_query.get_single(world) else {
                 return;
             };
             (*ref_tr, ref_cam.clone())
         };
 
-        let selected = world
-            .query_filtered::<Entity, With<Selected>>()
-            .iter(world)
-            .collect::<Vec<_>>();
-        let mut disable_pan_orbit = false;
-        let _gizmo_mode = GizmoMode::Translate;
-
-        let cell = world.as_unsafe_world_cell();
+        let targets = {
+            let mut query = world.query_filtered::<
+                (Entity, &GlobalTransform, Option<&Parent>),
+                (With<Selected>, With<Transform>),
+            >();
+            // locked entities are not moved
+            query
+                .iter(world)
+                .filter(|(entity, ..)| !is_locked(world, &layers, *entity))
+                .map(|(entity, global, parent)| GizmoTarget {
+                    entity,
+                    global: *global,
+                    parent_global: parent
+                        .and_then(|parent| world.get::<GlobalTransform>(parent.get()))
+                        .copied(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let active = world.resource::<ActiveSelected>().0;
+
+        // Surface placement. Transform changes are merged by undo into single change
+        if (drop_to_ground || place_pressed) && !targets.is_empty() {
+            let exclude = with_descendants(world, targets.iter().map(|target| target.entity));
+            if drop_to_ground {
+                for target in &targets {
+                    let ray = Ray {
+                        origin: target.global.translation(),
+                        direction: Vec3::NEG_Y,
+                    };
+                    if let Some(hit) = cast_surface_ray_world(world, ray, &exclude) {
+                        let aabb = world.get::<Aabb>(target.entity).copied();
+                        let placed = place_on_surface(
+                            &target.global,
+                            aabb.as_ref(),
+                            hit,
+                            placement.align_to_normal,
+                        );
+                        set_global_transform(world, target, placed.into());
+                    }
+                }
+            } else {
+                // anchor entity follows pointer, other selected entities keep offsets to it
+                let anchor = active
+                    .and_then(|active| targets.iter().find(|target| target.entity == active))
+                    .or(targets.first());
+                if let (Some(anchor), Some(ray)) = (anchor, pointer_ray(ui, world)) {
+                    if let Some(hit) = cast_surface_ray_world(world, ray, &exclude) {
+                        let aabb = world.get::<Aabb>(anchor.entity).copied();
+                        let frame = anchor.global.compute_transform();
+                        let new_frame = place_on_surface(
+                            &anchor.global,
+                            aabb.as_ref(),
+                            hit,
+                            placement.align_to_normal,
+                        );
+                        for target in &targets {
+                            set_global_transform(
+                                world,
+                                target,
+                                apply_frame_change(&frame, &new_frame, &target.global),
+                            );
+                        }
+                    }
+                }
+            }
+            return;
+        }
 
-        let view_matrix = Mat4::from(cam_transform.affine().inverse());
-        if multiple_pressed {
-            let mut mean_transform = Transform::IDENTITY;
-            for e in &selected {
-                let Some(ecell) = cell.get_entity(*e) else {
-                    continue;
-                };
-                let Some(global_transform) = (unsafe { ecell.get::<GlobalTransform>() }) else {
-                    continue;
+        if !vertex_snap_pressed || targets.is_empty() {
+            self.vertex_snap_source = None;
+        } else {
+            let selection = with_descendants(world, targets.iter().map(|target| target.entity));
+            let Some(ray) = pointer_ray(ui, world) else {
+                return;
+            };
+            // source vertex is picked once at press and moves together with selection
+            if self.vertex_snap_source.is_none() {
+                let anchor = active
+                    .and_then(|active| targets.iter().find(|target| target.entity == active))
+                    .or(targets.first());
+                self.vertex_snap_source = anchor
+                    .zip(MeshSnapGeometry::collect(world, &selection).nearest_vertex_to_ray(ray))
+                    .map(|(anchor, vertex)| {
+                        (
+                            anchor.entity,
+                            anchor.global.affine().inverse().transform_point3(vertex),
+                        )
+                    });
+            }
+            let source = self.vertex_snap_source.and_then(|(entity, local)| {
+                targets
+                    .iter()
+                    .find(|target| target.entity == entity)
+                    .map(|anchor| anchor.global.transform_point(local))
+            });
+
+            if let (Some(source), Some(hit)) =
+                (source, cast_surface_ray_world(world, ray, &selection))
+            {
+                let geometry = MeshSnapGeometry::collect(world, &HashSet::from([hit.entity]));
+                let snapped = if edge_snap_pressed {
+                    geometry.nearest_edge_point(hit.position)
+                } else {
+                    geometry.nearest_vertex(hit.position)
                 };
-                let tr = global_transform.compute_transform();
-                mean_transform.translation += tr.translation;
-                mean_transform.scale += tr.scale;
+                if let Some(snapped) = snapped {
+                    let offset = GlobalTransform::from_translation(snapped - source);
+                    for target in &targets {
+                        set_global_transform(world, target, offset * target.global);
+                    }
+                }
             }
-            mean_transform.translation /= selected.len() as f32;
-            mean_transform.scale /= selected.len() as f32;
+            return;
+        }
 
-            let mut global_mean = GlobalTransform::from(mean_transform);
+        let cursor = world.resource::<Cursor3d>().position;
+        let mut disable_pan_orbit = false;
 
-            //Set resource for draw lines from mean center to each entity
-            unsafe {
-                cell.world_mut().insert_resource(MultipleCenter {
-                    center: Some(global_mean.translation()),
-                });
-            }
+        let view_matrix = Mat4::from(cam_transform.affine().inverse());
+        let gizmo = |id: String, frame: &Transform| {
+            snapping.configure(
+                Gizmo::new(id)
+                    .projection_matrix(cam_proj.get_projection_matrix().to_cols_array_2d().into())
+                    .view_matrix(view_matrix.to_cols_array_2d().into())
+                    .model_matrix(frame.compute_matrix().to_cols_array_2d().into())
+                    .mode(gizmo_mode)
+                    .orientation(GizmoOrientation::Local),
+                snap_active,
+            )
+        };
 
-            let mut loc_transform = vec![];
-            for e in &selected {
-                let Some(ecell) = cell.get_entity(*e) else {
-                    continue;
-                };
-                let Some(global_transform) = (unsafe { ecell.get::<GlobalTransform>() }) else {
-                    continue;
-                };
-                loc_transform.push(global_transform.reparented_to(&global_mean));
+        if multiple_pressed && !targets.is_empty() {
+            let active_target = active
+                .and_then(|active| targets.iter().find(|target| target.entity == active))
+                .or(targets.first());
+
+            let mut median = Vec3::ZERO;
+            let mut mean_scale = Vec3::ZERO;
+            for target in &targets {
+                let (scale, _, translation) = target.global.to_scale_rotation_translation();
+                median += translation;
+                mean_scale += scale;
             }
+            median /= targets.len() as f32;
+            mean_scale /= targets.len() as f32;
+
+            let frame = Transform {
+                translation: match settings.pivot {
+                    PivotMode::Median | PivotMode::IndividualOrigins => median,
+                    PivotMode::ActiveEntity => {
+                        active_target.map_or(median, |target| target.global.translation())
+                    }
+                    PivotMode::Cursor => cursor,
+                },
+                rotation: settings.orientation.rotation(active_target, &cam_transform),
+                scale: mean_scale,
+            };
 
-            let mut gizmo_interacted = false;
+            //Set resource for draw lines from pivot to each entity
+            world.insert_resource(MultipleCenter {
+                center: Some(frame.translation),
+            });
 
-            if let Some(result) = egui_gizmo::Gizmo::new("Selected gizmo mean global".to_string())
-                .projection_matrix(cam_proj.get_projection_matrix().to_cols_array_2d().into())
-                .view_matrix(view_matrix.to_cols_array_2d().into())
-                .model_matrix(mean_transform.compute_matrix().to_cols_array_2d().into())
-                .mode(self.gizmo_mode)
-                .interact(ui)
+            if let Some(result) =
+                gizmo("Selected gizmo mean global".to_string(), &frame).interact(ui)
             {
-                gizmo_interacted = true;
-                mean_transform = Transform {
-                    translation: Vec3::from(<[f32; 3]>::from(result.translation)),
-                    rotation: Quat::from_array(<[f32; 4]>::from(result.rotation)),
-                    scale: Vec3::from(<[f32; 3]>::from(result.scale)),
-                };
                 disable_pan_orbit = true;
-            }
-
-            global_mean = GlobalTransform::from(mean_transform);
-
-            if gizmo_interacted && clone_pressed {
-                if self.is_move_cloned_entities {
-                } else {
-                    for e in selected.iter() {
-                        unsafe { cell.world_mut().send_event(CloneEvent { id: *e }) };
+                if clone_pressed && !self.is_move_cloned_entities {
+                    for target in &targets {
+                        world.send_event(CloneEvent { id: target.entity });
                     }
                     self.is_move_cloned_entities = true;
-                    return;
-                }
-            }
-
-            if gizmo_interacted {
-                for (idx, e) in selected.iter().enumerate() {
-                    let Some(ecell) = cell.get_entity(*e) else {
-                        continue;
-                    };
-                    let Some(mut transform) = (unsafe { ecell.get_mut::<Transform>() }) else {
-                        continue;
-                    };
-
-                    let new_global = global_mean.mul_transform(loc_transform[idx]);
-
-                    if let Some(parent) = unsafe { ecell.get::<Parent>() } {
-                        if let Some(parent) = cell.get_entity(parent.get()) {
-                            if let Some(parent_global) = unsafe { parent.get::<GlobalTransform>() }
-                            {
-                                *transform = new_global.reparented_to(parent_global);
-                            }
-                        }
-                    } else {
-                        *transform = new_global.compute_transform();
+                } else {
+                    let new_frame = snap(frame.translation, result_transform(&result));
+                    for target in &targets {
+                        let new_global = if settings.pivot == PivotMode::IndividualOrigins {
+                            // each entity rotates and scales around own origin
+                            let origin = Transform {
+                                translation: target.global.translation(),
+                                ..frame
+                            };
+                            let new_origin = Transform {
+                                translation: origin.translation + new_frame.translation
+                                    - frame.translation,
+                                ..new_frame
+                            };
+                            apply_frame_change(&origin, &new_origin, &target.global)
+                        } else {
+                            apply_frame_change(&frame, &new_frame, &target.global)
+                        };
+                        set_global_transform(world, target, new_global);
                     }
                 }
             }
         } else {
-            unsafe {
-                cell.world_mut()
-                    .insert_resource(MultipleCenter { center: None });
-            }
-
-            for e in &selected {
-                let Some(ecell) = cell.get_entity(*e) else {
-                    continue;
-                };
-                let Some(mut transform) = (unsafe { ecell.get_mut::<Transform>() }) else {
-                    continue;
+            world.insert_resource(MultipleCenter { center: None });
+
+            for target in &targets {
+                let (scale, _, translation) = target.global.to_scale_rotation_translation();
+                let frame = Transform {
+                    translation,
+                    rotation: settings.orientation.rotation(Some(target), &cam_transform),
+                    scale,
                 };
-                if let Some(parent) = unsafe { ecell.get::<Parent>() } {
-                    if let Some(parent) = cell.get_entity(parent.get()) {
-                        if let Some(parent_global) = unsafe { parent.get::<GlobalTransform>() } {
-                            if let Some(global) = unsafe { ecell.get::<GlobalTransform>() } {
-                                if let Some(result) =
-                                    egui_gizmo::Gizmo::new(format!("Selected gizmo {:?}", *e))
-                                        .projection_matrix(
-                                            cam_proj
-                                                .get_projection_matrix()
-                                                .to_cols_array_2d()
-                                                .into(),
-                                        )
-                                        .view_matrix(view_matrix.to_cols_array_2d().into())
-                                        .model_matrix(
-                                            global.compute_matrix().to_cols_array_2d().into(),
-                                        )
-                                        .mode(self.gizmo_mode)
-                                        .interact(ui)
-                                {
-                                    disable_pan_orbit = true;
-                                    let new_transform = Transform {
-                                        translation: Vec3::from(<[f32; 3]>::from(
-                                            result.translation,
-                                        )),
-                                        rotation: Quat::from_array(<[f32; 4]>::from(
-                                            result.rotation,
-                                        )),
-                                        scale: Vec3::from(<[f32; 3]>::from(result.scale)),
-                                    };
-
-                                    if clone_pressed {
-                                        if self.is_move_cloned_entities {
-                                            let new_transform =
-                                                GlobalTransform::from(new_transform);
-                                            *transform = new_transform.reparented_to(parent_global);
-                                            transform.set_changed();
-                                            disable_pan_orbit = true;
-                                        } else {
-                                            unsafe {
-                                                cell.world_mut().send_event(CloneEvent { id: *e })
-                                            };
-                                            self.is_move_cloned_entities = true;
-                                        }
-                                    } else {
-                                        let new_transform = GlobalTransform::from(new_transform);
-                                        *transform = new_transform.reparented_to(parent_global);
-                                        transform.set_changed();
-                                    }
-                                }
-                                continue;
-                            }
-                        }
-                    }
-                }
-                if let Some(result) = egui_gizmo::Gizmo::new(format!("Selected gizmo {:?}", *e))
-                    .projection_matrix(cam_proj.get_projection_matrix().to_cols_array_2d().into())
-                    .view_matrix(view_matrix.to_cols_array_2d().into())
-                    .model_matrix(transform.compute_matrix().to_cols_array_2d().into())
-                    .mode(self.gizmo_mode)
-                    .interact(ui)
+                if let Some(result) =
+                    gizmo(format!("Selected gizmo {:?}", target.entity), &frame).interact(ui)
                 {
-                    if clone_pressed {
-                        if self.is_move_cloned_entities {
-                            *transform = Transform {
-                                translation: Vec3::from(<[f32; 3]>::from(result.translation)),
-                                rotation: Quat::from_array(<[f32; 4]>::from(result.rotation)),
-                                scale: Vec3::from(<[f32; 3]>::from(result.scale)),
-                            };
-                            transform.set_changed();
-                        } else {
-                            unsafe { cell.world_mut().send_event(CloneEvent { id: *e }) };
-                            self.is_move_cloned_entities = true;
-                        }
+                    disable_pan_orbit = true;
+                    if clone_pressed && !self.is_move_cloned_entities {
+                        world.send_event(CloneEvent { id: target.entity });
+                        self.is_move_cloned_entities = true;
                     } else {
-                        *transform = Transform {
-                            translation: Vec3::from(<[f32; 3]>::from(result.translation)),
-                            rotation: Quat::from_array(<[f32; 4]>::from(result.rotation)),
-                            scale: Vec3::from(<[f32; 3]>::from(result.scale)),
-                        };
-                        transform.set_changed();
+                        let new_frame = snap(frame.translation, result_transform(&result));
+                        set_global_transform(
+                            world,
+                            target,
+                            apply_frame_change(&frame, &new_frame, &target.global),
+                        );
                     }
-                    disable_pan_orbit = true;
                 }
             }
         }
+
+        if disable_pan_orbit {
+            self.marquee.cancel();
+        } else if self.marquee.interact(ui, commands, world) {
+            disable_pan_orbit = true;
+        }
+
         if ui.ctx().wants_pointer_input() {
             disable_pan_orbit = true;
         }
 
         if disable_pan_orbit {
-            unsafe {
-                cell.get_resource_mut::<crate::EditorCameraEnabled>()
-                    .unwrap()
-                    .0 = false
-            };
+            world.resource_mut::<crate::EditorCameraEnabled>().0 = false;
         }
     }
 }
 
+/// Selected entity which can be changed by gizmo
+struct GizmoTarget {
+    entity: Entity,
+    global: GlobalTransform,
+    parent_global: Option<GlobalTransform>,
+}
+
+enum CursorAction {
+    ToSelected,
+    ToOrigin,
+}
+
+fn result_transform(result: &GizmoResult) -> Transform {
+    Transform {
+        translation: Vec3::from(<[f32; 3]>::from(result.translation)),
+        rotation: Quat::from_array(<[f32; 4]>::from(result.rotation)),
+        scale: Vec3::from(<[f32; 3]>::from(result.scale)),
+    }
+}
+
+/// Move global transform together with gizmo frame
+fn apply_frame_change(
+    frame: &Transform,
+    new_frame: &Transform,
+    global: &GlobalTransform,
+) -> GlobalTransform {
+    GlobalTransform::from(
+        new_frame.compute_affine() * frame.compute_affine().inverse() * global.affine(),
+    )
+}
+
+fn set_global_transform(world: &mut World, target: &GizmoTarget, global: GlobalTransform) {
+    let Some(mut transform) = world.get_mut::<Transform>(target.entity) else {
+        return;
+    };
+    *transform = target.parent_global.map_or_else(
+        || global.compute_transform(),
+        |parent_global| global.reparented_to(&parent_global),
+    );
+}
+
 #[derive(Resource, Default)]
 pub struct MultipleCenter {
     pub center: Option<Vec3>,
 }
 
+/// 3D cursor, which can be used as pivot of transforms
+#[derive(Resource, Default, Clone, Copy)]
+pub struct Cursor3d {
+    pub position: Vec3,
+    /// Cursor is drawn only while it is used as pivot
+    pub visible: bool,
+}
+
+/// Snapping settings of gizmo tool. Hold [`GizmoHotkey::Snap`] to invert `enabled` while dragging
+#[derive(Resource, Reflect, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct GizmoSnapping {
+    pub enabled: bool,
+    /// Grid size for translation
+    pub translate: f32,
+    /// Rotation step in degrees
+    pub angle: f32,
+    /// Scale step
+    pub scale: f32,
+}
+
+impl Default for GizmoSnapping {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            translate: 0.5,
+            angle: 15.,
+            scale: 0.1,
+        }
+    }
+}
+
+impl GizmoSnapping {
+    /// Set snapping steps to gizmo. Gizmo snaps offset from drag start
+    pub fn configure(&self, gizmo: Gizmo, active: bool) -> Gizmo {
+        gizmo
+            .snapping(active)
+            .snap_distance(self.translate)
+            .snap_angle(self.angle.to_radians())
+            .snap_scale(self.scale)
+    }
+
+    /// Align moved axes of translation to world grid, so entity placed off grid comes to the nearest grid node.
+    /// Axes which are not moved keep their value, so dragging along X does not move entity on Y and Z
+    pub fn snap_translation(&self, old: Vec3, new: Vec3) -> Vec3 {
+        if self.translate <= 0. {
+            return new;
+        }
+        let snapped = (new / self.translate).round() * self.translate;
+        let moved = (new - old).abs().cmpgt(Vec3::splat(f32::EPSILON));
+        Vec3::select(moved, snapped, new)
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.enabled, "Enabled");
+        ui.add(
+            egui::DragValue::new(&mut self.translate)
+                .clamp_range(0.001..=f32::MAX)
+                .speed(0.1)
+                .prefix("Grid: "),
+        );
+        ui.add(
+            egui::DragValue::new(&mut self.angle)
+                .clamp_range(0.1..=180.)
+                .prefix("Angle: ")
+                .suffix("°"),
+        );
+        ui.add(
+            egui::DragValue::new(&mut self.scale)
+                .clamp_range(0.001..=f32::MAX)
+                .speed(0.01)
+                .prefix("Scale step: "),
+        );
+    }
+}
+
 trait ToButton {
     fn to_button(&self, size: &Sizing) -> egui::Button;
 }
@@ -401,6 +839,7 @@ impl ToButton for GizmoMode {
 fn draw_lines_system(
     mut gizmos: Gizmos,
     mean_center: Res<MultipleCenter>,
+    cursor: Res<Cursor3d>,
     selected: Query<&GlobalTransform, With<Selected>>,
 ) {
     if let Some(center) = mean_center.center {
@@ -408,4 +847,24 @@ fn draw_lines_system(
             gizmos.line(selected.translation(), center, Color::WHITE);
         }
     }
+
+    if !cursor.visible {
+        return;
+    }
+    let size = 0.25;
+    gizmos.line(
+        cursor.position - Vec3::X * size,
+        cursor.position + Vec3::X * size,
+        Color::RED,
+    );
+    gizmos.line(
+        cursor.position - Vec3::Y * size,
+        cursor.position + Vec3::Y * size,
+        Color::GREEN,
+    );
+    gizmos.line(
+        cursor.position - Vec3::Z * size,
+        cursor.position + Vec3::Z * size,
+        Color::BLUE,
+    );
 }
@@ -0,0 +1,273 @@
+use bevy::{prelude::*, render::primitives::Aabb, utils::HashSet};
+use bevy_egui_next::egui;
+use space_editor_core::prelude::*;
+use space_prefab::component::{is_hidden, is_locked, scene_layers};
+use space_shared::*;
+
+use crate::{selection::SelectionOp, viewports::ActiveEditorCamera};
+
+/// Shape of viewport drag selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarqueeMode {
+    /// Left mouse drag orbits camera
+    #[default]
+    Off,
+    Box,
+    Lasso,
+}
+
+impl MarqueeMode {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Box, Self::Lasso];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "Orbit",
+            Self::Box => "Box select",
+            Self::Lasso => "Lasso select",
+        }
+    }
+}
+
+/// Box and lasso selection in game view.
+/// Shift adds to selection, Ctrl subtracts and Shift+Ctrl toggles (same as click selection)
+#[derive(Default)]
+pub struct MarqueeSelection {
+    pub mode: MarqueeMode,
+    /// Drag path in egui coordinates. Box uses first and last points
+    points: Vec<egui::Pos2>,
+    dragging: bool,
+}
+
+/// Distance in points after which press becomes drag. Shorter drags are handled as click by picking
+const DRAG_THRESHOLD: f32 = 4.;
+
+impl MarqueeSelection {
+    pub fn toolbar(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_id_source("marquee_mode")
+            .selected_text(self.mode.name())
+            .show_ui(ui, |ui| {
+                for mode in MarqueeMode::ALL {
+                    ui.selectable_value(&mut self.mode, mode, mode.name());
+                }
+            })
+            .response
+            .on_hover_text("Left mouse drag");
+    }
+
+    /// Stop drag without selection change. Used when pointer was taken by gizmo
+    pub fn cancel(&mut self) {
+        self.points.clear();
+        self.dragging = false;
+    }
+
+    /// Process drag in game view. Returns true while drag selection is active to block camera control
+    pub fn interact(&mut self, ui: &egui::Ui, commands: &mut Commands, world: &mut World) -> bool {
+        if self.mode == MarqueeMode::Off {
+            self.cancel();
+            return false;
+        }
+
+        let viewport_rect = ui.clip_rect();
+        let (pressed, down, released, pos) = ui.input(|i| {
+            (
+                i.pointer.primary_pressed(),
+                i.pointer.primary_down(),
+                i.pointer.primary_released(),
+                i.pointer.interact_pos(),
+            )
+        });
+
+        // gizmo and toolbar widgets take pointer before marquee
+        if pressed && !ui.ctx().is_using_pointer() {
+            if let Some(pos) = pos.filter(|pos| viewport_rect.contains(*pos)) {
+                self.points = vec![pos];
+            }
+        }
+        if self.points.is_empty() {
+            return false;
+        }
+
+        if let Some(pos) = pos {
+            if down && self.points.last() != Some(&pos) {
+                if self.mode == MarqueeMode::Box && self.points.len() > 1 {
+                    self.points[1] = pos;
+                } else {
+                    self.points.push(pos);
+                }
+            }
+            self.dragging |= self.points[0].distance(pos) > DRAG_THRESHOLD;
+        }
+
+        if released || !down {
+            if self.dragging {
+                let picked = self.picked_entities(viewport_rect, world);
+                let mut selected = world.query_filtered::<Entity, With<Selected>>();
+                let selected = selected.iter(world).collect::<Vec<_>>();
+                SelectionOp::from_keyboard(world.resource::<Input<KeyCode>>())
+                    .apply(commands, selected, &picked);
+            }
+            self.cancel();
+            return false;
+        }
+
+        if self.dragging {
+            self.draw(ui);
+        }
+        self.dragging
+    }
+
+    fn draw(&self, ui: &egui::Ui) {
+        let stroke = egui::Stroke::new(1., egui::Color32::WHITE);
+        let fill = egui::Color32::from_white_alpha(16);
+        let painter = ui.painter();
+        match self.mode {
+            MarqueeMode::Box => {
+                let rect =
+                    egui::Rect::from_two_pos(self.points[0], self.points[self.points.len() - 1]);
+                painter.rect(rect, 0., fill, stroke);
+            }
+            MarqueeMode::Lasso => {
+                painter.add(egui::Shape::closed_line(self.points.clone(), stroke));
+            }
+            MarqueeMode::Off => {}
+        }
+    }
+
+    fn contains(&self, point: egui::Pos2) -> bool {
+        match self.mode {
+            MarqueeMode::Box => {
+                egui::Rect::from_two_pos(self.points[0], self.points[self.points.len() - 1])
+                    .contains(point)
+            }
+            MarqueeMode::Lasso => polygon_contains(&self.points, point),
+            MarqueeMode::Off => false,
+        }
+    }
+
+    /// Prefab entities, which bounds or origins are projected inside marquee. Locked and hidden entities are skipped.
+    /// Picked meshes are resolved to entity selectable by click ([`SelectParent`] or closest prefab ancestor)
+    fn picked_entities(&self, viewport_rect: egui::Rect, world: &mut World) -> HashSet<Entity> {
+        let mut cameras =
+            world.query_filtered::<(&Camera, &GlobalTransform), With<ActiveEditorCamera>>();
+        let Ok((camera, camera_transform)) = cameras
+            .get_single(world)
+            .map(|(camera, transform)| (camera.clone(), *transform))
+        else {
+            return HashSet::new();
+        };
+        let scale_factor = world
+            .resource::<bevy_egui_next::EguiSettings>()
+            .scale_factor as f32;
+        // points behind camera or outside frustum depth are not projected
+        let to_screen = |point: Vec3| {
+            camera
+                .world_to_viewport(&camera_transform, point)
+                .map(|pos| viewport_rect.left_top() + egui::vec2(pos.x, pos.y) / scale_factor)
+        };
+
+        let mut hits = vec![];
+        let mut bounds = world.query::<(Entity, &Aabb, &GlobalTransform)>();
+        for (entity, aabb, global) in bounds.iter(world) {
+            let (min, max) = (Vec3::from(aabb.min()), Vec3::from(aabb.max()));
+            let points = (0..8)
+                .map(|i| Vec3::select(BVec3::new(i & 1 != 0, i & 2 != 0, i & 4 != 0), max, min))
+                .chain([Vec3::from(aabb.center)])
+                .filter_map(|corner| to_screen(global.transform_point(corner)))
+                .collect::<Vec<_>>();
+            if self.hits_points(&points) {
+                hits.push(entity);
+            }
+        }
+        let mut origins = world.query_filtered::<(Entity, &GlobalTransform), With<PrefabMarker>>();
+        for (entity, global) in origins.iter(world) {
+            if to_screen(global.translation()).is_some_and(|pos| self.contains(pos)) {
+                hits.push(entity);
+            }
+        }
+
+        let layers = scene_layers(world);
+        hits.into_iter()
+            .filter_map(|entity| selectable_entity(world, entity))
+            .filter(|entity| {
+                world
+                    .get::<InheritedVisibility>(*entity)
+                    .map_or(true, |visibility| visibility.get())
+            })
+            .filter(|entity| {
+                !is_locked(world, &layers, *entity) && !is_hidden(world, &layers, *entity)
+            })
+            .collect()
+    }
+
+    /// Box selects entities which projected bounds intersect it, lasso needs any projected point inside
+    fn hits_points(&self, points: &[egui::Pos2]) -> bool {
+        if points.is_empty() {
+            return false;
+        }
+        match self.mode {
+            MarqueeMode::Box => {
+                let bounds = egui::Rect::from_points(points);
+                egui::Rect::from_two_pos(self.points[0], self.points[self.points.len() - 1])
+                    .intersects(bounds)
+            }
+            _ => points.iter().any(|point| self.contains(*point)),
+        }
+    }
+}
+
+/// Entity which will be selected instead of picked one
+fn selectable_entity(world: &World, entity: Entity) -> Option<Entity> {
+    if let Some(select_parent) = world.get::<SelectParent>(entity) {
+        return Some(select_parent.parent);
+    }
+    let mut current = entity;
+    loop {
+        if world.get::<PrefabMarker>(current).is_some() {
+            return Some(current);
+        }
+        current = world.get::<Parent>(current)?.get();
+    }
+}
+
+/// Even-odd rule point in polygon test
+fn polygon_contains(polygon: &[egui::Pos2], point: egui::Pos2) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lasso_contains_points_of_concave_polygon() {
+        // U shape with notch between x = 1 and x = 2
+        let polygon = [
+            (0., 0.),
+            (3., 0.),
+            (3., 3.),
+            (2., 3.),
+            (2., 1.),
+            (1., 1.),
+            (1., 3.),
+            (0., 3.),
+        ]
+        .map(|(x, y)| egui::pos2(x, y));
+
+        assert!(polygon_contains(&polygon, egui::pos2(0.5, 2.)));
+        assert!(polygon_contains(&polygon, egui::pos2(1.5, 0.5)));
+        assert!(!polygon_contains(&polygon, egui::pos2(1.5, 2.)));
+        assert!(!polygon_contains(&polygon, egui::pos2(4., 1.)));
+        assert!(!polygon_contains(&[], egui::pos2(0., 0.)));
+    }
+}
@@ -0,0 +1,437 @@
+use std::sync::Arc;
+
+use bevy::{
+    ecs::system::SystemState,
+    prelude::*,
+    render::{mesh::PrimitiveTopology, primitives::Aabb},
+    utils::{HashMap, HashSet},
+};
+use bevy_egui_next::egui;
+use bevy_mod_picking::backends::raycast::bevy_mod_raycast::prelude::{
+    Raycast, RaycastSettings, RaycastVisibility,
+};
+use space_shared::*;
+use space_undo::UndoSet;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+use crate::viewports::ActiveEditorCamera;
+
+/// Plugin with surface placement settings and placement of new bundles
+pub struct SurfacePlacementPlugin;
+
+impl Plugin for SurfacePlacementPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SurfacePlacement>()
+            .register_type::<SurfacePlacement>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<SurfacePlacement>();
+        }
+
+        app.init_resource::<MeshSnapCache>();
+        app.add_systems(Update, invalidate_mesh_snap_cache);
+
+        // Placed before undo detection, so spawn and placement are one change
+        app.add_systems(
+            PostUpdate,
+            place_new_entities
+                .in_set(EditorSet::Editor)
+                .before(UndoSet::PerType),
+        );
+    }
+}
+
+/// Settings of placing entities onto scene geometry
+#[derive(Resource, Reflect, Clone, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct SurfacePlacement {
+    /// Rotate placed entities so their up axis matches surface normal
+    pub align_to_normal: bool,
+    /// Place new bundles on surface in the center of game view
+    pub place_new_bundles: bool,
+}
+
+impl Default for SurfacePlacement {
+    fn default() -> Self {
+        Self {
+            align_to_normal: false,
+            place_new_bundles: true,
+        }
+    }
+}
+
+impl SurfacePlacement {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.align_to_normal, "Align to normal");
+        ui.checkbox(&mut self.place_new_bundles, "Place new bundles on surface");
+    }
+}
+
+/// Marker for new entities, which will be placed on surface in the center of game view
+#[derive(Component, Default, Clone)]
+pub struct PlaceOnSurface;
+
+/// Point and normal of scene geometry hit by ray
+#[derive(Debug, Clone, Copy)]
+pub struct SurfaceHit {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub normal: Vec3,
+}
+
+/// Cast ray against scene meshes, skipping excluded entities
+pub fn cast_surface_ray(
+    raycast: &mut Raycast,
+    ray: Ray,
+    exclude: &HashSet<Entity>,
+) -> Option<SurfaceHit> {
+    let filter = |entity: Entity| !exclude.contains(&entity);
+    let settings = RaycastSettings::default()
+        .with_filter(&filter)
+        .with_visibility(RaycastVisibility::MustBeVisible);
+    raycast
+        .cast_ray(ray, &settings)
+        .first()
+        .map(|(entity, hit)| SurfaceHit {
+            entity: *entity,
+            position: hit.position(),
+            normal: hit.normal(),
+        })
+}
+
+/// Same as [`cast_surface_ray`], but for exclusive world access (tools ui)
+pub fn cast_surface_ray_world(
+    world: &mut World,
+    ray: Ray,
+    exclude: &HashSet<Entity>,
+) -> Option<SurfaceHit> {
+    let mut state = SystemState::<Raycast>::new(world);
+    let mut raycast = state.get_mut(world);
+    cast_surface_ray(&mut raycast, ray, exclude)
+}
+
+/// Entities with all their descendants. Used to not hit placed entities by own rays
+pub fn with_descendants(
+    world: &World,
+    entities: impl IntoIterator<Item = Entity>,
+) -> HashSet<Entity> {
+    let mut result = HashSet::new();
+    let mut stack = entities.into_iter().collect::<Vec<_>>();
+    while let Some(entity) = stack.pop() {
+        if result.insert(entity) {
+            if let Some(children) = world.get::<Children>(entity) {
+                stack.extend(children.iter().copied());
+            }
+        }
+    }
+    result
+}
+
+/// Transform which puts entity bottom onto surface hit. Bottom is taken from [`Aabb`] if entity has it
+pub fn place_on_surface(
+    global: &GlobalTransform,
+    aabb: Option<&Aabb>,
+    hit: SurfaceHit,
+    align_to_normal: bool,
+) -> Transform {
+    let mut transform = global.compute_transform();
+    let up = if align_to_normal {
+        transform.rotation =
+            Quat::from_rotation_arc(transform.up(), hit.normal) * transform.rotation;
+        hit.normal
+    } else {
+        Vec3::Y
+    };
+    // lowest corner of bounding box along up axis
+    let bottom = aabb.map_or(0., |aabb| {
+        let (min, max) = (Vec3::from(aabb.min()), Vec3::from(aabb.max()));
+        (0..8)
+            .map(|i| {
+                let corner = Vec3::select(BVec3::new(i & 1 != 0, i & 2 != 0, i & 4 != 0), max, min);
+                (transform.rotation * (transform.scale * corner)).dot(up)
+            })
+            .fold(f32::MAX, f32::min)
+    });
+    transform.translation = hit.position - up * bottom;
+    transform
+}
+
+fn place_new_entities(
+    mut commands: Commands,
+    settings: Res<SurfacePlacement>,
+    mut raycast: Raycast,
+    mut query: Query<(Entity, &mut Transform, Option<&Aabb>), Added<PlaceOnSurface>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<ActiveEditorCamera>>,
+) {
+    if query.is_empty() {
+        return;
+    }
+    let ray = cameras
+        .get_single()
+        .ok()
+        .and_then(|(camera, camera_transform)| {
+            let center = camera.logical_viewport_size()? / 2.;
+            camera.viewport_to_world(camera_transform, center)
+        });
+
+    for (entity, mut transform, aabb) in query.iter_mut() {
+        commands.entity(entity).remove::<PlaceOnSurface>();
+
+        let Some(ray) = ray else {
+            continue;
+        };
+        let Some(hit) = cast_surface_ray(&mut raycast, ray, &HashSet::from([entity])) else {
+            continue;
+        };
+        *transform = place_on_surface(
+            &GlobalTransform::from(*transform),
+            aabb,
+            hit,
+            settings.align_to_normal,
+        );
+    }
+}
+
+/// Local space vertices and unique triangle edges of mesh
+#[derive(Default)]
+struct MeshSnapData {
+    vertices: Vec<Vec3>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl MeshSnapData {
+    fn new(mesh: &Mesh) -> Self {
+        let Some(positions) = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|positions| positions.as_float3())
+        else {
+            return Self::default();
+        };
+        let vertices = positions
+            .iter()
+            .map(|position| Vec3::from(*position))
+            .collect();
+
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return Self {
+                vertices,
+                edges: vec![],
+            };
+        }
+        let indices = mesh.indices().map_or_else(
+            || (0..positions.len()).collect::<Vec<_>>(),
+            |indices| indices.iter().collect(),
+        );
+        let mut edges = HashSet::new();
+        for triangle in indices.chunks_exact(3) {
+            for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+                let (a, b) = (triangle[a], triangle[b]);
+                // triangles share edges
+                edges.insert((a.min(b), a.max(b)));
+            }
+        }
+        Self {
+            vertices,
+            edges: edges.into_iter().collect(),
+        }
+    }
+}
+
+/// Snap data of meshes. Mesh is scanned once and scanned again only after it is changed
+#[derive(Resource, Default)]
+pub struct MeshSnapCache(HashMap<AssetId<Mesh>, Arc<MeshSnapData>>);
+
+fn invalidate_mesh_snap_cache(
+    mut events: EventReader<AssetEvent<Mesh>>,
+    mut cache: ResMut<MeshSnapCache>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Modified { id } | AssetEvent::Removed { id } => {
+                cache.0.remove(id);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// World space vertices and triangle edges of meshes. Used for vertex and edge snapping
+#[derive(Default)]
+pub struct MeshSnapGeometry {
+    pub vertices: Vec<Vec3>,
+    /// Indices of edge ends in `vertices`
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl MeshSnapGeometry {
+    /// Collect geometry of entities with [`Handle<Mesh>`].
+    /// [`MeshPrimitivePrefab`](space_prefab::component::MeshPrimitivePrefab), [`AssetMesh`](space_prefab::component::AssetMesh)
+    /// and gltf scenes all create mesh handles, so pass entity with its descendants to snap whole prefab
+    pub fn collect(world: &mut World, entities: &HashSet<Entity>) -> Self {
+        world.resource_scope(|world, mut cache: Mut<MeshSnapCache>| {
+            let meshes = world.resource::<Assets<Mesh>>();
+            let mut geometry = Self::default();
+            for entity in entities.iter() {
+                let (Some(handle), Some(global)) = (
+                    world.get::<Handle<Mesh>>(*entity),
+                    world.get::<GlobalTransform>(*entity),
+                ) else {
+                    continue;
+                };
+                let data = match cache.0.get(&handle.id()) {
+                    Some(data) => data.clone(),
+                    None => {
+                        let Some(mesh) = meshes.get(handle) else {
+                            continue;
+                        };
+                        let data = Arc::new(MeshSnapData::new(mesh));
+                        cache.0.insert(handle.id(), data.clone());
+                        data
+                    }
+                };
+
+                let offset = geometry.vertices.len();
+                geometry.vertices.extend(
+                    data.vertices
+                        .iter()
+                        .map(|position| global.transform_point(*position)),
+                );
+                geometry
+                    .edges
+                    .extend(data.edges.iter().map(|(a, b)| (offset + a, offset + b)));
+            }
+            geometry
+        })
+    }
+
+    /// Vertex nearest to point
+    pub fn nearest_vertex(&self, point: Vec3) -> Option<Vec3> {
+        self.vertices.iter().copied().min_by(|a, b| {
+            a.distance_squared(point)
+                .total_cmp(&b.distance_squared(point))
+        })
+    }
+
+    /// Vertex nearest to ray line. Used to pick vertex under pointer
+    pub fn nearest_vertex_to_ray(&self, ray: Ray) -> Option<Vec3> {
+        let distance = |vertex: &Vec3| {
+            let along = (*vertex - ray.origin).dot(ray.direction);
+            vertex.distance_squared(ray.origin + ray.direction * along)
+        };
+        self.vertices
+            .iter()
+            .copied()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+    }
+
+    /// Point on triangle edges nearest to point
+    pub fn nearest_edge_point(&self, point: Vec3) -> Option<Vec3> {
+        self.edges
+            .iter()
+            .map(|(a, b)| {
+                let (a, b) = (self.vertices[*a], self.vertices[*b]);
+                let edge = b - a;
+                let t = (point - a).dot(edge) / edge.length_squared().max(f32::EPSILON);
+                a + edge * t.clamp(0., 1.)
+            })
+            .min_by(|a, b| {
+                a.distance_squared(point)
+                    .total_cmp(&b.distance_squared(point))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(position: Vec3, normal: Vec3) -> SurfaceHit {
+        SurfaceHit {
+            entity: Entity::PLACEHOLDER,
+            position,
+            normal,
+        }
+    }
+
+    fn unit_cube() -> MeshSnapGeometry {
+        let data = MeshSnapData::new(&Mesh::from(shape::Cube::new(1.)));
+        MeshSnapGeometry {
+            vertices: data.vertices,
+            edges: data.edges,
+        }
+    }
+
+    #[test]
+    fn place_on_tilted_surface() {
+        let aabb = Aabb::from_min_max(Vec3::splat(-0.5), Vec3::splat(0.5));
+        let position = Vec3::new(1., 2., 3.);
+        let normal = Vec3::new(1., 1., 0.).normalize();
+
+        // bottom of box touches hit point, box center is half of its height above surface
+        let aligned = place_on_surface(
+            &GlobalTransform::IDENTITY,
+            Some(&aabb),
+            hit(position, normal),
+            true,
+        );
+        assert!(aligned.up().abs_diff_eq(normal, 1e-5));
+        assert!(aligned
+            .translation
+            .abs_diff_eq(position + normal * 0.5, 1e-5));
+
+        // without alignment box stays upright and is lifted along world up by half of scaled bounds
+        let upright = place_on_surface(
+            &GlobalTransform::from_scale(Vec3::splat(2.)),
+            Some(&aabb),
+            hit(position, normal),
+            false,
+        );
+        assert_eq!(upright.rotation, Quat::IDENTITY);
+        assert!(upright.translation.abs_diff_eq(position + Vec3::Y, 1e-5));
+
+        // entity without bounds is placed by its origin
+        let origin = place_on_surface(
+            &GlobalTransform::IDENTITY,
+            None,
+            hit(position, normal),
+            false,
+        );
+        assert_eq!(origin.translation, position);
+    }
+
+    #[test]
+    fn unit_cube_snap_geometry() {
+        let cube = unit_cube();
+        // 6 faces with 4 sides and 1 diagonal each, shared diagonal is stored once
+        assert_eq!(cube.edges.len(), 30);
+
+        assert_eq!(
+            cube.nearest_vertex(Vec3::new(0.4, 0.6, 0.45)),
+            Some(Vec3::splat(0.5))
+        );
+
+        // ray passes through corner, which is not the nearest corner to ray origin
+        let origin = Vec3::new(2., 0.6, 0.4);
+        let corner = Vec3::new(0.5, 0.5, -0.5);
+        let ray = Ray {
+            origin,
+            direction: (corner - origin).normalize(),
+        };
+        assert!(cube
+            .nearest_vertex_to_ray(ray)
+            .unwrap()
+            .abs_diff_eq(corner, 1e-5));
+
+        // point near middle of vertical edge snaps onto edge, not onto its ends
+        assert!(cube
+            .nearest_edge_point(Vec3::new(0.7, 0.1, 0.6))
+            .unwrap()
+            .abs_diff_eq(Vec3::new(0.5, 0.1, 0.5), 1e-5));
+
+        let empty = MeshSnapGeometry::default();
+        assert_eq!(empty.nearest_vertex(Vec3::ZERO), None);
+        assert_eq!(empty.nearest_edge_point(Vec3::ZERO), None);
+    }
+}
@@ -0,0 +1,540 @@
+use std::{any::Any, f32::consts::PI, sync::Arc};
+
+use bevy::{prelude::*, utils::HashSet};
+use bevy_egui_next::egui;
+use space_editor_core::prelude::*;
+use space_prefab::{
+    component::{is_locked, scene_layers},
+    load::{PrefabBundle, PrefabLoader},
+};
+use space_shared::*;
+use space_undo::{AddedEntity, NewChange, OneFrameUndoIgnore, RemovedEntity};
+
+use crate::{
+    game_view::pointer_ray, prelude::EditorTool, tool::ToolExt, ui_registration::BundleReg,
+    viewports::ActiveEditorCamera,
+};
+
+use super::placement::{cast_surface_ray_world, with_descendants};
+
+pub struct ScatterToolPlugin;
+
+impl Plugin for ScatterToolPlugin {
+    fn build(&self, app: &mut App) {
+        app.editor_tool(ScatterTool::default());
+    }
+}
+
+/// Thing which can be painted by scatter tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScatterItem {
+    /// Bundle registered with `editor_bundle`
+    Bundle { category: String, name: String },
+    /// Prefab file loaded by [`PrefabLoader`]
+    Prefab { path: String },
+}
+
+impl ScatterItem {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Bundle { name, .. } => name,
+            Self::Prefab { path } => path,
+        }
+    }
+
+    /// Check if entity was created from this item. Bundles are recognized by [`Name`]
+    fn matches(&self, world: &World, entity: Entity) -> bool {
+        match self {
+            Self::Bundle { name, .. } => world
+                .get::<Name>(entity)
+                .is_some_and(|entity_name| entity_name.as_str() == name),
+            Self::Prefab { path } => world
+                .get::<PrefabLoader>(entity)
+                .is_some_and(|loader| &loader.path == path),
+        }
+    }
+
+    fn spawn(&self, world: &mut World, commands: &mut Commands, transform: Transform) -> Entity {
+        let entity = match self {
+            Self::Bundle { category, name } => world
+                .resource::<BundleReg>()
+                .bundles
+                .get(category)
+                .and_then(|bundles| bundles.get(name))
+                .map(|bundle| bundle.spawn(commands)),
+            Self::Prefab { path } => {
+                let name = std::path::Path::new(path)
+                    .file_stem()
+                    .map_or_else(|| path.clone(), |stem| stem.to_string_lossy().to_string());
+                Some(
+                    commands
+                        .spawn((PrefabBundle::new(path), PrefabMarker, Name::new(name)))
+                        .id(),
+                )
+            }
+        }
+        .unwrap_or_else(|| commands.spawn(PrefabMarker).id());
+        // whole entity is recorded by AddedEntity at the end of stroke
+        commands
+            .entity(entity)
+            .insert((transform, OneFrameUndoIgnore::default()));
+        entity
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScatterMode {
+    #[default]
+    Paint,
+    Erase,
+}
+
+/// Changes of one brush stroke. They are sent to undo together when stroke ends
+#[derive(Default)]
+struct ScatterStroke {
+    last_dab: Vec3,
+    painted: Vec<Entity>,
+    /// Erased entities are despawned when stroke ends
+    erased: HashSet<Entity>,
+}
+
+/// Brush tool, which paints bundles and prefabs onto surfaces under pointer.
+/// Painted entities are usual prefab entities, each stroke is one undo change
+pub struct ScatterTool {
+    pub items: Vec<ScatterItem>,
+    pub mode: ScatterMode,
+    pub radius: f32,
+    /// Entities per square unit in one brush dab
+    pub density: f32,
+    /// Minimal distance between painted entities
+    pub min_spacing: f32,
+    /// Allowed surface slope in degrees
+    pub slope: (f32, f32),
+    /// Random rotation around up axis in degrees
+    pub rotation: (f32, f32),
+    /// Max random tilt from up axis in degrees
+    pub tilt: f32,
+    /// Random uniform scale
+    pub scale: (f32, f32),
+    pub align_to_normal: bool,
+    prefab_path: String,
+    stroke: Option<ScatterStroke>,
+    rng: fastrand::Rng,
+}
+
+impl Default for ScatterTool {
+    fn default() -> Self {
+        Self {
+            items: vec![],
+            mode: ScatterMode::Paint,
+            radius: 2.,
+            density: 0.3,
+            min_spacing: 1.,
+            slope: (0., 40.),
+            rotation: (0., 360.),
+            tilt: 0.,
+            scale: (0.8, 1.2),
+            align_to_normal: false,
+            prefab_path: String::new(),
+            stroke: None,
+            rng: fastrand::Rng::new(),
+        }
+    }
+}
+
+impl EditorTool for ScatterTool {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Scatter"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
+        let settings_rect = self.settings_ui(ui, world);
+
+        let Some(ray) = pointer_ray(ui, world) else {
+            self.finish_stroke(world, commands);
+            return;
+        };
+        let over_settings = ui
+            .input(|i| i.pointer.hover_pos())
+            .is_some_and(|pos| settings_rect.contains(pos));
+        let exclude = self.stroke_exclude(world);
+        let Some(hit) = cast_surface_ray_world(world, ray, &exclude) else {
+            return;
+        };
+
+        self.draw_brush(ui, world, hit.position, hit.normal);
+
+        let (pressed, down) = ui.input(|i| (i.pointer.primary_pressed(), i.pointer.primary_down()));
+        if pressed && !over_settings && !ui.ctx().is_using_pointer() {
+            self.stroke = Some(ScatterStroke::default());
+            self.dab(world, commands, hit.position, hit.normal);
+        } else if !down {
+            self.finish_stroke(world, commands);
+        } else if self
+            .stroke
+            .as_ref()
+            .is_some_and(|stroke| stroke.last_dab.distance(hit.position) > self.radius * 0.5)
+        {
+            self.dab(world, commands, hit.position, hit.normal);
+        }
+
+        if self.stroke.is_some() {
+            world.resource_mut::<crate::EditorCameraEnabled>().0 = false;
+        }
+    }
+}
+
+impl ScatterTool {
+    /// Returns rect of settings panel to not paint under it
+    fn settings_ui(&mut self, ui: &mut egui::Ui, world: &mut World) -> egui::Rect {
+        ui.group(|ui| {
+            ui.set_max_width(260.);
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.mode, ScatterMode::Paint, "Paint");
+                ui.selectable_value(&mut self.mode, ScatterMode::Erase, "Erase");
+            });
+
+            egui::Grid::new("scatter_settings")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Radius");
+                    ui.add(
+                        egui::DragValue::new(&mut self.radius)
+                            .speed(0.1)
+                            .clamp_range(0.1..=100.),
+                    );
+                    ui.end_row();
+                    ui.label("Density");
+                    ui.add(
+                        egui::DragValue::new(&mut self.density)
+                            .speed(0.01)
+                            .clamp_range(0.0..=100.),
+                    )
+                    .on_hover_text("Entities per square unit in one brush dab");
+                    ui.end_row();
+                    ui.label("Min spacing");
+                    ui.add(
+                        egui::DragValue::new(&mut self.min_spacing)
+                            .speed(0.05)
+                            .clamp_range(0.0..=100.),
+                    );
+                    ui.end_row();
+                    range_edit(ui, "Slope", &mut self.slope, 1., 0.0..=90.);
+                    range_edit(ui, "Rotation", &mut self.rotation, 1., -360.0..=360.);
+                    ui.label("Tilt");
+                    ui.add(
+                        egui::DragValue::new(&mut self.tilt)
+                            .speed(1.)
+                            .clamp_range(0.0..=90.),
+                    );
+                    ui.end_row();
+                    range_edit(ui, "Scale", &mut self.scale, 0.01, 0.01..=100.);
+                    ui.label("Align");
+                    ui.checkbox(&mut self.align_to_normal, "To surface normal");
+                    ui.end_row();
+                });
+
+            ui.separator();
+            let mut removed = None;
+            for (idx, item) in self.items.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(item.name());
+                    if ui.button("🗑").clicked() {
+                        removed = Some(idx);
+                    }
+                });
+            }
+            if let Some(idx) = removed {
+                self.items.remove(idx);
+            }
+
+            ui.horizontal(|ui| {
+                ui.menu_button("➕ Bundle", |ui| {
+                    for (category, bundles) in world.resource::<BundleReg>().bundles.iter() {
+                        ui.menu_button(category, |ui| {
+                            for name in bundles.keys() {
+                                if ui.button(name).clicked() {
+                                    self.items.push(ScatterItem::Bundle {
+                                        category: category.clone(),
+                                        name: name.clone(),
+                                    });
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    }
+                });
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.prefab_path)
+                        .hint_text("prefab path")
+                        .desired_width(100.),
+                );
+                if ui
+                    .add_enabled(!self.prefab_path.is_empty(), egui::Button::new("➕ Prefab"))
+                    .clicked()
+                {
+                    self.items.push(ScatterItem::Prefab {
+                        path: std::mem::take(&mut self.prefab_path),
+                    });
+                }
+            });
+            if self.items.is_empty() {
+                ui.label("Add bundles or prefabs to paint");
+            }
+        })
+        .response
+        .rect
+    }
+
+    /// Entities painted in current stroke are not used as surface
+    fn stroke_exclude(&self, world: &World) -> HashSet<Entity> {
+        self.stroke.as_ref().map_or_else(HashSet::new, |stroke| {
+            with_descendants(world, stroke.painted.iter().copied())
+        })
+    }
+
+    /// Send stroke changes to undo in one frame, so they are merged into one change
+    fn finish_stroke(&mut self, world: &mut World, commands: &mut Commands) {
+        let Some(stroke) = self.stroke.take() else {
+            return;
+        };
+        for entity in stroke.painted {
+            world.send_event(NewChange {
+                change: Arc::new(AddedEntity { entity }),
+            });
+        }
+        for entity in stroke.erased {
+            commands.entity(entity).despawn_recursive();
+            world.send_event(NewChange {
+                change: Arc::new(RemovedEntity { entity }),
+            });
+        }
+    }
+
+    /// Draw brush circle on surface and marks of entities, which will be erased
+    fn draw_brush(&self, ui: &egui::Ui, world: &mut World, center: Vec3, normal: Vec3) {
+        let mut cameras =
+            world.query_filtered::<(&Camera, &GlobalTransform), With<ActiveEditorCamera>>();
+        let Ok((camera, camera_transform)) = cameras.get_single(world) else {
+            return;
+        };
+        let scale_factor = world
+            .resource::<bevy_egui_next::EguiSettings>()
+            .scale_factor as f32;
+        let viewport_rect = ui.clip_rect();
+        let to_screen = |point: Vec3| {
+            camera
+                .world_to_viewport(camera_transform, point)
+                .map(|pos| viewport_rect.left_top() + egui::vec2(pos.x, pos.y) / scale_factor)
+        };
+
+        let rotation = Quat::from_rotation_arc(Vec3::Y, normal);
+        let points = (0..=32)
+            .filter_map(|i| {
+                let angle = i as f32 / 32. * 2. * PI;
+                to_screen(center + rotation * Vec3::new(angle.cos(), 0., angle.sin()) * self.radius)
+            })
+            .collect::<Vec<_>>();
+        let color = match self.mode {
+            ScatterMode::Paint => egui::Color32::WHITE,
+            ScatterMode::Erase => egui::Color32::LIGHT_RED,
+        };
+        let painter = ui.painter();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+
+        if let Some(stroke) = &self.stroke {
+            for entity in stroke.erased.iter() {
+                if let Some(pos) = world
+                    .get::<GlobalTransform>(*entity)
+                    .and_then(|global| to_screen(global.translation()))
+                {
+                    painter.circle_stroke(pos, 4., egui::Stroke::new(1.5, color));
+                }
+            }
+        }
+    }
+
+    /// Paint or erase entities in brush area
+    fn dab(&mut self, world: &mut World, commands: &mut Commands, center: Vec3, normal: Vec3) {
+        let Some(mut stroke) = self.stroke.take() else {
+            return;
+        };
+        stroke.last_dab = center;
+
+        let nearby = {
+            let mut query =
+                world.query_filtered::<(Entity, &GlobalTransform), With<PrefabMarker>>();
+            query
+                .iter(world)
+                .filter(|(entity, _)| !stroke.erased.contains(entity))
+                .filter(|(entity, _)| self.items.iter().any(|item| item.matches(world, *entity)))
+                .map(|(entity, global)| (entity, global.translation()))
+                .filter(|(_, position)| position.distance(center) <= self.radius + self.min_spacing)
+                .collect::<Vec<_>>()
+        };
+
+        match self.mode {
+            ScatterMode::Erase => {
+                let layers = scene_layers(world);
+                stroke.erased.extend(
+                    nearby
+                        .into_iter()
+                        .filter(|(_, position)| position.distance(center) <= self.radius)
+                        // locked entities are kept
+                        .filter(|(entity, _)| !is_locked(world, &layers, *entity))
+                        .map(|(entity, _)| entity),
+                );
+            }
+            ScatterMode::Paint if !self.items.is_empty() => {
+                let mut positions = nearby
+                    .into_iter()
+                    .map(|(_, position)| position)
+                    .collect::<Vec<_>>();
+                let exclude = with_descendants(world, stroke.painted.iter().copied());
+                let count = dab_count(&mut self.rng, self.density, self.radius);
+
+                for _ in 0..count {
+                    let offset = disk_offset(&mut self.rng, self.radius, normal);
+                    let ray = Ray {
+                        origin: center + offset + normal * self.radius,
+                        direction: -normal,
+                    };
+                    let Some(hit) = cast_surface_ray_world(world, ray, &exclude) else {
+                        continue;
+                    };
+                    if hit.position.distance(ray.origin) > self.radius * 2. {
+                        continue;
+                    }
+                    let slope = hit.normal.angle_between(Vec3::Y).to_degrees();
+                    if slope < self.slope.0 || slope > self.slope.1 {
+                        continue;
+                    }
+                    if !is_spaced(&positions, hit.position, self.min_spacing) {
+                        continue;
+                    }
+
+                    let up = if self.align_to_normal {
+                        hit.normal
+                    } else {
+                        Vec3::Y
+                    };
+                    let yaw = lerp(self.rotation, self.rng.f32()).to_radians();
+                    let tilt_axis = Quat::from_rotation_y(self.rng.f32() * 2. * PI) * Vec3::X;
+                    let tilt = (self.tilt * self.rng.f32()).to_radians();
+                    let transform = Transform {
+                        translation: hit.position,
+                        rotation: Quat::from_rotation_arc(Vec3::Y, up)
+                            * Quat::from_axis_angle(tilt_axis, tilt)
+                            * Quat::from_rotation_y(yaw),
+                        scale: Vec3::splat(lerp(self.scale, self.rng.f32())),
+                    };
+
+                    let item = self.items[self.rng.usize(..self.items.len())].clone();
+                    positions.push(hit.position);
+                    stroke.painted.push(item.spawn(world, commands, transform));
+                }
+            }
+            ScatterMode::Paint => {}
+        }
+
+        self.stroke = Some(stroke);
+    }
+}
+
+/// Count of entities in one dab. Fraction of expected count is painted with its probability,
+/// so sparse brushes still paint on average
+fn dab_count(rng: &mut fastrand::Rng, density: f32, radius: f32) -> usize {
+    let expected = density * PI * radius * radius;
+    expected as usize + usize::from(rng.f32() < expected.fract())
+}
+
+/// Random offset in brush disk, which lies in plane with given normal. Offsets are uniform over disk area
+fn disk_offset(rng: &mut fastrand::Rng, radius: f32, normal: Vec3) -> Vec3 {
+    let angle = rng.f32() * 2. * PI;
+    let distance = radius * rng.f32().sqrt();
+    Quat::from_rotation_arc(Vec3::Y, normal) * Vec3::new(angle.cos(), 0., angle.sin()) * distance
+}
+
+/// Position is not closer than minimal spacing to any of painted positions
+fn is_spaced(positions: &[Vec3], position: Vec3, min_spacing: f32) -> bool {
+    positions
+        .iter()
+        .all(|painted| painted.distance(position) >= min_spacing)
+}
+
+fn lerp(range: (f32, f32), t: f32) -> f32 {
+    range.0 + (range.1 - range.0) * t
+}
+
+fn range_edit(
+    ui: &mut egui::Ui,
+    label: &str,
+    range: &mut (f32, f32),
+    speed: f32,
+    clamp: std::ops::RangeInclusive<f32>,
+) {
+    ui.label(label);
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::DragValue::new(&mut range.0)
+                .speed(speed)
+                .clamp_range(*clamp.start()..=range.1),
+        );
+        ui.label("..");
+        ui.add(
+            egui::DragValue::new(&mut range.1)
+                .speed(speed)
+                .clamp_range(range.0..=*clamp.end()),
+        );
+    });
+    ui.end_row();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dab_count_matches_density() {
+        let mut rng = fastrand::Rng::with_seed(7);
+        // 0.3 entities per square unit in disk of radius 2 is 3.77 entities per dab
+        let expected = 0.3 * PI * 4.;
+        let counts = (0..10000)
+            .map(|_| dab_count(&mut rng, 0.3, 2.))
+            .collect::<Vec<_>>();
+        assert!(counts.iter().all(|count| *count == 3 || *count == 4));
+        let mean = counts.iter().sum::<usize>() as f32 / counts.len() as f32;
+        assert!((mean - expected).abs() < 0.05, "{mean} != {expected}");
+
+        assert_eq!(dab_count(&mut rng, 0., 2.), 0);
+    }
+
+    #[test]
+    fn disk_offsets_are_uniform_in_tangent_plane() {
+        let mut rng = fastrand::Rng::with_seed(7);
+        let normal = Vec3::new(1., 1., 0.).normalize();
+        let offsets = (0..10000)
+            .map(|_| disk_offset(&mut rng, 2., normal))
+            .collect::<Vec<_>>();
+
+        assert!(offsets
+            .iter()
+            .all(|offset| offset.length() <= 2. + 1e-4 && offset.dot(normal).abs() < 1e-4));
+        // inner disk of half radius has quarter of area
+        let inner = offsets.iter().filter(|offset| offset.length() < 1.).count();
+        let inner = inner as f32 / offsets.len() as f32;
+        assert!((inner - 0.25).abs() < 0.02, "{inner} != 0.25");
+    }
+
+    #[test]
+    fn spacing_rejects_close_positions() {
+        let painted = [Vec3::ZERO, Vec3::new(3., 0., 0.)];
+        assert!(is_spaced(&painted, Vec3::new(1.5, 0., 0.), 1.));
+        assert!(!is_spaced(&painted, Vec3::new(2.5, 0., 0.), 1.));
+        assert!(is_spaced(&[], Vec3::ZERO, 1.));
+    }
+}
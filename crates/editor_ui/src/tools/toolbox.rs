@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use bevy_egui_next::egui;
+use space_editor_core::prelude::*;
+
+use crate::prelude::{EditorTab, EditorTabName, EditorUiAppExt};
+
+use super::align::{AlignPlugin, AlignSettings};
+
+/// Plugin with toolbox tab, which contains operations for selected entities
+pub struct ToolBoxPlugin;
+
+impl Plugin for ToolBoxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(AlignPlugin);
+        app.editor_tab_by_trait(EditorTabName::ToolBox, ToolBoxTab::default());
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ToolBoxTab {}
+
+impl EditorTab for ToolBoxTab {
+    fn ui(&mut self, ui: &mut egui::Ui, _commands: &mut Commands, world: &mut World) {
+        let selected = world
+            .query_filtered::<(), With<Selected>>()
+            .iter(world)
+            .count();
+        ui.label(format!("Selected: {}", selected));
+        ui.separator();
+
+        let mut align = world.resource::<AlignSettings>().clone();
+        align.ui(ui, world);
+        if *world.resource::<AlignSettings>() != align {
+            world.insert_resource(align);
+        }
+    }
+
+    fn title(&self) -> egui::WidgetText {
+        "Toolbox".into()
+    }
+}
@@ -55,12 +55,17 @@ impl FlatPluginList for EditorUiPlugin {
             .add(MeshlessVisualizerPlugin)
             .add(EditorUiCore::default())
             .add(GameViewPlugin)
+            .add(ViewportsPlugin)
             .add(menu_toolbars::BottomMenuPlugin)
             .add(MouseCheck)
             .add(CameraViewTabPlugin)
             .add(SpaceHierarchyPlugin::default())
+            .add(layers::LayersPlugin)
             .add(SpaceInspectorPlugin)
             .add(GizmoToolPlugin)
+            .add(ArrayToolPlugin)
+            .add(ScatterToolPlugin)
+            .add(ToolBoxPlugin)
             .add(ChangeChainViewPlugin)
             .add(settings::SettingsWindowPlugin);
 
@@ -439,6 +444,7 @@ pub fn ui_camera_block(
     mut ctxs: Query<&mut EguiContext, With<PrimaryWindow>>,
     mut state: ResMut<EditorCameraEnabled>,
     game_view: Res<GameViewTab>,
+    viewports: Res<EditorViewports>,
 ) {
     let Ok(mut ctx_ref) = ctxs.get_single_mut() else {
         return;
@@ -448,7 +454,8 @@ pub fn ui_camera_block(
         let Some(pos) = ctx.pointer_latest_pos() else {
             return;
         };
-        if let Some(area) = game_view.viewport_rect {
+        if viewports.contains(pos) {
+        } else if let Some(area) = game_view.viewport_rect {
             if area.contains(pos) {
             } else {
                 *state = EditorCameraEnabled(false);
@@ -0,0 +1,406 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_egui_next::{egui, EguiContext};
+use bevy_mod_picking::{backends::raycast::RaycastPickable, PickableBundle};
+use bevy_panorbit_camera::PanOrbitCamera;
+use space_shared::*;
+
+use crate::{
+    camera_plugin::{set_axis_view, set_orthographic, set_view_angles, AxisView},
+    colors::TEXT_COLOR,
+    editor_tab::EditorTabName,
+    game_view::GameViewTab,
+    prelude::GameModeSettings,
+    sizing::{to_label, Sizing},
+    ui_plugin::{EditorUi, EditorUiReg},
+    RenderLayers, UiSystemSet,
+};
+
+/// Count of additional view tabs besides game view
+pub const EXTRA_VIEWPORTS: usize = 3;
+
+/// Plugin with additional editor view tabs. Each view has its own editor camera, which renders into view rect.
+/// Tools, picking and camera hotkeys work with view under pointer
+pub struct ViewportsPlugin;
+
+impl Plugin for ViewportsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorViewports>();
+
+        for idx in 0..EXTRA_VIEWPORTS {
+            let reg = EditorUiReg::ResourceBased {
+                show_command: Box::new(
+                    move |ui: &mut egui::Ui, commands: &mut Commands, world: &mut World| {
+                        world.resource_scope(|world, mut viewports: Mut<EditorViewports>| {
+                            let active = viewports.active == Some(idx);
+                            viewports.views[idx].ui(idx, active, ui, commands, world);
+                        });
+                    },
+                ),
+                title_command: Box::new(move |world| {
+                    let sizing = world.resource::<Sizing>().clone();
+                    let preset = world.resource::<EditorViewports>().views[idx].preset;
+                    to_label(&format!("{} view", preset.name()), sizing.text).into()
+                }),
+            };
+            app.world
+                .resource_mut::<EditorUi>()
+                .registry
+                .insert(EditorTabName::Viewport(idx), reg);
+        }
+
+        app.add_systems(
+            Update,
+            update_active_viewport.in_set(UiSystemSet::BeforeShow),
+        );
+        app.add_systems(
+            Update,
+            (sync_viewport_cameras, apply_viewport_layout).in_set(UiSystemSet::AfterShow),
+        );
+    }
+}
+
+/// Editor camera used by tools and camera hotkeys. Moved to camera of view under pointer
+#[derive(Component, Default)]
+pub struct ActiveEditorCamera;
+
+/// Editor camera of additional view with index in [`EditorViewports::views`]
+#[derive(Component)]
+pub struct ViewportCamera(pub usize);
+
+/// Initial camera placement of view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewportPreset {
+    #[default]
+    Perspective,
+    Top,
+    Front,
+    Side,
+}
+
+impl ViewportPreset {
+    pub const ALL: [Self; 4] = [Self::Perspective, Self::Top, Self::Front, Self::Side];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Perspective => "Perspective",
+            Self::Top => "Top",
+            Self::Front => "Front",
+            Self::Side => "Side",
+        }
+    }
+
+    pub const fn axis_view(&self) -> Option<AxisView> {
+        match self {
+            Self::Perspective => None,
+            Self::Top => Some(AxisView::Top),
+            Self::Front => Some(AxisView::Front),
+            Self::Side => Some(AxisView::Side),
+        }
+    }
+
+    fn apply(&self, world: &mut World, camera: Entity) {
+        if let Some(view) = self.axis_view() {
+            set_axis_view(world, camera, view);
+        } else {
+            set_orthographic(world, camera, false);
+            set_view_angles(world, camera, -0.4, 0.4);
+        }
+    }
+}
+
+/// Arrangement of game view and additional views in dock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewportLayout {
+    Single,
+    /// Game view and top view side by side
+    Two,
+    /// Game view with top, front and side views in 2x2 grid
+    Four,
+}
+
+impl ViewportLayout {
+    pub const ALL: [Self; 3] = [Self::Single, Self::Two, Self::Four];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Single => "Single view",
+            Self::Two => "2 views",
+            Self::Four => "4 views",
+        }
+    }
+}
+
+/// State of additional view tab
+#[derive(Default)]
+pub struct EditorViewport {
+    pub preset: ViewportPreset,
+    pub camera: Option<Entity>,
+    pub viewport_rect: Option<egui::Rect>,
+    /// View was shown in current frame. Cameras of hidden views are disabled
+    pub shown: bool,
+}
+
+/// Additional editor views
+#[derive(Resource)]
+pub struct EditorViewports {
+    pub views: Vec<EditorViewport>,
+    /// View under pointer, `None` for game view
+    pub active: Option<usize>,
+    /// Layout, which will be applied to dock after editor ui is shown
+    pub requested_layout: Option<ViewportLayout>,
+}
+
+impl Default for EditorViewports {
+    fn default() -> Self {
+        Self {
+            views: [
+                ViewportPreset::Top,
+                ViewportPreset::Front,
+                ViewportPreset::Side,
+            ]
+            .into_iter()
+            .map(|preset| EditorViewport {
+                preset,
+                ..default()
+            })
+            .collect(),
+            active: None,
+            requested_layout: None,
+        }
+    }
+}
+
+impl EditorViewports {
+    /// Pointer is inside one of shown views
+    pub fn contains(&self, pos: egui::Pos2) -> bool {
+        self.views
+            .iter()
+            .any(|view| view.shown && view.viewport_rect.is_some_and(|rect| rect.contains(pos)))
+    }
+}
+
+impl EditorViewport {
+    fn ui(
+        &mut self,
+        idx: usize,
+        active: bool,
+        ui: &mut egui::Ui,
+        commands: &mut Commands,
+        world: &mut World,
+    ) {
+        if !world.resource::<GameModeSettings>().is_3d() {
+            ui.label("Additional views are available only in 3D mode");
+            return;
+        }
+
+        let camera = match self
+            .camera
+            .filter(|camera| world.get_entity(*camera).is_some())
+        {
+            Some(camera) => camera,
+            None => {
+                let camera = spawn_viewport_camera(world, idx);
+                self.preset.apply(world, camera);
+                self.camera = Some(camera);
+                camera
+            }
+        };
+
+        self.viewport_rect = Some(ui.clip_rect());
+        self.shown = true;
+
+        ui.vertical(|ui| {
+            ui.style_mut().visuals.override_text_color = Some(TEXT_COLOR);
+
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source(("viewport_preset", idx))
+                    .selected_text(self.preset.name())
+                    .show_ui(ui, |ui| {
+                        for preset in ViewportPreset::ALL {
+                            if ui
+                                .selectable_label(self.preset == preset, preset.name())
+                                .clicked()
+                            {
+                                self.preset = preset;
+                                preset.apply(world, camera);
+                            }
+                        }
+                    });
+            });
+
+            ui.push_id(("viewport", idx), |ui| {
+                world.resource_scope(|world, mut game_view: Mut<GameViewTab>| {
+                    game_view.view_ui(ui, commands, world, Some(camera), active, false);
+                });
+            });
+        });
+    }
+}
+
+fn spawn_viewport_camera(world: &mut World, idx: usize) -> Entity {
+    // new views look at focus of game view camera
+    let focus = world
+        .query_filtered::<&PanOrbitCamera, (With<EditorCameraMarker>, Without<ViewportCamera>)>()
+        .iter(world)
+        .next()
+        .map_or(Vec3::ZERO, |pan_orbit| pan_orbit.focus);
+    let radius = 10.;
+    let transform =
+        Transform::from_translation(focus + Vec3::new(0., 0., radius)).looking_at(focus, Vec3::Y);
+
+    world
+        .spawn((
+            Camera3dBundle {
+                transform,
+                camera: Camera {
+                    order: idx as isize + 1,
+                    ..default()
+                },
+                ..default()
+            },
+            PanOrbitCamera {
+                focus,
+                radius: Some(radius),
+                alpha: Some(0.),
+                beta: Some(0.),
+                ..default()
+            },
+            EditorCameraMarker,
+            ViewportCamera(idx),
+            Name::from(format!("Editor Camera {}", idx + 2)),
+            PickableBundle::default(),
+            RaycastPickable,
+            RenderLayers::all(),
+        ))
+        .id()
+}
+
+/// Select view under pointer and move [`ActiveEditorCamera`] to its camera.
+/// Active view is kept while any mouse button is pressed, so dragging gizmo or camera does not jump between views
+fn update_active_viewport(
+    mut commands: Commands,
+    mut ctxs: Query<&mut EguiContext, With<PrimaryWindow>>,
+    mouse: Res<Input<MouseButton>>,
+    mut viewports: ResMut<EditorViewports>,
+    main_camera: Query<Entity, (With<EditorCameraMarker>, Without<ViewportCamera>)>,
+    active_cameras: Query<Entity, With<ActiveEditorCamera>>,
+) {
+    if mouse.get_pressed().next().is_none() {
+        let pointer = ctxs
+            .get_single_mut()
+            .ok()
+            .and_then(|mut ctx| ctx.get_mut().pointer_latest_pos());
+        viewports.active = pointer.and_then(|pos| {
+            viewports.views.iter().position(|view| {
+                view.shown
+                    && view.camera.is_some()
+                    && view.viewport_rect.is_some_and(|rect| rect.contains(pos))
+            })
+        });
+    }
+
+    let camera = match viewports.active {
+        Some(idx) => viewports.views[idx].camera,
+        None => main_camera.iter().next(),
+    };
+    for entity in active_cameras.iter() {
+        if Some(entity) != camera {
+            commands.entity(entity).remove::<ActiveEditorCamera>();
+        }
+    }
+    if let Some(camera) = camera.filter(|camera| !active_cameras.contains(*camera)) {
+        commands.entity(camera).insert(ActiveEditorCamera);
+    }
+
+    for view in viewports.views.iter_mut() {
+        view.shown = false;
+    }
+}
+
+/// Enable cameras of shown views and fit them to view rect
+fn sync_viewport_cameras(
+    viewports: Res<EditorViewports>,
+    primary_window: Query<&Window, With<PrimaryWindow>>,
+    egui_settings: Res<bevy_egui_next::EguiSettings>,
+    mut cameras: Query<(&mut Camera, &ViewportCamera)>,
+) {
+    let Ok(window) = primary_window.get_single() else {
+        return;
+    };
+    let scale_factor = (window.scale_factor() * egui_settings.scale_factor) as f32;
+
+    for (mut camera, viewport_camera) in cameras.iter_mut() {
+        let Some(view) = viewports.views.get(viewport_camera.0) else {
+            continue;
+        };
+        let rect = view.viewport_rect.filter(|_| view.shown);
+        if camera.is_active != rect.is_some() {
+            camera.is_active = rect.is_some();
+        }
+        let Some(rect) = rect else {
+            continue;
+        };
+
+        let viewport_pos = rect.left_top().to_vec2() * scale_factor;
+        let viewport_size = rect.size() * scale_factor;
+        let position = UVec2::new(viewport_pos.x as u32, viewport_pos.y as u32);
+        let size = UVec2::new(viewport_size.x as u32, viewport_size.y as u32).max(UVec2::ONE);
+        if camera
+            .viewport
+            .as_ref()
+            .map(|v| (v.physical_position, v.physical_size))
+            != Some((position, size))
+        {
+            camera.viewport = Some(bevy::render::camera::Viewport {
+                physical_position: position,
+                physical_size: size,
+                depth: 0.0..1.0,
+            });
+        }
+    }
+}
+
+/// Menu to split game view into several views
+pub fn viewport_layout_menu(ui: &mut egui::Ui, world: &mut World) {
+    for layout in ViewportLayout::ALL {
+        if ui.button(layout.name()).clicked() {
+            world.resource_mut::<EditorViewports>().requested_layout = Some(layout);
+            ui.close_menu();
+        }
+    }
+}
+
+/// Rebuild dock around game view. Dock can not be changed while editor ui is shown
+fn apply_viewport_layout(mut viewports: ResMut<EditorViewports>, mut editor_ui: ResMut<EditorUi>) {
+    let Some(layout) = viewports.requested_layout.take() else {
+        return;
+    };
+    let tree = &mut editor_ui.tree;
+    for idx in 0..EXTRA_VIEWPORTS {
+        if let Some(location) = tree.find_tab(&EditorTabName::Viewport(idx)) {
+            tree.remove_tab(location);
+        }
+    }
+
+    let Some((surface, game_node, _)) = tree.find_tab(&EditorTabName::GameView) else {
+        return;
+    };
+    let Some(nodes) = tree
+        .get_surface_mut(surface)
+        .and_then(|surface| surface.node_tree_mut())
+    else {
+        return;
+    };
+    match layout {
+        ViewportLayout::Single => {}
+        ViewportLayout::Two => {
+            nodes.split_right(game_node, 0.5, vec![EditorTabName::Viewport(0)]);
+        }
+        ViewportLayout::Four => {
+            let [game_node, right] =
+                nodes.split_right(game_node, 0.5, vec![EditorTabName::Viewport(0)]);
+            nodes.split_below(game_node, 0.5, vec![EditorTabName::Viewport(1)]);
+            nodes.split_below(right, 0.5, vec![EditorTabName::Viewport(2)]);
+        }
+    }
+}
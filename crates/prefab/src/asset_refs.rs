@@ -0,0 +1,226 @@
+use std::{any::TypeId, collections::BTreeMap, path::Path};
+
+use bevy::{prelude::*, reflect::GetPath};
+use space_shared::PrefabMarker;
+
+use crate::editor_registry::EditorRegistry;
+
+/// Reference to asset file from string field of prefab component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetReference {
+    pub entity: Entity,
+    pub component: TypeId,
+    pub component_name: String,
+    pub field: String,
+    pub path: String,
+}
+
+impl AssetReference {
+    /// Path of asset file without label
+    pub fn file_path(&self) -> &str {
+        strip_label(&self.path)
+    }
+}
+
+/// Remove label from asset path (`models/tree.glb#Scene0` -> `models/tree.glb`)
+pub fn strip_label(path: &str) -> &str {
+    path.split('#').next().unwrap_or(path)
+}
+
+/// Collect all non empty paths from registered asset path fields of prefab entities
+pub fn collect_asset_references(world: &mut World) -> Vec<AssetReference> {
+    let asset_paths = world.resource::<EditorRegistry>().asset_paths.clone();
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let entities = world
+        .query_filtered::<Entity, With<PrefabMarker>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+
+    let mut references = vec![];
+    for entity in entities {
+        for (type_id, fields) in asset_paths.iter() {
+            let Some(registration) = type_registry.get(*type_id) else {
+                continue;
+            };
+            let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+                continue;
+            };
+            let Some(component) = reflect_component.reflect(world.entity(entity)) else {
+                continue;
+            };
+            for field in fields.iter() {
+                let Some(path) = component
+                    .reflect_path(field.as_str())
+                    .ok()
+                    .and_then(|value| value.downcast_ref::<String>())
+                else {
+                    continue;
+                };
+                if path.is_empty() {
+                    continue;
+                }
+                references.push(AssetReference {
+                    entity,
+                    component: *type_id,
+                    component_name: registration
+                        .type_info()
+                        .type_path_table()
+                        .short_path()
+                        .to_string(),
+                    field: field.clone(),
+                    path: path.clone(),
+                });
+            }
+        }
+    }
+    references
+}
+
+/// Find references to not existing asset files. References are grouped by file path
+pub fn find_broken_references(
+    world: &mut World,
+    asset_folder: &Path,
+) -> BTreeMap<String, Vec<AssetReference>> {
+    let mut broken: BTreeMap<String, Vec<AssetReference>> = BTreeMap::new();
+    for reference in collect_asset_references(world) {
+        if !asset_folder.join(reference.file_path()).exists() {
+            broken
+                .entry(reference.file_path().to_string())
+                .or_default()
+                .push(reference);
+        }
+    }
+    broken
+}
+
+/// Replace asset file path in all registered asset path fields of prefab entities.
+/// Label of path is kept (`old.glb#Scene0` -> `new.glb#Scene0`). Returns count of changed fields.
+///
+/// Changed copy of component is applied to entity, so auto undo records relink as one change
+pub fn relink_asset_path(world: &mut World, from: &str, to: &str) -> usize {
+    let references = collect_asset_references(world)
+        .into_iter()
+        .filter(|reference| reference.file_path() == from)
+        .collect::<Vec<_>>();
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let mut changed = 0;
+    for reference in references {
+        let Some(reflect_component) = type_registry
+            .get(reference.component)
+            .and_then(|registration| registration.data::<ReflectComponent>())
+        else {
+            continue;
+        };
+        let Some(mut component) = reflect_component
+            .reflect(world.entity(reference.entity))
+            .map(|component| component.clone_value())
+        else {
+            continue;
+        };
+        let Some(path) = component
+            .reflect_path_mut(reference.field.as_str())
+            .ok()
+            .and_then(|value| value.downcast_mut::<String>())
+        else {
+            continue;
+        };
+        *path = format!("{}{}", to, &reference.path[from.len()..]);
+        reflect_component.apply(&mut world.entity_mut(reference.entity), component.as_ref());
+        changed += 1;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use space_undo::{AppAutoUndo, ChangeChain, UndoMarker, UndoPlugin, UndoRedo};
+
+    use crate::{component::GltfPrefab, editor_registry::EditorRegistryExt};
+
+    fn app_with_gltf(path: &str) -> (App, Entity) {
+        let mut app = App::new();
+        app.init_resource::<EditorRegistry>();
+        app.register_type::<GltfPrefab>();
+        app.editor_asset_path::<GltfPrefab>("path");
+        let entity = app
+            .world
+            .spawn((
+                PrefabMarker,
+                GltfPrefab {
+                    path: path.to_string(),
+                    scene: "Scene0".to_string(),
+                },
+            ))
+            .id();
+        (app, entity)
+    }
+
+    #[test]
+    fn missing_files_are_reported() {
+        let (mut app, entity) = app_with_gltf("moved/ship.glb#Scene0");
+
+        let broken = find_broken_references(&mut app.world, Path::new("not_existing_assets"));
+
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken["moved/ship.glb"][0].entity, entity);
+        assert_eq!(broken["moved/ship.glb"][0].field, "path");
+    }
+
+    #[test]
+    fn relink_is_one_undo_change() {
+        let (mut app, entity) = app_with_gltf("moved/ship.glb#Scene0");
+        let second = app
+            .world
+            .spawn((
+                PrefabMarker,
+                GltfPrefab {
+                    path: "moved/ship.glb".to_string(),
+                    scene: "Scene0".to_string(),
+                },
+                UndoMarker,
+            ))
+            .id();
+        app.add_plugins((MinimalPlugins, UndoPlugin));
+        app.auto_reflected_undo::<GltfPrefab>();
+        app.world.entity_mut(entity).insert(UndoMarker);
+        // wait until undo ignore of just added components is over
+        for _ in 0..15 {
+            app.update();
+        }
+        app.world.resource_mut::<ChangeChain>().changes.clear();
+
+        relink_asset_path(&mut app.world, "moved/ship.glb", "ships/ship.glb");
+        for _ in 0..5 {
+            app.update();
+        }
+        assert_eq!(app.world.resource::<ChangeChain>().changes.len(), 1);
+
+        app.world.send_event(UndoRedo::Undo);
+        app.update();
+        assert_eq!(
+            app.world.get::<GltfPrefab>(entity).unwrap().path,
+            "moved/ship.glb#Scene0"
+        );
+        assert_eq!(
+            app.world.get::<GltfPrefab>(second).unwrap().path,
+            "moved/ship.glb"
+        );
+    }
+
+    #[test]
+    fn relink_keeps_label() {
+        let (mut app, entity) = app_with_gltf("moved/ship.glb#Scene0");
+
+        let changed = relink_asset_path(&mut app.world, "moved/ship.glb", "ships/ship.glb");
+
+        assert_eq!(changed, 1);
+        assert_eq!(
+            app.world.get::<GltfPrefab>(entity).unwrap().path,
+            "ships/ship.glb#Scene0"
+        );
+    }
+}
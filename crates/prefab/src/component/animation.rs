@@ -0,0 +1,78 @@
+use bevy::{
+    animation::{AnimationClip, AnimationPlayer},
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+    render::mesh::skinning::{SkinnedMesh, SkinnedMeshInverseBindposes},
+};
+
+/// Prefab component that store paths to animation clips (`model.gltf#Animation0`) for [`AnimationPlayer`]
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component, Default)]
+pub struct AnimationPlayerPrefab {
+    pub clips: Vec<String>,
+    /// Index of clip which will be played in loop in game mode
+    pub autoplay: Option<usize>,
+}
+
+/// Prefab component that store skin of mesh: path to inverse bindposes (`model.gltf#Skin0/InverseBindMatrices`) and joint entities
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component, Default, MapEntities)]
+pub struct SkinnedMeshPrefab {
+    pub inverse_bindposes: String,
+    pub joints: Vec<Entity>,
+}
+
+impl MapEntities for SkinnedMeshPrefab {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.joints = self
+            .joints
+            .iter()
+            .map(|e| entity_mapper.get_or_reserve(*e))
+            .collect();
+    }
+}
+
+/// System to create [`AnimationPlayer`] from [`AnimationPlayerPrefab`]. Runs only in game mode to not animate prefab in editor
+pub fn sync_animation_player(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &AnimationPlayerPrefab),
+        Or<(Changed<AnimationPlayerPrefab>, Without<AnimationPlayer>)>,
+    >,
+    asset_server: Res<AssetServer>,
+) {
+    for (e, prefab) in query.iter() {
+        let mut player = AnimationPlayer::default();
+        if let Some(clip) = prefab.autoplay.and_then(|idx| prefab.clips.get(idx)) {
+            player
+                .play(asset_server.load::<AnimationClip>(clip))
+                .repeat();
+        }
+        commands.entity(e).insert(player);
+    }
+}
+
+/// System to sync [`SkinnedMesh`] and [`SkinnedMeshPrefab`]
+pub fn sync_skinned_mesh(
+    mut commands: Commands,
+    query: Query<(Entity, &SkinnedMeshPrefab), Changed<SkinnedMeshPrefab>>,
+    mut deleted: RemovedComponents<SkinnedMeshPrefab>,
+    asset_server: Res<AssetServer>,
+) {
+    for (e, prefab) in query.iter() {
+        commands.entity(e).insert(SkinnedMesh {
+            inverse_bindposes: asset_server
+                .load::<SkinnedMeshInverseBindposes>(&prefab.inverse_bindposes),
+            joints: prefab.joints.clone(),
+        });
+    }
+
+    for e in deleted.read() {
+        if let Some(mut cmd) = commands.get_entity(e) {
+            cmd.remove::<SkinnedMesh>();
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
+
+/// Placement of array copies
+#[derive(Reflect, Clone, PartialEq, Debug, Default)]
+#[reflect(Default)]
+pub enum ArrayShape {
+    /// Each copy is moved by `offset` from previous one
+    #[default]
+    Linear,
+    /// Copies fill grid with `counts` cells per axis, `offset` is cell size. `count` is ignored
+    Grid { counts: UVec3 },
+    /// Copies are rotated around `axis` through `center` (both in entity space) and spread on `angle` degrees
+    Radial {
+        center: Vec3,
+        axis: Vec3,
+        angle: f32,
+    },
+    /// Copies are spread evenly along polyline, which starts at entity and goes through `points` (relative to entity position)
+    Path { points: Vec<Vec3>, align: bool },
+}
+
+/// Prefab component which keeps copies of entity in array. Copies are marked with [`ArrayCopy`]
+/// and regenerated by editor, when parameters or entity transform change
+///
+/// Offset, rotation (degrees) and scale steps are accumulated for each next copy in entity space
+#[derive(Component, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Component, Default)]
+pub struct ArrayModifier {
+    pub shape: ArrayShape,
+    /// Count of items including entity itself
+    pub count: u32,
+    pub offset: Vec3,
+    pub rotation: Vec3,
+    pub scale: Vec3,
+}
+
+impl Default for ArrayModifier {
+    fn default() -> Self {
+        Self {
+            shape: ArrayShape::Linear,
+            count: 3,
+            offset: Vec3::X * 2.,
+            rotation: Vec3::ZERO,
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+/// Marker of entity generated by [`ArrayModifier`] of `source` entity
+#[derive(Component, Reflect, Clone, PartialEq, Eq, Debug)]
+#[reflect(Component, Default, MapEntities)]
+pub struct ArrayCopy {
+    pub source: Entity,
+    /// Index of copy in [`ArrayModifier::transforms`]
+    pub index: usize,
+}
+
+impl Default for ArrayCopy {
+    fn default() -> Self {
+        Self {
+            source: Entity::PLACEHOLDER,
+            index: 0,
+        }
+    }
+}
+
+impl MapEntities for ArrayCopy {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.source = entity_mapper.get_or_reserve(self.source);
+    }
+}
+
+impl ArrayModifier {
+    /// Count of copies without source entity
+    pub const fn copies_count(&self) -> usize {
+        match &self.shape {
+            ArrayShape::Grid { counts } => {
+                (counts.x * counts.y * counts.z).saturating_sub(1) as usize
+            }
+            _ => self.count.saturating_sub(1) as usize,
+        }
+    }
+
+    /// Local transforms of copies for source entity transform. Copies are siblings of source
+    pub fn transforms(&self, source: &Transform) -> Vec<Transform> {
+        let stepped = |offset: Vec3, step: f32| Transform {
+            translation: source.translation + source.rotation * offset,
+            rotation: source.rotation
+                * Quat::from_euler(
+                    EulerRot::XYZ,
+                    (self.rotation.x * step).to_radians(),
+                    (self.rotation.y * step).to_radians(),
+                    (self.rotation.z * step).to_radians(),
+                ),
+            scale: source.scale * self.scale.powf(step),
+        };
+        let count = self.copies_count();
+
+        match &self.shape {
+            ArrayShape::Linear => (1..=count)
+                .map(|i| stepped(self.offset * i as f32, i as f32))
+                .collect(),
+            ArrayShape::Grid { counts } => {
+                let mut transforms = vec![];
+                for z in 0..counts.z {
+                    for y in 0..counts.y {
+                        for x in 0..counts.x {
+                            let cell = UVec3::new(x, y, z);
+                            if cell == UVec3::ZERO {
+                                continue;
+                            }
+                            let step = transforms.len() as f32 + 1.;
+                            transforms.push(stepped(self.offset * cell.as_vec3(), step));
+                        }
+                    }
+                }
+                transforms
+            }
+            ArrayShape::Radial {
+                center,
+                axis,
+                angle,
+            } => {
+                let pivot = source.translation + source.rotation * *center;
+                let axis = (source.rotation * *axis).try_normalize().unwrap_or(Vec3::Y);
+                // full circle must not put last copy onto source
+                let spacing = if (angle.abs() - 360.).abs() < f32::EPSILON {
+                    angle / (count + 1) as f32
+                } else {
+                    angle / count.max(1) as f32
+                };
+                (1..=count)
+                    .map(|i| {
+                        let rotation =
+                            Quat::from_axis_angle(axis, (spacing * i as f32).to_radians());
+                        let base = stepped(self.offset * i as f32, i as f32);
+                        Transform {
+                            translation: pivot + rotation * (base.translation - pivot),
+                            rotation: rotation * base.rotation,
+                            scale: base.scale,
+                        }
+                    })
+                    .collect()
+            }
+            ArrayShape::Path { points, align } => {
+                let path = std::iter::once(Vec3::ZERO)
+                    .chain(points.iter().copied())
+                    .collect::<Vec<_>>();
+                let length = path.windows(2).map(|s| s[0].distance(s[1])).sum::<f32>();
+                let start_tangent = path
+                    .windows(2)
+                    .find_map(|s| (s[1] - s[0]).try_normalize())
+                    .unwrap_or(Vec3::X);
+
+                (1..=count)
+                    .map(|i| {
+                        let (position, tangent) =
+                            point_on_path(&path, length * i as f32 / count as f32);
+                        let mut transform = stepped(self.offset * i as f32, i as f32);
+                        transform.translation += position;
+                        if *align {
+                            transform.rotation = Quat::from_rotation_arc(start_tangent, tangent)
+                                * transform.rotation;
+                        }
+                        transform
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Point and direction of polyline at distance from start
+fn point_on_path(path: &[Vec3], distance: f32) -> (Vec3, Vec3) {
+    let mut left = distance;
+    let mut last = (path.last().copied().unwrap_or_default(), Vec3::X);
+    for segment in path.windows(2) {
+        let length = segment[0].distance(segment[1]);
+        let Some(tangent) = (segment[1] - segment[0]).try_normalize() else {
+            continue;
+        };
+        if left <= length {
+            return (segment[0] + tangent * left, tangent);
+        }
+        left -= length;
+        last = (segment[1], tangent);
+    }
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: Vec3, b: Vec3) {
+        assert!(a.distance(b) < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn linear_steps_in_entity_space() {
+        let modifier = ArrayModifier {
+            count: 3,
+            offset: Vec3::X,
+            scale: Vec3::splat(2.),
+            ..default()
+        };
+        let source = Transform::from_xyz(1., 0., 0.)
+            .with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2));
+
+        let transforms = modifier.transforms(&source);
+        assert_eq!(transforms.len(), 2);
+        assert_near(transforms[0].translation, Vec3::new(1., 0., -1.));
+        assert_near(transforms[1].translation, Vec3::new(1., 0., -2.));
+        assert_near(transforms[1].scale, Vec3::splat(4.));
+    }
+
+    #[test]
+    fn grid_skips_source_cell() {
+        let modifier = ArrayModifier {
+            shape: ArrayShape::Grid {
+                counts: UVec3::new(2, 1, 2),
+            },
+            offset: Vec3::ONE,
+            ..default()
+        };
+
+        let transforms = modifier.transforms(&Transform::IDENTITY);
+        assert_eq!(transforms.len(), 3);
+        assert_near(transforms[2].translation, Vec3::new(1., 0., 1.));
+    }
+
+    #[test]
+    fn radial_full_circle() {
+        let modifier = ArrayModifier {
+            shape: ArrayShape::Radial {
+                center: Vec3::new(-1., 0., 0.),
+                axis: Vec3::Y,
+                angle: 360.,
+            },
+            count: 4,
+            offset: Vec3::ZERO,
+            ..default()
+        };
+
+        let transforms = modifier.transforms(&Transform::from_xyz(1., 0., 0.));
+        assert_eq!(transforms.len(), 3);
+        assert_near(transforms[0].translation, Vec3::new(0., 0., -1.));
+        assert_near(transforms[1].translation, Vec3::new(-1., 0., 0.));
+        assert_near(transforms[2].translation, Vec3::new(0., 0., 1.));
+    }
+
+    #[test]
+    fn rotation_and_scale_steps_accumulate() {
+        let modifier = ArrayModifier {
+            count: 3,
+            rotation: Vec3::new(0., 90., 0.),
+            scale: Vec3::new(1., 0.5, 1.),
+            ..default()
+        };
+
+        let transforms = modifier.transforms(&Transform::IDENTITY);
+        assert_eq!(modifier.copies_count(), 2);
+        assert_near(transforms[0].rotation * Vec3::X, Vec3::NEG_Z);
+        assert_near(transforms[1].rotation * Vec3::X, Vec3::NEG_X);
+        assert_near(transforms[1].scale, Vec3::new(1., 0.25, 1.));
+    }
+
+    #[test]
+    fn radial_partial_angle_ends_at_angle() {
+        let modifier = ArrayModifier {
+            shape: ArrayShape::Radial {
+                center: Vec3::new(-1., 0., 0.),
+                axis: Vec3::Y,
+                angle: 90.,
+            },
+            count: 3,
+            offset: Vec3::ZERO,
+            ..default()
+        };
+
+        let transforms = modifier.transforms(&Transform::from_xyz(1., 0., 0.));
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert_near(transforms[0].translation, Vec3::new(half, 0., -half));
+        assert_near(transforms[1].translation, Vec3::new(0., 0., -1.));
+    }
+
+    #[test]
+    fn grid_copies_count() {
+        let grid = |counts| ArrayModifier {
+            shape: ArrayShape::Grid { counts },
+            ..default()
+        };
+        assert_eq!(grid(UVec3::splat(2)).copies_count(), 7);
+        assert_eq!(grid(UVec3::new(3, 0, 1)).copies_count(), 0);
+        assert!(grid(UVec3::new(3, 0, 1))
+            .transforms(&Transform::IDENTITY)
+            .is_empty());
+    }
+
+    #[test]
+    fn path_spreads_copies_evenly() {
+        let modifier = ArrayModifier {
+            shape: ArrayShape::Path {
+                points: vec![Vec3::new(2., 0., 0.), Vec3::new(2., 0., 2.)],
+                align: true,
+            },
+            count: 3,
+            offset: Vec3::ZERO,
+            ..default()
+        };
+
+        let transforms = modifier.transforms(&Transform::IDENTITY);
+        assert_eq!(transforms.len(), 2);
+        assert_near(transforms[0].translation, Vec3::new(2., 0., 0.));
+        assert_near(transforms[1].translation, Vec3::new(2., 0., 2.));
+        assert_near(transforms[1].rotation * Vec3::X, Vec3::Z);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::ext::*;
+
+/// Saved editor camera position. Orbit parameters follow `PanOrbitCamera` convention:
+/// camera looks at `focus` from `radius` distance, rotated by `alpha` (yaw) and `beta` (pitch) radians
+#[derive(Reflect, Clone, Debug, PartialEq)]
+#[reflect(Default)]
+pub struct CameraBookmark {
+    pub name: String,
+    pub focus: Vec3,
+    pub alpha: f32,
+    pub beta: f32,
+    pub radius: f32,
+    pub orthographic: bool,
+    /// Visible height of orthographic view
+    pub scale: f32,
+}
+
+impl Default for CameraBookmark {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            focus: Vec3::ZERO,
+            alpha: 0.,
+            beta: 0.,
+            radius: 5.,
+            orthographic: false,
+            scale: 5.,
+        }
+    }
+}
+
+/// Editor camera bookmarks of scene
+#[derive(Component, Reflect, Clone, Default, Debug, PartialEq)]
+#[reflect(Component, Default)]
+pub struct CameraBookmarks {
+    pub bookmarks: Vec<CameraBookmark>,
+}
@@ -0,0 +1,185 @@
+use bevy::ecs::system::SystemParam;
+use space_shared::PrefabMarker;
+
+use super::{EntityLayer, SceneLayers};
+use crate::ext::*;
+
+/// Entity and its children are not drawn in editor viewport.
+///
+/// [`Visibility`] is not changed, so game and saved scene are not affected
+#[derive(Component, Reflect, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct EditorHidden;
+
+/// Entity and its children can not be picked in viewport and are not changed by editor tools
+#[derive(Component, Reflect, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct EditorLocked;
+
+/// Own flag of entity: it has flag component or belongs to layer with the flag
+fn entity_flag(
+    flagged: bool,
+    member: Option<&EntityLayer>,
+    layers: Option<&SceneLayers>,
+    layer_flag: fn(&SceneLayers, &str) -> bool,
+) -> bool {
+    flagged
+        || member
+            .zip(layers)
+            .is_some_and(|(member, layers)| layer_flag(layers, &member.layer))
+}
+
+/// Entity or one of its ancestors has own flag
+fn inherited_flag(
+    mut entity: Entity,
+    parent: impl Fn(Entity) -> Option<Entity>,
+    flag: impl Fn(Entity) -> bool,
+) -> bool {
+    loop {
+        if flag(entity) {
+            return true;
+        }
+        match parent(entity) {
+            Some(parent) => entity = parent,
+            None => return false,
+        }
+    }
+}
+
+fn world_flag<T: Component>(
+    world: &World,
+    layers: &SceneLayers,
+    entity: Entity,
+    layer_flag: fn(&SceneLayers, &str) -> bool,
+) -> bool {
+    inherited_flag(
+        entity,
+        |entity| world.get::<Parent>(entity).map(Parent::get),
+        |entity| {
+            entity_flag(
+                world.get::<T>(entity).is_some(),
+                world.get::<EntityLayer>(entity),
+                Some(layers),
+                layer_flag,
+            )
+        },
+    )
+}
+
+/// Entity is hidden in editor by own or ancestor [`EditorHidden`] flag or by hidden layer
+pub fn is_hidden(world: &World, layers: &SceneLayers, entity: Entity) -> bool {
+    world_flag::<EditorHidden>(world, layers, entity, SceneLayers::is_hidden)
+}
+
+/// Entity is locked in editor by own or ancestor [`EditorLocked`] flag or by locked layer
+pub fn is_locked(world: &World, layers: &SceneLayers, entity: Entity) -> bool {
+    world_flag::<EditorLocked>(world, layers, entity, SceneLayers::is_locked)
+}
+
+/// [`EditorHidden`] and [`EditorLocked`] flags of entities, including flags of their layers.
+/// Flags of ancestors are applied to descendants
+#[derive(SystemParam)]
+pub struct EditorFlags<'w, 's> {
+    pub hidden: Query<'w, 's, (), With<EditorHidden>>,
+    pub locked: Query<'w, 's, (), With<EditorLocked>>,
+    layers: Query<'w, 's, &'static SceneLayers, With<PrefabMarker>>,
+    members: Query<'w, 's, &'static EntityLayer>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+
+impl EditorFlags<'_, '_> {
+    pub fn is_hidden(&self, entity: Entity) -> bool {
+        self.flag(
+            entity,
+            |entity| self.hidden.contains(entity),
+            SceneLayers::is_hidden,
+        )
+    }
+
+    pub fn is_locked(&self, entity: Entity) -> bool {
+        self.flag(
+            entity,
+            |entity| self.locked.contains(entity),
+            SceneLayers::is_locked,
+        )
+    }
+
+    fn flag(
+        &self,
+        entity: Entity,
+        flagged: impl Fn(Entity) -> bool,
+        layer_flag: fn(&SceneLayers, &str) -> bool,
+    ) -> bool {
+        let layers = self.layers.iter().next();
+        inherited_flag(
+            entity,
+            |entity| self.parents.get(entity).ok().map(Parent::get),
+            |entity| {
+                entity_flag(
+                    flagged(entity),
+                    self.members.get(entity).ok(),
+                    layers,
+                    layer_flag,
+                )
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::{scene_layers, SceneLayer};
+
+    #[test]
+    fn flags_are_inherited_by_descendants() {
+        let mut app = App::new();
+        let root = app.world.spawn(EditorLocked).id();
+        let child = app.world.spawn_empty().id();
+        let other = app.world.spawn_empty().id();
+        app.world.entity_mut(root).add_child(child);
+
+        let layers = SceneLayers::default();
+        assert!(is_locked(&app.world, &layers, child));
+        assert!(!is_locked(&app.world, &layers, other));
+        assert!(!is_hidden(&app.world, &layers, child));
+
+        let mut state = bevy::ecs::system::SystemState::<EditorFlags>::new(&mut app.world);
+        let flags = state.get(&app.world);
+        assert!(flags.is_locked(child));
+        assert!(!flags.is_locked(other));
+        assert!(!flags.is_hidden(root));
+    }
+
+    #[test]
+    fn layer_flags_apply_to_members() {
+        let mut app = App::new();
+        app.world.spawn((
+            SceneLayers {
+                layers: vec![SceneLayer {
+                    name: "Collision".to_string(),
+                    visible: false,
+                    ..default()
+                }],
+            },
+            PrefabMarker,
+        ));
+        let member = app
+            .world
+            .spawn(EntityLayer {
+                layer: "Collision".to_string(),
+            })
+            .id();
+        let other = app.world.spawn_empty().id();
+
+        let layers = scene_layers(&mut app.world);
+        assert!(is_hidden(&app.world, &layers, member));
+        assert!(!is_hidden(&app.world, &layers, other));
+        assert!(!is_locked(&app.world, &layers, member));
+
+        let mut state = bevy::ecs::system::SystemState::<EditorFlags>::new(&mut app.world);
+        let flags = state.get(&app.world);
+        assert!(flags.is_hidden(member));
+        assert!(!flags.is_hidden(other));
+    }
+}
@@ -0,0 +1,259 @@
+use bevy::{render::view::RenderLayers, utils::HashSet};
+use space_shared::PrefabMarker;
+
+use crate::ext::*;
+
+/// Named group of entities, independent from hierarchy
+#[derive(Reflect, Clone, Debug, PartialEq, Eq)]
+#[reflect(Default)]
+pub struct SceneLayer {
+    pub name: String,
+    /// Members are drawn in editor viewport
+    pub visible: bool,
+    /// Members can not be picked and changed by editor tools
+    pub locked: bool,
+    /// [`RenderLayers`] layer of members in game
+    pub render_layer: Option<u8>,
+}
+
+impl Default for SceneLayer {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            visible: true,
+            locked: false,
+            render_layer: None,
+        }
+    }
+}
+
+/// Layers of scene. Kept on one prefab entity and saved with prefab
+#[derive(Component, Reflect, Clone, Default, Debug, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct SceneLayers {
+    pub layers: Vec<SceneLayer>,
+}
+
+impl SceneLayers {
+    pub fn get(&self, name: &str) -> Option<&SceneLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    pub fn is_hidden(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|layer| !layer.visible)
+    }
+
+    pub fn is_locked(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|layer| layer.locked)
+    }
+
+    /// Render layers of layer members, if layer is mapped to render layer
+    pub fn render_layers(&self, name: &str) -> Option<RenderLayers> {
+        self.get(name)?.render_layer.map(RenderLayers::layer)
+    }
+}
+
+/// Layer of entity. Children of entity belong to the same layer
+#[derive(Component, Reflect, Clone, Default, Debug, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct EntityLayer {
+    pub layer: String,
+}
+
+/// Layers of current scene
+pub fn scene_layers(world: &mut World) -> SceneLayers {
+    world
+        .query_filtered::<&SceneLayers, With<PrefabMarker>>()
+        .iter(world)
+        .next()
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Marks [`RenderLayers`] inserted by [`apply_layer_render_layers`].
+/// Only marked render layers are removed when layer mapping is gone
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct LayerRenderLayers;
+
+/// Members of layers with render layer and their descendants get [`RenderLayers`] of closest member ancestor.
+///
+/// Render layers are removed again when entity leaves layer or layer mapping is cleared.
+/// Runs only in game mode, so editor cameras show all layers
+pub fn apply_layer_render_layers(
+    mut commands: Commands,
+    layers: Query<Ref<SceneLayers>>,
+    members: Query<(Entity, Ref<EntityLayer>)>,
+    applied: Query<(Entity, &RenderLayers), With<LayerRenderLayers>>,
+    reparented: Query<Entity, Changed<Parent>>,
+    mut removed_members: RemovedComponents<EntityLayer>,
+    mut removed_parents: RemovedComponents<Parent>,
+    children: Query<&Children>,
+    parents: Query<&Parent>,
+    entities: Query<()>,
+) {
+    let removed = removed_members
+        .read()
+        .chain(removed_parents.read())
+        .filter(|entity| entities.contains(*entity))
+        .collect::<Vec<_>>();
+
+    let Some(layers) = layers.iter().next() else {
+        for (entity, _) in applied.iter() {
+            commands
+                .entity(entity)
+                .remove::<(RenderLayers, LayerRenderLayers)>();
+        }
+        return;
+    };
+
+    // entities whose subtree must be updated
+    let roots: Vec<Entity> = if layers.is_changed() {
+        members
+            .iter()
+            .map(|(entity, _)| entity)
+            .chain(applied.iter().map(|(entity, _)| entity))
+            .collect()
+    } else {
+        members
+            .iter()
+            .filter(|(_, layer)| layer.is_changed())
+            .map(|(entity, _)| entity)
+            // children spawned later, for example gltf scenes, get render layers of their members
+            .chain(reparented.iter())
+            .chain(removed)
+            .collect()
+    };
+
+    let render_layers = |entity: Entity| {
+        std::iter::once(entity)
+            .chain(parents.iter_ancestors(entity))
+            .find_map(|entity| members.get(entity).ok())
+            .and_then(|(_, member)| layers.render_layers(&member.layer))
+    };
+
+    let mut visited = HashSet::new();
+    for root in roots {
+        if visited.contains(&root) {
+            continue;
+        }
+        for entity in std::iter::once(root).chain(children.iter_descendants(root)) {
+            if !visited.insert(entity) {
+                continue;
+            }
+            let current = applied.get(entity).ok().map(|(_, layers)| *layers);
+            match render_layers(entity) {
+                Some(render_layers) if current != Some(render_layers) => {
+                    commands
+                        .entity(entity)
+                        .insert((render_layers, LayerRenderLayers));
+                }
+                None if current.is_some() => {
+                    commands
+                        .entity(entity)
+                        .remove::<(RenderLayers, LayerRenderLayers)>();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn members_get_render_layers_of_mapped_layers() {
+        let mut app = App::new();
+        app.add_systems(Update, apply_layer_render_layers);
+        app.world.spawn(SceneLayers {
+            layers: vec![
+                SceneLayer {
+                    name: "Art".to_string(),
+                    render_layer: Some(2),
+                    ..default()
+                },
+                SceneLayer {
+                    name: "Gameplay".to_string(),
+                    ..default()
+                },
+            ],
+        });
+        let art = app
+            .world
+            .spawn(EntityLayer {
+                layer: "Art".to_string(),
+            })
+            .id();
+        let gameplay = app
+            .world
+            .spawn(EntityLayer {
+                layer: "Gameplay".to_string(),
+            })
+            .id();
+
+        app.update();
+        let child = app.world.spawn_empty().id();
+        app.world.entity_mut(art).add_child(child);
+        app.update();
+
+        assert_eq!(
+            app.world.get::<RenderLayers>(art),
+            Some(&RenderLayers::layer(2))
+        );
+        assert_eq!(
+            app.world.get::<RenderLayers>(child),
+            Some(&RenderLayers::layer(2))
+        );
+        assert!(app.world.get::<RenderLayers>(gameplay).is_none());
+
+        // leaving layer removes render layers from member and its children
+        app.world.entity_mut(art).remove::<EntityLayer>();
+        app.update();
+        assert!(app.world.get::<RenderLayers>(art).is_none());
+        assert!(app.world.get::<RenderLayers>(child).is_none());
+    }
+
+    #[test]
+    fn render_layers_follow_layer_mapping_and_parent() {
+        let mut app = App::new();
+        app.add_systems(Update, apply_layer_render_layers);
+        let holder = app
+            .world
+            .spawn(SceneLayers {
+                layers: vec![SceneLayer {
+                    name: "Art".to_string(),
+                    render_layer: Some(1),
+                    ..default()
+                }],
+            })
+            .id();
+        let member = app
+            .world
+            .spawn(EntityLayer {
+                layer: "Art".to_string(),
+            })
+            .id();
+        let child = app.world.spawn_empty().id();
+        app.world.entity_mut(member).add_child(child);
+        // RenderLayers which are not set by layers are kept
+        let own = app.world.spawn(RenderLayers::layer(3)).id();
+        app.update();
+        assert_eq!(
+            app.world.get::<RenderLayers>(child),
+            Some(&RenderLayers::layer(1))
+        );
+
+        app.world.entity_mut(child).remove_parent();
+        app.update();
+        assert!(app.world.get::<RenderLayers>(child).is_none());
+
+        app.world.get_mut::<SceneLayers>(holder).unwrap().layers[0].render_layer = None;
+        app.update();
+        assert!(app.world.get::<RenderLayers>(member).is_none());
+        assert_eq!(
+            app.world.get::<RenderLayers>(own),
+            Some(&RenderLayers::layer(3))
+        );
+    }
+}
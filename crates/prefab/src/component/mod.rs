@@ -14,10 +14,34 @@ pub use sprite::*;
 pub mod camera;
 pub use camera::*;
 
+/// Module contatins structures for determining editor camera bookmarks
+pub mod camera_bookmarks;
+pub use camera_bookmarks::*;
+
+/// Module contatins structures for determining skeletal animation and skins
+pub mod animation;
+pub use animation::*;
+
 /// Module contatins structures for determining player start
 pub mod player_start;
 pub use player_start::*;
 
+/// Module contatins structures for determining named selection sets
+pub mod selection_sets;
+pub use selection_sets::*;
+
+/// Module contatins structures for determining scene layers
+pub mod layers;
+pub use layers::*;
+
+/// Module contatins structures for determining editor only entity flags
+pub mod editor_flags;
+pub use editor_flags::*;
+
+/// Module contatins structures for determining arrays of entity copies
+pub mod array;
+pub use array::*;
+
 /// NOT USED. Planned to be used in future for auto structs
 pub mod path;
 
@@ -28,6 +52,12 @@ pub mod ext {
     pub use space_shared::ext::*;
 }
 
+/// Marker of prefab entity which only stores scene data, like [`SceneLayers`] or [`CameraBookmarks`].
+/// Such entities are not shown in hierarchy
+#[derive(Component, Reflect, Clone, Copy, Default, Debug)]
+#[reflect(Component, Default)]
+pub struct SceneDataHolder;
+
 /// Component to define path to gltf asset that will be loaded after prefab spawn
 #[derive(Component, Reflect, Clone)]
 #[reflect(Component)]
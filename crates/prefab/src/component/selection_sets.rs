@@ -0,0 +1,24 @@
+use crate::ext::*;
+
+/// Names of editor selection sets which contain this entity
+#[derive(Component, Reflect, Clone, Default, Debug, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct SelectionSets {
+    pub sets: Vec<String>,
+}
+
+impl SelectionSets {
+    pub fn contains(&self, name: &str) -> bool {
+        self.sets.iter().any(|set| set == name)
+    }
+
+    pub fn add(&mut self, name: &str) {
+        if !self.contains(name) {
+            self.sets.push(name.to_string());
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.sets.retain(|set| set != name);
+    }
+}
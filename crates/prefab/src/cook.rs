@@ -0,0 +1,509 @@
+use std::{
+    any::TypeId,
+    collections::BTreeSet,
+    fmt,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use bevy::{
+    prelude::*,
+    reflect::TypeRegistryArc,
+    scene::serde::SceneDeserializer,
+    tasks::IoTaskPool,
+    utils::{HashMap, HashSet},
+};
+use serde::de::DeserializeSeed;
+use space_shared::{AssetFolder, EditorPrefabPath};
+
+use crate::{
+    editor_registry::EditorRegistry,
+    load::PrefabLoader,
+    plugins::BasePrefabPlugin,
+    save::{
+        delete_prepared_children, extract_prefab_scene, prepare_children, ChildrenPrefab,
+        SaveConfig, SaveState,
+    },
+};
+
+/// Plugin to cook editor prefabs into flat runtime scenes
+pub struct CookPlugin;
+
+impl Plugin for CookPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CookRuntimeApp>();
+
+        app.add_systems(
+            OnEnter(SaveState::Cook),
+            (
+                prepare_children,
+                apply_deferred,
+                cook_world_scene,
+                delete_prepared_children,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Describes the runtime (game only) app. Cooked scenes are validated against its type registry.
+/// By default it contains only types from [`BasePrefabPlugin`]
+#[derive(Resource, Clone)]
+pub struct CookRuntimeApp {
+    setups: Vec<Arc<dyn Fn(&mut App) + Send + Sync>>,
+    /// Registry of built runtime app. Reset when new setup is added
+    registry: OnceLock<TypeRegistryArc>,
+}
+
+impl Default for CookRuntimeApp {
+    fn default() -> Self {
+        Self {
+            setups: vec![Arc::new(|app| {
+                app.add_plugins(BasePrefabPlugin);
+            })],
+            registry: OnceLock::new(),
+        }
+    }
+}
+
+impl CookRuntimeApp {
+    /// Add function which registers runtime types in runtime app
+    pub fn add_setup(&mut self, setup: impl Fn(&mut App) + Send + Sync + 'static) {
+        self.setups.push(Arc::new(setup));
+        self.registry = OnceLock::new();
+    }
+
+    /// Type registry of runtime app. App is built without running it on first call
+    pub fn type_registry(&self) -> TypeRegistryArc {
+        self.registry
+            .get_or_init(|| {
+                let mut app = App::new();
+                for setup in self.setups.iter() {
+                    setup(&mut app);
+                }
+                app.world.resource::<AppTypeRegistry>().0.clone()
+            })
+            .clone()
+    }
+}
+
+pub trait CookAppExt {
+    /// Register types which exist in runtime (game only) app.
+    /// Cooked scene can contain only types registered in runtime app
+    fn cook_runtime_types(&mut self, setup: impl Fn(&mut App) + Send + Sync + 'static)
+        -> &mut Self;
+}
+
+impl CookAppExt for App {
+    fn cook_runtime_types(
+        &mut self,
+        setup: impl Fn(&mut Self) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.init_resource::<CookRuntimeApp>();
+        self.world.resource_mut::<CookRuntimeApp>().add_setup(setup);
+        self
+    }
+}
+
+/// Settings for [`cook_scene`]
+#[derive(Clone)]
+pub struct CookSettings {
+    /// Folder to resolve [`PrefabLoader`] paths
+    pub asset_folder: PathBuf,
+    /// Max nesting of prefabs. Used to detect prefabs which include themselves
+    pub max_depth: usize,
+    /// Components to strip from cooked scene
+    pub editor_only: HashSet<TypeId>,
+    /// If set, all components of cooked scene must be registered in this registry
+    pub runtime_registry: Option<TypeRegistryArc>,
+}
+
+impl Default for CookSettings {
+    fn default() -> Self {
+        Self {
+            asset_folder: PathBuf::from("assets"),
+            max_depth: 16,
+            editor_only: HashSet::default(),
+            runtime_registry: None,
+        }
+    }
+}
+
+/// Short statistic of cooking
+#[derive(Default, Debug, Clone)]
+pub struct CookReport {
+    pub entities: usize,
+    pub inlined_prefabs: usize,
+    pub stripped_components: usize,
+}
+
+#[derive(Debug)]
+pub enum CookError {
+    /// Prefab file can not be read or written
+    Io(String, String),
+    /// Prefab file can not be parsed
+    Deserialize(String, String),
+    /// Scene can not be written to world (most often it contains unregistered types)
+    Spawn(String),
+    /// Nested prefabs are deeper than [`CookSettings::max_depth`]
+    PrefabDepth(String),
+    /// Cooked scene contains types which are not registered in runtime app
+    UnregisteredTypes(Vec<String>),
+    /// Cooked scene can not be serialized
+    Serialize(String),
+}
+
+impl fmt::Display for CookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "failed to access {}: {}", path, err),
+            Self::Deserialize(path, err) => write!(f, "failed to parse {}: {}", path, err),
+            Self::Spawn(err) => write!(f, "failed to spawn scene: {}", err),
+            Self::PrefabDepth(path) => write!(
+                f,
+                "prefab {} is nested too deep (is it including itself?)",
+                path
+            ),
+            Self::UnregisteredTypes(types) => write!(
+                f,
+                "types are not registered in runtime app: {}",
+                types.join(", ")
+            ),
+            Self::Serialize(err) => write!(f, "failed to serialize cooked scene: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CookError {}
+
+/// Format of scene file, detected by extension
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SceneFormat {
+    Ron,
+    Json,
+}
+
+impl SceneFormat {
+    pub fn from_path(path: &Path) -> Self {
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::Json
+        } else {
+            Self::Ron
+        }
+    }
+}
+
+/// Read prefab file in format detected by extension and deserialize it to [`DynamicScene`]
+pub fn read_scene_file(path: &Path, registry: &TypeRegistryArc) -> Result<DynamicScene, CookError> {
+    let path_name = path.display().to_string();
+    let data = std::fs::read_to_string(path)
+        .map_err(|err| CookError::Io(path_name.clone(), err.to_string()))?;
+    let registry = registry.read();
+    let scene_deserializer = SceneDeserializer {
+        type_registry: &registry,
+    };
+    let scene = match SceneFormat::from_path(path) {
+        SceneFormat::Ron => {
+            let mut deserializer = ron::de::Deserializer::from_str(&data)
+                .map_err(|err| CookError::Deserialize(path_name.clone(), err.to_string()))?;
+            scene_deserializer
+                .deserialize(&mut deserializer)
+                .map_err(|err| err.to_string())
+        }
+        SceneFormat::Json => {
+            let mut deserializer = serde_json::Deserializer::from_str(&data);
+            scene_deserializer
+                .deserialize(&mut deserializer)
+                .map_err(|err| err.to_string())
+        }
+    };
+    scene.map_err(|err| CookError::Deserialize(path_name, err))
+}
+
+/// Convert prefab to flat runtime scene:
+/// all nested [`PrefabLoader`] prefabs are inlined as children
+/// and all editor only components are stripped
+pub fn cook_scene(
+    scene: &DynamicScene,
+    registry: &TypeRegistryArc,
+    settings: &CookSettings,
+) -> Result<(DynamicScene, CookReport), CookError> {
+    let app_registry = AppTypeRegistry(registry.clone());
+    let mut world = World::new();
+    world.insert_resource(app_registry.clone());
+    let mut report = CookReport::default();
+
+    let mut entity_map = HashMap::new();
+    scene
+        .write_to_world_with(&mut world, &mut entity_map, &app_registry)
+        .map_err(|err| CookError::Spawn(err.to_string()))?;
+
+    // Each pass inlines one level of nested prefabs
+    let mut depth = 0;
+    loop {
+        let loaders = world
+            .query::<(Entity, &PrefabLoader)>()
+            .iter(&world)
+            .map(|(e, loader)| (e, loader.path.clone()))
+            .collect::<Vec<_>>();
+        if loaders.is_empty() {
+            break;
+        }
+
+        depth += 1;
+        if depth > settings.max_depth {
+            return Err(CookError::PrefabDepth(loaders[0].1.clone()));
+        }
+
+        for (entity, path) in loaders {
+            let nested = read_scene_file(&settings.asset_folder.join(&path), registry)?;
+            let mut nested_map = HashMap::new();
+            nested
+                .write_to_world_with(&mut world, &mut nested_map, &app_registry)
+                .map_err(|err| CookError::Spawn(err.to_string()))?;
+
+            let linked = nested_map
+                .values()
+                .filter_map(|e| world.get::<ChildrenPrefab>(*e))
+                .flat_map(|children| children.0.iter().copied())
+                .collect::<HashSet<_>>();
+            let mut roots = nested_map
+                .values()
+                .filter(|e| !linked.contains(*e))
+                .copied()
+                .collect::<Vec<_>>();
+            roots.sort();
+
+            let mut entity_mut = world.entity_mut(entity);
+            entity_mut.remove::<PrefabLoader>();
+            if !entity_mut.contains::<Transform>() {
+                entity_mut.insert(Transform::default());
+            }
+            if !entity_mut.contains::<Visibility>() {
+                entity_mut.insert(Visibility::default());
+            }
+            if let Some(mut children) = entity_mut.get_mut::<ChildrenPrefab>() {
+                children.0.extend(roots);
+            } else {
+                entity_mut.insert(ChildrenPrefab(roots));
+            }
+
+            report.inlined_prefabs += 1;
+        }
+    }
+
+    let entities = world.iter_entities().map(|e| e.id()).collect::<Vec<_>>();
+    report.entities = entities.len();
+
+    {
+        let registry = registry.read();
+        for type_id in settings.editor_only.iter() {
+            let Some(reflect_component) = registry
+                .get(*type_id)
+                .and_then(|reg| reg.data::<ReflectComponent>())
+            else {
+                continue;
+            };
+            for entity in entities.iter() {
+                let mut entity_mut = world.entity_mut(*entity);
+                if entity_mut.contains_type_id(*type_id) {
+                    reflect_component.remove(&mut entity_mut);
+                    report.stripped_components += 1;
+                }
+            }
+        }
+    }
+
+    if let Some(runtime_registry) = &settings.runtime_registry {
+        let runtime_registry = runtime_registry.read();
+        let mut missing = BTreeSet::new();
+        for entity in world.iter_entities() {
+            for component_id in entity.archetype().components() {
+                let Some(info) = world.components().get_info(component_id) else {
+                    continue;
+                };
+                let registered = info
+                    .type_id()
+                    .and_then(|id| runtime_registry.get(id))
+                    .is_some_and(|reg| reg.data::<ReflectComponent>().is_some());
+                if !registered {
+                    missing.insert(info.name().to_string());
+                }
+            }
+        }
+        if !missing.is_empty() {
+            return Err(CookError::UnregisteredTypes(missing.into_iter().collect()));
+        }
+    }
+
+    let cooked = DynamicSceneBuilder::from_world(&world)
+        .extract_entities(entities.into_iter())
+        .build();
+
+    Ok((cooked, report))
+}
+
+/// Cook prefab entities from editor world and save result to [`SaveConfig`] path
+fn cook_world_scene(world: &mut World) {
+    let config = world.resource::<SaveConfig>().clone();
+    let scene = extract_prefab_scene(world);
+    let registry = world.resource::<AppTypeRegistry>().0.clone();
+    let settings = CookSettings {
+        asset_folder: world.resource::<AssetFolder>().0.clone(),
+        editor_only: world.resource::<EditorRegistry>().editor_only.clone(),
+        runtime_registry: Some(world.resource::<CookRuntimeApp>().type_registry()),
+        ..default()
+    };
+
+    world
+        .resource_mut::<NextState<SaveState>>()
+        .set(SaveState::Idle);
+
+    let Some(EditorPrefabPath::File(path)) = config.path else {
+        error!("Cooked scene can be saved only to file");
+        return;
+    };
+
+    let cooked = cook_scene(&scene, &registry, &settings).and_then(|(cooked, report)| {
+        cooked
+            .serialize_ron(&registry)
+            .map(|data| (data, report))
+            .map_err(|err| CookError::Serialize(err.to_string()))
+    });
+
+    match cooked {
+        Ok((data, report)) => {
+            info!(
+                "Cooked {} entities ({} prefabs inlined, {} editor components stripped)",
+                report.entities, report.inlined_prefabs, report.stripped_components
+            );
+            IoTaskPool::get()
+                .spawn(async move {
+                    File::create(&path)
+                        .and_then(|mut file| file.write(data.as_bytes()))
+                        .expect("Error while writing cooked scene to file");
+                    info!("Saved cooked scene to file {}", path);
+                })
+                .detach();
+        }
+        Err(err) => {
+            error!("Failed to cook scene: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::EditorRegistryExt;
+
+    #[derive(Component, Reflect, Default, Clone)]
+    #[reflect(Component, Default)]
+    struct EditorOnlyTag;
+
+    fn editor_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(BasePrefabPlugin);
+        app.editor_registry::<EditorOnlyTag>();
+        app.editor_only::<EditorOnlyTag>();
+        app
+    }
+
+    fn scene_with_tag(app: &mut App) -> DynamicScene {
+        app.world.spawn((Name::new("tagged"), EditorOnlyTag));
+        DynamicSceneBuilder::from_world(&app.world)
+            .allow::<Name>()
+            .allow::<EditorOnlyTag>()
+            .extract_entities(app.world.iter_entities().map(|e| e.id()))
+            .build()
+    }
+
+    #[test]
+    fn asset_folder_is_read_from_asset_plugin() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                file_path: "game_assets".to_string(),
+                ..default()
+            },
+            BasePrefabPlugin,
+        ));
+
+        assert!(app
+            .world
+            .resource::<AssetFolder>()
+            .0
+            .ends_with("game_assets"));
+    }
+
+    #[test]
+    fn cook_strips_editor_only_components() {
+        let mut app = editor_app();
+        let scene = scene_with_tag(&mut app);
+        let registry = app.world.resource::<AppTypeRegistry>().0.clone();
+        let settings = CookSettings {
+            editor_only: app.world.resource::<EditorRegistry>().editor_only.clone(),
+            ..default()
+        };
+
+        let (cooked, report) = cook_scene(&scene, &registry, &settings).unwrap();
+
+        assert_eq!(report.stripped_components, 1);
+        assert_eq!(cooked.entities.len(), 1);
+        assert_eq!(cooked.entities[0].components.len(), 1);
+    }
+
+    #[test]
+    fn cook_reports_types_missing_in_runtime_app() {
+        let mut app = editor_app();
+        let scene = scene_with_tag(&mut app);
+        let registry = app.world.resource::<AppTypeRegistry>().0.clone();
+        let settings = CookSettings {
+            runtime_registry: Some(CookRuntimeApp::default().type_registry()),
+            ..default()
+        };
+
+        let res = cook_scene(&scene, &registry, &settings);
+
+        assert!(matches!(res, Err(CookError::UnregisteredTypes(types)) if types.len() == 1));
+    }
+
+    #[test]
+    fn runtime_registry_is_built_once_per_setup() {
+        let mut runtime = CookRuntimeApp::default();
+
+        let first = runtime.type_registry();
+        let second = runtime.type_registry();
+        runtime.add_setup(|app| {
+            app.register_type::<EditorOnlyTag>();
+        });
+        let third = runtime.type_registry();
+
+        assert!(Arc::ptr_eq(&first.internal, &second.internal));
+        assert!(!Arc::ptr_eq(&first.internal, &third.internal));
+        assert!(third.read().get(TypeId::of::<EditorOnlyTag>()).is_some());
+    }
+
+    #[test]
+    fn json_scene_file_is_read() {
+        let mut app = editor_app();
+        app.world.spawn(EditorOnlyTag);
+        let scene = DynamicSceneBuilder::from_world(&app.world)
+            .extract_entities(app.world.iter_entities().map(|e| e.id()))
+            .build();
+        let registry = app.world.resource::<AppTypeRegistry>().0.clone();
+        let path = std::env::temp_dir().join(format!("space_cook_{}.scn.json", std::process::id()));
+        let data =
+            serde_json::to_string(&bevy::scene::serde::SceneSerializer::new(&scene, &registry))
+                .unwrap();
+        std::fs::write(&path, data).unwrap();
+
+        let res = read_scene_file(&path, &registry);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(res.unwrap().entities.len(), 1);
+    }
+}
@@ -126,6 +126,42 @@ impl SendEvent {
     }
 }
 
+/// Description of component in add component menu
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComponentInfo {
+    pub category: String,
+    /// Pretty type name is used if empty
+    pub display_name: String,
+    /// Doc comment of type is used if empty
+    pub description: String,
+    /// Emoji shown before name
+    pub icon: String,
+}
+
+impl ComponentInfo {
+    pub fn new(category: &str) -> Self {
+        Self {
+            category: category.to_string(),
+            ..default()
+        }
+    }
+
+    pub fn name(mut self, display_name: &str) -> Self {
+        self.display_name = display_name.to_string();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = icon.to_string();
+        self
+    }
+}
+
 /// Resource, which contains all custom editor registry
 #[derive(Default, Resource, Clone)]
 pub struct EditorRegistry {
@@ -135,6 +171,16 @@ pub struct EditorRegistry {
     pub remove_components: HashMap<TypeId, RemoveComponent>,
     pub send_events: Vec<SendEvent>,
     pub silent: HashSet<TypeId>, //skip in inspector ui
+    /// Registered with [`Self::editor_only_register`]
+    pub editor_only: HashSet<TypeId>,
+    /// String fields which contain asset paths
+    pub asset_paths: HashMap<TypeId, Vec<String>>,
+    /// glTF extras keys mapped to components
+    pub gltf_extras: HashMap<String, TypeId>,
+    /// Category, name and description in add component menu
+    pub component_info: HashMap<TypeId, ComponentInfo>,
+    /// Components added automatically with key component
+    pub relations: HashMap<TypeId, Vec<TypeId>>,
 }
 
 impl EditorRegistry {
@@ -184,6 +230,51 @@ impl EditorRegistry {
         self.clone_components.push(CloneComponent::new::<T>());
     }
 
+    /// Mark registered component as editor only. It will be saved in prefab, but stripped from cooked scenes
+    pub fn editor_only_register<T: Component>(&mut self) {
+        self.editor_only.insert(TypeId::of::<T>());
+    }
+
+    /// Check if component type is marked as editor only
+    pub fn is_editor_only(&self, id: &TypeId) -> bool {
+        self.editor_only.contains(id)
+    }
+
+    /// Mark string field of component as asset path. Field is described by reflect path (`"path"`, `"textures[0]"`)
+    pub fn asset_path_register<T: Component>(&mut self, field: &str) {
+        let fields = self.asset_paths.entry(TypeId::of::<T>()).or_default();
+        if !fields.iter().any(|f| f == field) {
+            fields.push(field.to_string());
+        }
+    }
+
+    /// Map key of glTF extras (custom properties of node) to component. Value of key will be parsed as component fields
+    pub fn gltf_extras_register<T: Component>(&mut self, key: &str) {
+        self.gltf_extras.insert(key.to_string(), TypeId::of::<T>());
+    }
+
+    /// Set category, name, description and icon of component in add component menu
+    pub fn component_info_register<T: Component>(&mut self, info: ComponentInfo) {
+        self.component_info.insert(TypeId::of::<T>(), info);
+    }
+
+    /// Remember that `Relation` component is added automatically with `T`
+    pub fn relation_register<T: Component, Relation: Component>(&mut self) {
+        let relations = self.relations.entry(TypeId::of::<T>()).or_default();
+        if !relations.contains(&TypeId::of::<Relation>()) {
+            relations.push(TypeId::of::<Relation>());
+        }
+    }
+
+    /// Parent components, which add this component automatically
+    pub fn relation_parents(&self, id: &TypeId) -> Vec<TypeId> {
+        self.relations
+            .iter()
+            .filter(|(_, relations)| relations.contains(id))
+            .map(|(parent, _)| *parent)
+            .collect()
+    }
+
     /// Get spawn function for this component type
     pub fn get_spawn_command(&self, id: &TypeId) -> AddDefaultComponent {
         self.spawn_components.get(id).unwrap().clone()
@@ -236,6 +327,18 @@ pub trait EditorRegistryExt {
         &mut self,
     ) -> &mut Self;
 
+    /// Mark component as editor only data (will be dropped from cooked scenes)
+    fn editor_only<T: Component>(&mut self) -> &mut Self;
+
+    /// Mark string field of component as asset path (will be checked for broken references)
+    fn editor_asset_path<T: Component>(&mut self, field: &str) -> &mut Self;
+
+    /// Insert component to gltf nodes which have key in extras (custom properties)
+    fn editor_gltf_extras<T: Component>(&mut self, key: &str) -> &mut Self;
+
+    /// Set category, name, description and icon of component in add component menu
+    fn editor_component_info<T: Component>(&mut self, info: ComponentInfo) -> &mut Self;
+
     /// Mark that if T component spawned, then Relation must be spawned too
     fn editor_relation<T, Relation>(&mut self) -> &mut Self
     where
@@ -305,11 +408,42 @@ impl EditorRegistryExt for App {
         self
     }
 
+    fn editor_only<T: Component>(&mut self) -> &mut Self {
+        self.world
+            .resource_mut::<EditorRegistry>()
+            .editor_only_register::<T>();
+        self
+    }
+
+    fn editor_asset_path<T: Component>(&mut self, field: &str) -> &mut Self {
+        self.world
+            .resource_mut::<EditorRegistry>()
+            .asset_path_register::<T>(field);
+        self
+    }
+
+    fn editor_gltf_extras<T: Component>(&mut self, key: &str) -> &mut Self {
+        self.world
+            .resource_mut::<EditorRegistry>()
+            .gltf_extras_register::<T>(key);
+        self
+    }
+
+    fn editor_component_info<T: Component>(&mut self, info: ComponentInfo) -> &mut Self {
+        self.world
+            .resource_mut::<EditorRegistry>()
+            .component_info_register::<T>(info);
+        self
+    }
+
     fn editor_relation<T, Relation>(&mut self) -> &mut Self
     where
         T: Component,
         Relation: Component + Default,
     {
+        if let Some(mut registry) = self.world.get_resource_mut::<EditorRegistry>() {
+            registry.relation_register::<T, Relation>();
+        }
         self.add_systems(
             Update,
             relation_system::<T, Relation>.in_set(PrefabSet::Relation),
@@ -410,7 +544,9 @@ fn relation_system<T: Component, Relation: Component + Default>(
 mod tests {
     use bevy::{ecs::system::CommandQueue, prelude::*};
 
-    use crate::prelude::{EditorRegistry, EditorRegistryExt, EditorRegistryPlugin};
+    use std::any::TypeId;
+
+    use crate::prelude::{ComponentInfo, EditorRegistry, EditorRegistryExt, EditorRegistryPlugin};
 
     /// Test for clone logic in editor registry
     #[test]
@@ -442,4 +578,31 @@ mod tests {
             name
         );
     }
+
+    #[test]
+    fn relations_are_grouped_by_parent() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(EditorRegistryPlugin);
+        app.editor_registry::<Camera3d>()
+            .editor_relation::<Camera3d, Camera>()
+            .editor_relation::<Camera3d, Camera>()
+            .editor_relation::<Camera2d, Camera>()
+            .editor_component_info::<Camera3d>(ComponentInfo::new("Camera").icon("📷"));
+
+        let registry = app.world.resource::<EditorRegistry>();
+        assert_eq!(
+            registry.relations[&TypeId::of::<Camera3d>()],
+            vec![TypeId::of::<Camera>()]
+        );
+        let mut parents = registry.relation_parents(&TypeId::of::<Camera>());
+        parents.sort();
+        let mut expected = vec![TypeId::of::<Camera3d>(), TypeId::of::<Camera2d>()];
+        expected.sort();
+        assert_eq!(parents, expected);
+        assert_eq!(
+            registry.component_info[&TypeId::of::<Camera3d>()].category,
+            "Camera"
+        );
+    }
 }
@@ -0,0 +1,262 @@
+use std::any::TypeId;
+
+use bevy::{
+    gltf::GltfExtras,
+    prelude::*,
+    reflect::{
+        serde::{TypedReflectDeserializer, TypedReflectSerializer},
+        TypeInfo, TypeRegistry, VariantInfo,
+    },
+    utils::HashMap,
+};
+use serde::de::DeserializeSeed;
+use serde_json::{Map, Value};
+
+use crate::editor_registry::EditorRegistry;
+
+/// System to insert components mapped by [`EditorRegistry::gltf_extras`] to entities with new [`GltfExtras`]
+///
+/// Works both for scenes spawned by [`GltfPrefab`](crate::component::GltfPrefab) and for unpacked gltf nodes
+pub fn apply_gltf_extras(
+    mut commands: Commands,
+    query: Query<(Entity, &GltfExtras), Added<GltfExtras>>,
+) {
+    for (entity, extras) in query.iter() {
+        let extras = extras.value.clone();
+        commands.add(move |world: &mut World| {
+            insert_extras_components(world, entity, &extras);
+        });
+    }
+}
+
+/// Parse extras json and insert all mapped components to entity
+pub fn insert_extras_components(world: &mut World, entity: Entity, extras: &str) {
+    let mapping = world.resource::<EditorRegistry>().gltf_extras.clone();
+    if mapping.is_empty() {
+        return;
+    }
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let components = match components_from_extras(extras, &mapping, &type_registry) {
+        Ok(components) => components,
+        Err(err) => {
+            warn!("Failed to parse gltf extras of {:?}: {}", entity, err);
+            return;
+        }
+    };
+
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    for (type_id, component) in components {
+        if let Some(reflect_component) = type_registry
+            .get(type_id)
+            .and_then(|registration| registration.data::<ReflectComponent>())
+        {
+            reflect_component.insert(&mut entity_mut, component.as_reflect());
+        }
+    }
+}
+
+/// Create components from gltf extras json (`{"collider": "FromMesh", "player_start": {"prefab": "player.scn.ron"}}`)
+///
+/// Value of each mapped key is applied over default component:
+/// objects set only listed fields, other values replace the component (unit enum variant, newtype value).
+/// Flags like `"spawn": 1` on struct components insert default component. Keys without mapping are skipped
+pub fn components_from_extras(
+    extras: &str,
+    mapping: &HashMap<String, TypeId>,
+    registry: &TypeRegistry,
+) -> Result<Vec<(TypeId, Box<dyn Reflect>)>, serde_json::Error> {
+    let Value::Object(extras) = serde_json::from_str::<Value>(extras)? else {
+        return Ok(vec![]);
+    };
+
+    let mut components = vec![];
+    for (key, value) in extras {
+        let Some(type_id) = mapping.get(&key) else {
+            continue;
+        };
+        match component_from_value(value, *type_id, registry) {
+            Ok(component) => components.push((*type_id, component)),
+            Err(err) => warn!("Failed to parse gltf extras key {}: {}", key, err),
+        }
+    }
+    Ok(components)
+}
+
+fn component_from_value(
+    value: Value,
+    type_id: TypeId,
+    registry: &TypeRegistry,
+) -> Result<Box<dyn Reflect>, String> {
+    let registration = registry
+        .get(type_id)
+        .ok_or_else(|| "component type is not registered".to_string())?;
+    let default = registration
+        .data::<ReflectDefault>()
+        .ok_or_else(|| {
+            format!(
+                "{} does not reflect Default",
+                registration.type_info().type_path()
+            )
+        })?
+        .default();
+
+    let mut merged = serde_json::to_value(TypedReflectSerializer::new(&*default, registry))
+        .map_err(|err| err.to_string())?;
+    merge_value(&mut merged, value, type_id, registry);
+
+    TypedReflectDeserializer::new(registration, registry)
+        .deserialize(merged)
+        .map_err(|err| err.to_string())
+}
+
+/// Apply json value over serialized reflect value of type
+fn merge_value(base: &mut Value, patch: Value, type_id: TypeId, registry: &TypeRegistry) {
+    match (registry.get_type_info(type_id), &mut *base, patch) {
+        (Some(TypeInfo::Struct(info)), Value::Object(fields), Value::Object(patch)) => {
+            merge_fields(
+                fields,
+                patch,
+                |name| info.field(name).map(|f| f.type_id()),
+                registry,
+            );
+        }
+        // same struct variant of enum keeps not listed fields
+        (Some(TypeInfo::Enum(info)), Value::Object(variant), Value::Object(patch))
+            if variant.len() == 1
+                && patch.len() == 1
+                && patch.keys().next() == variant.keys().next() =>
+        {
+            for (name, patch) in patch {
+                match (info.variant(&name), variant.get_mut(&name), patch) {
+                    (
+                        Some(VariantInfo::Struct(variant_info)),
+                        Some(Value::Object(fields)),
+                        Value::Object(patch),
+                    ) => merge_fields(
+                        fields,
+                        patch,
+                        |name| variant_info.field(name).map(|f| f.type_id()),
+                        registry,
+                    ),
+                    (Some(VariantInfo::Tuple(variant_info)), Some(base_value), patch)
+                        if variant_info.field_len() == 1 =>
+                    {
+                        if let Some(field) = variant_info.field_at(0) {
+                            merge_value(base_value, patch, field.type_id(), registry);
+                        }
+                    }
+                    (_, _, patch) => {
+                        variant.insert(name, patch);
+                    }
+                }
+            }
+        }
+        // flag value on struct component
+        (Some(TypeInfo::Struct(_)), Value::Object(_), Value::Bool(_) | Value::Number(_)) => {}
+        (Some(TypeInfo::TupleStruct(info)), Value::Array(items), patch)
+            if info.field_len() == 1 && items.len() == 1 && !patch.is_array() =>
+        {
+            if let Some(field) = info.field_at(0) {
+                merge_value(&mut items[0], patch, field.type_id(), registry);
+            }
+        }
+        (_, _, patch) => *base = patch,
+    }
+}
+
+fn merge_fields(
+    fields: &mut Map<String, Value>,
+    patch: Map<String, Value>,
+    field_type: impl Fn(&str) -> Option<TypeId>,
+    registry: &TypeRegistry,
+) {
+    for (name, value) in patch {
+        match (field_type(&name), fields.get_mut(&name)) {
+            (Some(type_id), Some(base_value)) => {
+                merge_value(base_value, value, type_id, registry);
+            }
+            _ => {
+                fields.insert(name, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::PlayerStart;
+
+    fn test_registry() -> (TypeRegistry, HashMap<String, TypeId>) {
+        let mut registry = TypeRegistry::default();
+        registry.register::<PlayerStart>();
+        registry.register::<Visibility>();
+        registry.register::<Projection>();
+        registry.register::<PerspectiveProjection>();
+
+        let mut mapping = HashMap::new();
+        mapping.insert("player_start".to_string(), TypeId::of::<PlayerStart>());
+        mapping.insert("visibility".to_string(), TypeId::of::<Visibility>());
+        mapping.insert("projection".to_string(), TypeId::of::<Projection>());
+        (registry, mapping)
+    }
+
+    #[test]
+    fn extras_keys_become_components() {
+        let (registry, mapping) = test_registry();
+
+        let components = components_from_extras(
+            r#"{"player_start": {"prefab": "player.scn.ron"}, "visibility": "Hidden", "blender_id": 7}"#,
+            &mapping,
+            &registry,
+        )
+        .unwrap();
+
+        assert_eq!(components.len(), 2);
+        for (type_id, component) in components {
+            if type_id == TypeId::of::<PlayerStart>() {
+                let start = PlayerStart::from_reflect(&*component).unwrap();
+                assert_eq!(start.prefab, "player.scn.ron");
+            } else {
+                let visibility = Visibility::from_reflect(&*component).unwrap();
+                assert_eq!(visibility, Visibility::Hidden);
+            }
+        }
+    }
+
+    #[test]
+    fn flag_inserts_default_component() {
+        let (registry, mapping) = test_registry();
+
+        let components =
+            components_from_extras(r#"{"player_start": 1}"#, &mapping, &registry).unwrap();
+
+        assert_eq!(components.len(), 1);
+        let start = PlayerStart::from_reflect(&*components[0].1).unwrap();
+        assert_eq!(start.prefab, "");
+    }
+
+    #[test]
+    fn enum_variant_keeps_default_fields() {
+        let (registry, mapping) = test_registry();
+
+        let components = components_from_extras(
+            r#"{"projection": {"Perspective": {"fov": 1.0}}}"#,
+            &mapping,
+            &registry,
+        )
+        .unwrap();
+
+        let Projection::Perspective(perspective) =
+            Projection::from_reflect(&*components[0].1).unwrap()
+        else {
+            panic!("expected perspective projection");
+        };
+        assert_eq!(perspective.fov, 1.0);
+        assert_eq!(perspective.far, PerspectiveProjection::default().far);
+    }
+}
@@ -5,16 +5,26 @@
 #[cfg(all(feature = "f32", feature = "f64"))]
 compile_error!("feature \"f32\" and feature \"f64\" cannot be enabled at the same time");
 
+/// Contains scan and relink of asset paths stored in prefab components
+pub mod asset_refs;
 /// Contains all component for prefab logic
 pub mod component;
+/// Contains tools for cooking prefabs into flat runtime scenes
+pub mod cook;
+/// Contains mapping of glTF extras (custom properties) to prefab components
+pub mod gltf_extras;
 /// Contains systems for loading prefab from file
 pub mod load;
 /// Module contains all prefab plugin extensions
 pub mod plugins;
+/// Contains filters of prefab entities by components and reflected field values
+pub mod query;
 /// Contains systems for saving prefab
 pub mod save;
 /// Contains systems for spawning prefabs
 pub mod spawn_system;
+/// Contains checks of prefab files (unknown types, missing assets, broken entity links)
+pub mod validate;
 
 pub mod editor_registry;
 
@@ -30,6 +40,7 @@ pub mod ext {
 /// All useful structure from this crate
 pub mod prelude {
     pub use crate::component::*;
+    pub use crate::cook::{CookAppExt, CookPlugin, CookRuntimeApp};
     pub use crate::editor_registry::*;
     pub use crate::load::PrefabBundle;
     pub use crate::plugins::*;
@@ -44,6 +44,7 @@ pub struct PrefabAutoChild;
 impl Plugin for LoadPlugin {
     fn build(&self, app: &mut App) {
         app.editor_registry::<PrefabLoader>();
+        app.editor_asset_path::<PrefabLoader>("path");
 
         app.add_systems(
             Update,
@@ -12,11 +12,17 @@ use bevy::{
     },
 };
 use bevy_scene_hook::HookPlugin;
-use space_shared::{LightAreaToggle, PrefabMarker};
+use space_shared::{AssetFolder, LightAreaToggle, PrefabMarker};
+use space_undo::AppAutoUndo;
 
 use crate::{
-    component, editor_registry::EditorRegistryExt, load, prelude::EditorRegistryPlugin, save,
-    spawn_system, EditorState, PrefabSet,
+    component,
+    cook::CookPlugin,
+    editor_registry::{ComponentInfo, EditorRegistryExt},
+    gltf_extras::apply_gltf_extras,
+    load,
+    prelude::EditorRegistryPlugin,
+    save, spawn_system, EditorState, PrefabSet,
 };
 
 use component::*;
@@ -40,6 +46,11 @@ impl Plugin for BasePrefabPlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<EditorState>();
 
+        if !app.world.contains_resource::<AssetFolder>() {
+            let asset_folder = AssetFolder::from_app(app);
+            app.insert_resource(asset_folder);
+        }
+
         if !app.is_plugin_added::<HookPlugin>() {
             app.add_plugins(HookPlugin);
         }
@@ -70,16 +81,26 @@ impl Plugin for BasePrefabPlugin {
         app.editor_registry::<Visibility>();
 
         app.editor_registry::<GltfPrefab>();
+        app.editor_asset_path::<GltfPrefab>("path");
         app.editor_registry::<MaterialPrefab>();
+        app.editor_asset_path::<MaterialPrefab>("base_color_texture");
+        app.editor_asset_path::<MaterialPrefab>("emissive_texture");
+        app.editor_asset_path::<MaterialPrefab>("metallic_roughness_texture");
+        app.editor_asset_path::<MaterialPrefab>("normal_map_texture");
+        app.editor_asset_path::<MaterialPrefab>("occlusion_texture");
+        app.editor_asset_path::<MaterialPrefab>("depth_map");
         app.editor_registry::<ColorMaterialPrefab>();
+        app.editor_asset_path::<ColorMaterialPrefab>("texture");
 
         app.editor_registry::<Sprite>();
         app.editor_registry::<SpriteTexture>();
+        app.editor_asset_path::<SpriteTexture>("texture");
         app.editor_relation::<SpriteTexture, Transform>();
         app.editor_relation::<SpriteTexture, Visibility>();
 
         // Spritesheet bundle
         app.editor_registry::<SpritesheetTexture>();
+        app.editor_asset_path::<SpritesheetTexture>("texture");
         app.editor_relation::<SpritesheetTexture, Transform>();
         app.editor_relation::<SpritesheetTexture, Visibility>();
         app.editor_registry::<AnimationIndicesSpriteSheet>();
@@ -112,17 +133,34 @@ impl Plugin for BasePrefabPlugin {
         app.register_type::<TorusPrefab>();
 
         app.editor_registry::<AssetMesh>();
+        app.editor_asset_path::<AssetMesh>("path");
         app.add_systems(
             Update,
             sync_asset_mesh.in_set(PrefabSet::DetectPrefabChange),
         );
 
         app.editor_registry::<AssetMaterial>();
+        app.editor_asset_path::<AssetMaterial>("path");
         app.add_systems(
             Update,
             sync_asset_material.in_set(PrefabSet::DetectPrefabChange),
         );
 
+        //animation
+        app.editor_registry::<AnimationPlayerPrefab>();
+        app.editor_registry::<SkinnedMeshPrefab>();
+        app.editor_asset_path::<SkinnedMeshPrefab>("inverse_bindposes");
+        app.add_systems(
+            Update,
+            sync_skinned_mesh.in_set(PrefabSet::DetectPrefabChange),
+        );
+        app.add_systems(
+            Update,
+            sync_animation_player
+                .in_set(PrefabSet::DetectPrefabChange)
+                .run_if(in_state(EditorState::Game)),
+        );
+
         //material registration
         app.register_type::<Color>();
         app.register_type::<AlphaMode>();
@@ -153,6 +191,7 @@ impl Plugin for BasePrefabPlugin {
         app.add_systems(Update, camera_render_graph_creation);
 
         app.editor_registry::<PlayerStart>();
+        app.editor_gltf_extras::<PlayerStart>("player_start");
         app.editor_relation::<PlayerStart, Transform>();
         app.editor_relation::<PlayerStart, GlobalTransform>();
         app.editor_relation::<PlayerStart, Visibility>();
@@ -161,8 +200,46 @@ impl Plugin for BasePrefabPlugin {
 
         app.editor_relation::<Transform, GlobalTransform>();
 
+        app.editor_silent_registry::<SelectionSets>();
+        app.editor_only::<SelectionSets>();
+
+        app.editor_silent_registry::<SceneDataHolder>();
+
+        app.register_type::<SceneLayer>()
+            .register_type::<Vec<SceneLayer>>()
+            .register_type::<Option<u8>>();
+        app.editor_silent_registry::<SceneLayers>();
+        app.auto_reflected_undo::<SceneLayers>();
+        app.editor_silent_registry::<EntityLayer>();
+        app.auto_reflected_undo::<EntityLayer>();
+        app.add_systems(
+            Update,
+            apply_layer_render_layers
+                .in_set(PrefabSet::DetectPrefabChange)
+                .run_if(in_state(EditorState::Game)),
+        );
+
+        app.editor_silent_registry::<EditorHidden>();
+        app.editor_only::<EditorHidden>();
+        app.auto_reflected_undo::<EditorHidden>();
+        app.editor_silent_registry::<EditorLocked>();
+        app.editor_only::<EditorLocked>();
+        app.auto_reflected_undo::<EditorLocked>();
+
+        app.register_type::<CameraBookmark>()
+            .register_type::<Vec<CameraBookmark>>();
+        app.editor_silent_registry::<CameraBookmarks>();
+        app.editor_only::<CameraBookmarks>();
+
+        app.register_type::<ArrayShape>();
+        app.editor_registry::<ArrayModifier>();
+        app.editor_only::<ArrayModifier>();
+        app.editor_silent_registry::<ArrayCopy>();
+        app.editor_only::<ArrayCopy>();
+
         //Light
         app.editor_registry::<LightAreaToggle>();
+        app.editor_only::<LightAreaToggle>();
 
         app.editor_registry::<PointLight>();
         app.editor_relation::<PointLight, CubemapVisibleEntities>();
@@ -184,9 +261,14 @@ impl Plugin for BasePrefabPlugin {
         app.editor_relation::<SpotLight, Transform>();
         app.editor_relation::<SpotLight, Visibility>();
 
+        register_component_info(app);
+
         app.add_systems(OnEnter(EditorState::Game), spawn_player_start);
 
-        app.add_systems(Update, spawn_scene.in_set(PrefabSet::PrefabLoad));
+        app.add_systems(
+            Update,
+            (spawn_scene, apply_gltf_extras).in_set(PrefabSet::PrefabLoad),
+        );
         app.add_systems(
             Update,
             (
@@ -220,6 +302,7 @@ impl Plugin for BasePrefabPlugin {
         app.add_systems(Update, animate_sprite);
 
         app.add_plugins(SavePrefabPlugin);
+        app.add_plugins(CookPlugin);
         app.add_plugins(LoadPlugin);
     }
 }
@@ -326,3 +409,54 @@ fn sync_asset_material(
         }
     }
 }
+
+/// Categories and icons of built-in components in add component menu
+fn register_component_info(app: &mut App) {
+    let core = ComponentInfo::new("Core");
+    app.editor_component_info::<Transform>(core.clone().icon("✥"))
+        .editor_component_info::<Name>(core.clone().icon("🏷"))
+        .editor_component_info::<Visibility>(core.icon("👁"));
+
+    let mesh = ComponentInfo::new("Mesh").icon("🧊");
+    app.editor_component_info::<MeshPrimitivePrefab>(mesh.clone().name("Mesh Primitive"))
+        .editor_component_info::<AssetMesh>(mesh.clone().name("Mesh Asset"))
+        .editor_component_info::<GltfPrefab>(mesh.clone().name("glTF Scene"))
+        .editor_component_info::<SkinnedMeshPrefab>(mesh.name("Skinned Mesh"));
+
+    let material = ComponentInfo::new("Material").icon("🎨");
+    app.editor_component_info::<MaterialPrefab>(material.clone().name("Material"))
+        .editor_component_info::<AssetMaterial>(material.clone().name("Material Asset"))
+        .editor_component_info::<ColorMaterialPrefab>(material.name("Color Material 2D"));
+
+    let sprite = ComponentInfo::new("2D").icon("🖼");
+    app.editor_component_info::<Sprite>(sprite.clone())
+        .editor_component_info::<SpriteTexture>(sprite.clone())
+        .editor_component_info::<SpritesheetTexture>(sprite.clone())
+        .editor_component_info::<TextureAtlasPrefab>(sprite.clone())
+        .editor_component_info::<AnimationIndicesSpriteSheet>(sprite.clone())
+        .editor_component_info::<AnimationTimerSpriteSheet>(sprite.clone())
+        .editor_component_info::<MeshPrimitive2dPrefab>(sprite.name("Mesh Primitive 2D"));
+
+    let animation = ComponentInfo::new("Animation").icon("🎬");
+    app.editor_component_info::<AnimationPlayerPrefab>(animation.clone())
+        .editor_component_info::<AnimationClipName>(animation.clone())
+        .editor_component_info::<AvailableAnimationClips>(animation);
+
+    let camera = ComponentInfo::new("Camera").icon("📷");
+    app.editor_component_info::<Camera>(camera.clone())
+        .editor_component_info::<Camera3d>(camera.clone())
+        .editor_component_info::<Camera2d>(camera.clone())
+        .editor_component_info::<Projection>(camera.clone())
+        .editor_component_info::<OrthographicProjection>(camera.clone())
+        .editor_component_info::<CameraPlay>(camera);
+
+    let light = ComponentInfo::new("Light").icon("💡");
+    app.editor_component_info::<PointLight>(light.clone())
+        .editor_component_info::<DirectionalLight>(light.clone())
+        .editor_component_info::<SpotLight>(light.clone())
+        .editor_component_info::<LightAreaToggle>(light);
+
+    let gameplay = ComponentInfo::new("Gameplay");
+    app.editor_component_info::<PlayerStart>(gameplay.clone().icon("🚩"))
+        .editor_component_info::<ArrayModifier>(gameplay.icon("▦"));
+}
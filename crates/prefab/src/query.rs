@@ -0,0 +1,398 @@
+use std::any::TypeId;
+
+use bevy::{
+    prelude::*,
+    reflect::{GetPath, TypeRegistry},
+};
+use space_shared::PrefabMarker;
+
+/// Comparison of reflected field with text value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompareOp {
+    #[default]
+    Eq,
+    NotEq,
+    Less,
+    Greater,
+    Contains,
+}
+
+impl CompareOp {
+    pub const ALL: [Self; 5] = [
+        Self::Eq,
+        Self::NotEq,
+        Self::Less,
+        Self::Greater,
+        Self::Contains,
+    ];
+
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            Self::Eq => "==",
+            Self::NotEq => "!=",
+            Self::Less => "<",
+            Self::Greater => ">",
+            Self::Contains => "contains",
+        }
+    }
+}
+
+/// Single condition of entity query: component presence and optional field predicate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryCondition {
+    pub component: TypeId,
+    /// Entity must not have component. Field predicate is ignored
+    pub without: bool,
+    /// Reflect path of field (`"intensity"`, `"color.r"`). Empty path checks only component presence
+    pub field: String,
+    pub op: CompareOp,
+    pub value: String,
+}
+
+impl QueryCondition {
+    pub const fn new(component: TypeId, without: bool) -> Self {
+        Self {
+            component,
+            without,
+            field: String::new(),
+            op: CompareOp::Eq,
+            value: String::new(),
+        }
+    }
+
+    pub const fn with<T: Component>() -> Self {
+        Self::new(TypeId::of::<T>(), false)
+    }
+
+    pub const fn without<T: Component>() -> Self {
+        Self::new(TypeId::of::<T>(), true)
+    }
+
+    pub fn field(mut self, field: &str, op: CompareOp, value: &str) -> Self {
+        self.field = field.to_string();
+        self.op = op;
+        self.value = value.to_string();
+        self
+    }
+}
+
+/// Find prefab entities matching all conditions
+pub fn query_entities(world: &mut World, conditions: &[QueryCondition]) -> Vec<Entity> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let mut entities = world.query_filtered::<Entity, With<PrefabMarker>>();
+
+    entities
+        .iter(world)
+        .filter(|entity| entity_matches(world.entity(*entity), conditions, &type_registry))
+        .collect()
+}
+
+/// Entity matches all conditions
+pub fn entity_matches(
+    entity: EntityRef,
+    conditions: &[QueryCondition],
+    type_registry: &TypeRegistry,
+) -> bool {
+    conditions.iter().all(|condition| {
+        let component = type_registry
+            .get(condition.component)
+            .and_then(|registration| registration.data::<ReflectComponent>())
+            .and_then(|reflect_component| reflect_component.reflect(entity));
+        match component {
+            None => condition.without,
+            Some(_) if condition.without => false,
+            Some(_) if condition.field.is_empty() => true,
+            Some(component) => component
+                .reflect_path(condition.field.as_str())
+                .is_ok_and(|value| value_matches(value, condition.op, &condition.value)),
+        }
+    })
+}
+
+/// Entity filter parsed from text like `lamp t:PointLight intensity>100 !Visibility`. Words are:
+/// - `t:Type` or `c:Type` - entity has component, next field words check its fields
+/// - `!Type` - entity does not have component
+/// - `field=value`, `field!=value`, `field<value`, `field>value` and `field~value` (contains)
+/// - other words - entity name contains word, case insensitive
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntityFilter {
+    /// Lowercase words of entity name
+    pub name: Vec<String>,
+    pub conditions: Vec<QueryCondition>,
+}
+
+impl EntityFilter {
+    /// Parse filter. Components are found by short or full type path in registry
+    pub fn parse(text: &str, type_registry: &TypeRegistry) -> Result<Self, String> {
+        let mut filter = Self::default();
+        // component of last `t:` word, field words are applied to it
+        let mut component = None;
+        for word in text.split_whitespace() {
+            if let Some(name) = word.strip_prefix("t:").or_else(|| word.strip_prefix("c:")) {
+                let type_id = find_component(type_registry, name)?;
+                filter.conditions.push(QueryCondition::new(type_id, false));
+                component = Some(type_id);
+            } else if let Some((field, op, value)) = split_field(word) {
+                let Some(type_id) = component else {
+                    return Err(format!(
+                        "`{}` needs component before it, like `t:Transform {}`",
+                        word, word
+                    ));
+                };
+                let condition = QueryCondition::new(type_id, false).field(field, op, value);
+                match filter.conditions.last_mut() {
+                    Some(last) if last.component == type_id && last.field.is_empty() => {
+                        *last = condition;
+                    }
+                    _ => filter.conditions.push(condition),
+                }
+            } else if let Some(name) = word.strip_prefix('!').filter(|name| !name.is_empty()) {
+                let type_id = find_component(type_registry, name)?;
+                filter.conditions.push(QueryCondition::new(type_id, true));
+                component = None;
+            } else {
+                filter.name.push(word.to_lowercase());
+            }
+        }
+        Ok(filter)
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.name.is_empty() && self.conditions.is_empty()
+    }
+
+    pub fn matches(&self, entity: EntityRef, type_registry: &TypeRegistry) -> bool {
+        let name = entity
+            .get::<Name>()
+            .map_or_else(|| "entity".to_string(), |name| name.to_lowercase());
+        self.name.iter().all(|word| name.contains(word))
+            && entity_matches(entity, &self.conditions, type_registry)
+    }
+}
+
+/// Reflected component with short (`PointLight`, case insensitive) or full type path
+fn find_component(type_registry: &TypeRegistry, name: &str) -> Result<TypeId, String> {
+    type_registry
+        .iter()
+        .filter(|registration| registration.data::<ReflectComponent>().is_some())
+        .find(|registration| {
+            let path = registration.type_info().type_path_table();
+            path.short_path().eq_ignore_ascii_case(name) || path.path() == name
+        })
+        .map(|registration| registration.type_id())
+        .ok_or_else(|| format!("Unknown component `{}`", name))
+}
+
+/// Split `field<op>value` word. Operator at word start is not field comparison
+fn split_field(word: &str) -> Option<(&str, CompareOp, &str)> {
+    [
+        ("!=", CompareOp::NotEq),
+        ("~", CompareOp::Contains),
+        ("=", CompareOp::Eq),
+        ("<", CompareOp::Less),
+        (">", CompareOp::Greater),
+    ]
+    .into_iter()
+    .filter_map(|(symbol, op)| word.find(symbol).map(|idx| (idx, symbol, op)))
+    .min_by_key(|(idx, ..)| *idx)
+    .filter(|(idx, ..)| *idx > 0)
+    .map(|(idx, symbol, op)| (&word[..idx], op, &word[idx + symbol.len()..]))
+}
+
+/// Compare reflected value with text. Numbers and bools are parsed, strings are compared as is,
+/// other values (enums, structs) are compared by their debug text
+pub fn value_matches(value: &dyn Reflect, op: CompareOp, expected: &str) -> bool {
+    if let Some(number) = reflect_number(value) {
+        let Ok(expected) = expected.trim().parse::<f64>() else {
+            return false;
+        };
+        return match op {
+            CompareOp::Eq => number == expected,
+            CompareOp::NotEq => number != expected,
+            CompareOp::Less => number < expected,
+            CompareOp::Greater => number > expected,
+            CompareOp::Contains => false,
+        };
+    }
+    if let Some(flag) = value.downcast_ref::<bool>() {
+        let Ok(expected) = expected.trim().parse::<bool>() else {
+            return false;
+        };
+        return match op {
+            CompareOp::Eq => *flag == expected,
+            CompareOp::NotEq => *flag != expected,
+            _ => false,
+        };
+    }
+
+    let text = value
+        .downcast_ref::<String>()
+        .cloned()
+        .unwrap_or_else(|| format!("{:?}", value));
+    match op {
+        CompareOp::Eq => text == expected,
+        CompareOp::NotEq => text != expected,
+        CompareOp::Less => text.as_str() < expected,
+        CompareOp::Greater => text.as_str() > expected,
+        CompareOp::Contains => text.contains(expected),
+    }
+}
+
+fn reflect_number(value: &dyn Reflect) -> Option<f64> {
+    macro_rules! downcast_number {
+        ($($ty:ty),*) => {
+            $(if let Some(v) = value.downcast_ref::<$ty>() {
+                return Some(*v as f64);
+            })*
+        };
+    }
+    downcast_number!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::GltfPrefab;
+
+    fn test_world() -> World {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<PointLight>();
+            registry.register::<GltfPrefab>();
+            registry.register::<Visibility>();
+        }
+        world.insert_resource(registry);
+        world
+    }
+
+    #[test]
+    fn query_by_field_value() {
+        let mut world = test_world();
+        let bright = world
+            .spawn((
+                PrefabMarker,
+                PointLight {
+                    intensity: 2000.,
+                    ..default()
+                },
+            ))
+            .id();
+        world.spawn((
+            PrefabMarker,
+            PointLight {
+                intensity: 500.,
+                ..default()
+            },
+        ));
+        // not prefab entities are skipped
+        world.spawn(PointLight {
+            intensity: 5000.,
+            ..default()
+        });
+
+        let found = query_entities(
+            &mut world,
+            &[QueryCondition::with::<PointLight>().field("intensity", CompareOp::Greater, "1000")],
+        );
+        assert_eq!(found, vec![bright]);
+    }
+
+    #[test]
+    fn query_by_string_and_absence() {
+        let mut world = test_world();
+        let tree = world
+            .spawn((
+                PrefabMarker,
+                GltfPrefab {
+                    path: "models/tree.glb".to_string(),
+                    scene: "Scene0".to_string(),
+                },
+            ))
+            .id();
+        world.spawn((
+            PrefabMarker,
+            GltfPrefab {
+                path: "models/tree.glb".to_string(),
+                scene: "Scene0".to_string(),
+            },
+            Visibility::Hidden,
+        ));
+        world.spawn((
+            PrefabMarker,
+            GltfPrefab {
+                path: "models/rock.glb".to_string(),
+                scene: "Scene0".to_string(),
+            },
+        ));
+
+        let found = query_entities(
+            &mut world,
+            &[
+                QueryCondition::with::<GltfPrefab>().field(
+                    "path",
+                    CompareOp::Eq,
+                    "models/tree.glb",
+                ),
+                QueryCondition::without::<Visibility>(),
+            ],
+        );
+        assert_eq!(found, vec![tree]);
+    }
+
+    #[test]
+    fn filter_parse_and_match() {
+        let mut world = test_world();
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        let filter = EntityFilter::parse("Tree c:gltfprefab path~tree !Visibility", &registry)
+            .expect("filter is valid");
+        assert_eq!(filter.name, vec!["tree".to_string()]);
+        assert_eq!(
+            filter.conditions,
+            vec![
+                QueryCondition::with::<GltfPrefab>().field("path", CompareOp::Contains, "tree"),
+                QueryCondition::without::<Visibility>(),
+            ]
+        );
+
+        let tree = world
+            .spawn((
+                Name::new("Big tree"),
+                GltfPrefab {
+                    path: "models/tree.glb".to_string(),
+                    scene: "Scene0".to_string(),
+                },
+            ))
+            .id();
+        let rock = world
+            .spawn((
+                Name::new("Tree rock"),
+                GltfPrefab {
+                    path: "models/rock.glb".to_string(),
+                    scene: "Scene0".to_string(),
+                },
+            ))
+            .id();
+        assert!(filter.matches(world.entity(tree), &registry));
+        assert!(!filter.matches(world.entity(rock), &registry));
+
+        assert!(EntityFilter::parse("t:Unknown", &registry).is_err());
+        assert!(EntityFilter::parse("intensity>10", &registry).is_err());
+        assert!(EntityFilter::parse("", &registry).unwrap().is_empty());
+    }
+
+    #[test]
+    fn enum_compared_by_variant_name() {
+        assert!(value_matches(&Visibility::Hidden, CompareOp::Eq, "Hidden"));
+        assert!(!value_matches(
+            &Visibility::Hidden,
+            CompareOp::Eq,
+            "Visible"
+        ));
+    }
+}
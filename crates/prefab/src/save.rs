@@ -36,6 +36,8 @@ pub struct SavePrefabPlugin;
 impl Plugin for SavePrefabPlugin {
     fn build(&self, app: &mut App) {
         app.editor_registry::<ChildrenPrefab>();
+        // Registered by render plugins in editor, but required for headless loading too
+        app.register_type::<Vec<Entity>>();
 
         app.init_resource::<SaveConfig>().add_state::<SaveState>();
 
@@ -62,11 +64,15 @@ pub struct SaveConfig {
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 pub enum SaveState {
     Save,
+    Cook,
     #[default]
     Idle,
 }
 
-fn prepare_children(mut commands: Commands, query: Query<(Entity, &Children), With<PrefabMarker>>) {
+pub(crate) fn prepare_children(
+    mut commands: Commands,
+    query: Query<(Entity, &Children), With<PrefabMarker>>,
+) {
     for (entity, children) in query.iter() {
         commands
             .entity(entity)
@@ -74,16 +80,17 @@ fn prepare_children(mut commands: Commands, query: Query<(Entity, &Children), Wi
     }
 }
 
-fn delete_prepared_children(mut commands: Commands, query: Query<Entity, With<ChildrenPrefab>>) {
+pub(crate) fn delete_prepared_children(
+    mut commands: Commands,
+    query: Query<Entity, With<ChildrenPrefab>>,
+) {
     for entity in query.iter() {
         commands.entity(entity).remove::<ChildrenPrefab>();
     }
 }
 
-/// Convert world scene to prefab
-pub fn serialize_scene(world: &mut World) {
-    let config = world.resource::<SaveConfig>().clone();
-
+/// Collect all prefab entities with registered components to [`DynamicScene`]
+pub fn extract_prefab_scene(world: &mut World) -> DynamicScene {
     let mut prefab_query = world.query_filtered::<Entity, With<PrefabMarker>>();
     let entities = prefab_query.iter(world).collect::<Vec<_>>();
 
@@ -101,7 +108,14 @@ pub fn serialize_scene(world: &mut World) {
             allow_types.iter().cloned(),
         )))
         .extract_entities(entities.iter().copied());
-    let scene = builder.build();
+    builder.build()
+}
+
+/// Convert world scene to prefab
+pub fn serialize_scene(world: &mut World) {
+    let config = world.resource::<SaveConfig>().clone();
+
+    let scene = extract_prefab_scene(world);
 
     let res = scene.serialize_ron(world.resource::<AppTypeRegistry>());
 
@@ -0,0 +1,336 @@
+use std::{any::TypeId, collections::BTreeMap, fmt, path::Path};
+
+use bevy::{
+    prelude::*,
+    reflect::{GetPath, ReflectRef, TypeRegistry},
+    utils::{HashMap, HashSet},
+};
+
+use crate::asset_refs::strip_label;
+
+/// Problem found in prefab file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SceneIssue {
+    /// File can not be read or parsed
+    Parse(String),
+    /// Type path is not registered in type registry
+    UnknownType(String),
+    /// Asset path points to not existing file
+    MissingAsset {
+        entity: Entity,
+        component: String,
+        field: String,
+        path: String,
+    },
+    /// Entity field points to entity which is not in the scene
+    DanglingEntity {
+        entity: Entity,
+        component: String,
+        field: String,
+        target: Entity,
+    },
+}
+
+impl fmt::Display for SceneIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "parse error: {}", err),
+            Self::UnknownType(type_path) => write!(f, "unknown type {}", type_path),
+            Self::MissingAsset {
+                entity,
+                component,
+                field,
+                path,
+            } => write!(
+                f,
+                "entity {:?}: {}{} points to missing asset {}",
+                entity, component, field, path
+            ),
+            Self::DanglingEntity {
+                entity,
+                component,
+                field,
+                target,
+            } => write!(
+                f,
+                "entity {:?}: {}{} points to entity {:?} which is not in the scene",
+                entity, component, field, target
+            ),
+        }
+    }
+}
+
+/// Call visitor for value and all nested fields. Field path is written in `.field[index]` style
+pub fn visit_reflect(value: &dyn Reflect, visitor: &mut dyn FnMut(&str, &dyn Reflect)) {
+    let mut path = String::new();
+    visit_reflect_inner(value, &mut path, visitor);
+}
+
+fn visit_reflect_inner(
+    value: &dyn Reflect,
+    path: &mut String,
+    visitor: &mut dyn FnMut(&str, &dyn Reflect),
+) {
+    visitor(path, value);
+    let len = path.len();
+    match value.reflect_ref() {
+        ReflectRef::Struct(s) => {
+            for idx in 0..s.field_len() {
+                if let (Some(name), Some(field)) = (s.name_at(idx), s.field_at(idx)) {
+                    path.push('.');
+                    path.push_str(name);
+                    visit_reflect_inner(field, path, visitor);
+                    path.truncate(len);
+                }
+            }
+        }
+        ReflectRef::TupleStruct(s) => {
+            for (idx, field) in s.iter_fields().enumerate() {
+                path.push_str(&format!(".{}", idx));
+                visit_reflect_inner(field, path, visitor);
+                path.truncate(len);
+            }
+        }
+        ReflectRef::Tuple(s) => {
+            for (idx, field) in s.iter_fields().enumerate() {
+                path.push_str(&format!(".{}", idx));
+                visit_reflect_inner(field, path, visitor);
+                path.truncate(len);
+            }
+        }
+        ReflectRef::List(s) => {
+            for (idx, field) in s.iter().enumerate() {
+                path.push_str(&format!("[{}]", idx));
+                visit_reflect_inner(field, path, visitor);
+                path.truncate(len);
+            }
+        }
+        ReflectRef::Array(s) => {
+            for (idx, field) in s.iter().enumerate() {
+                path.push_str(&format!("[{}]", idx));
+                visit_reflect_inner(field, path, visitor);
+                path.truncate(len);
+            }
+        }
+        ReflectRef::Map(s) => {
+            for (idx, (_, field)) in s.iter().enumerate() {
+                path.push_str(&format!("[{}]", idx));
+                visit_reflect_inner(field, path, visitor);
+                path.truncate(len);
+            }
+        }
+        ReflectRef::Enum(s) => {
+            for (idx, field) in s.iter_fields().enumerate() {
+                match field.name() {
+                    Some(name) => path.push_str(&format!("::{}.{}", s.variant_name(), name)),
+                    None => path.push_str(&format!("::{}.{}", s.variant_name(), idx)),
+                }
+                visit_reflect_inner(field.value(), path, visitor);
+                path.truncate(len);
+            }
+        }
+        ReflectRef::Value(_) => {}
+    }
+}
+
+/// Name of component type (real type path for dynamic values)
+pub fn component_type_path(component: &dyn Reflect) -> String {
+    component
+        .get_represented_type_info()
+        .map(|info| info.type_path().to_string())
+        .unwrap_or_else(|| component.reflect_type_path().to_string())
+}
+
+/// Collect type paths from parsed scene file which are not registered in type registry.
+/// Works without deserializing components, so all unknown types are reported at once
+pub fn unknown_types(value: &ron::Value, registry: &TypeRegistry) -> Result<Vec<String>, String> {
+    let mut unknown = vec![];
+    let ron::Value::Map(root) = value else {
+        return Err("scene must be a struct".to_string());
+    };
+    for (key, value) in root.iter() {
+        let ron::Value::String(key) = key else {
+            continue;
+        };
+        let type_paths: Vec<&ron::Value> = match (key.as_str(), value) {
+            ("resources", ron::Value::Map(resources)) => resources.keys().collect(),
+            ("entities", ron::Value::Map(entities)) => entities
+                .values()
+                .filter_map(|entity| match entity {
+                    ron::Value::Map(entity) => entity
+                        .iter()
+                        .find(|(key, _)| **key == ron::Value::String("components".to_string()))
+                        .map(|(_, components)| components),
+                    _ => None,
+                })
+                .filter_map(|components| match components {
+                    ron::Value::Map(components) => Some(components.keys()),
+                    _ => None,
+                })
+                .flatten()
+                .collect(),
+            _ => vec![],
+        };
+        for type_path in type_paths {
+            if let ron::Value::String(type_path) = type_path {
+                if registry.get_with_type_path(type_path).is_none() && !unknown.contains(type_path)
+                {
+                    unknown.push(type_path.clone());
+                }
+            }
+        }
+    }
+    unknown.sort();
+    Ok(unknown)
+}
+
+/// Check asset paths and entity links of deserialized scene.
+///
+/// Only fields registered with [`EditorRegistryExt::editor_asset_path`](crate::editor_registry::EditorRegistryExt::editor_asset_path)
+/// are checked as asset paths (`asset_paths` is [`EditorRegistry::asset_paths`](crate::editor_registry::EditorRegistry))
+pub fn validate_scene(
+    scene: &DynamicScene,
+    asset_paths: &HashMap<TypeId, Vec<String>>,
+    asset_folder: &Path,
+) -> Vec<SceneIssue> {
+    let scene_entities = scene
+        .entities
+        .iter()
+        .map(|e| e.entity)
+        .collect::<HashSet<_>>();
+    let mut issues = vec![];
+
+    for dyn_entity in scene.entities.iter() {
+        for component in dyn_entity.components.iter() {
+            let type_path = component_type_path(component.as_ref());
+            visit_reflect(component.as_ref(), &mut |field, value| {
+                if let Some(target) = value.as_any().downcast_ref::<Entity>() {
+                    if *target != Entity::PLACEHOLDER && !scene_entities.contains(target) {
+                        issues.push(SceneIssue::DanglingEntity {
+                            entity: dyn_entity.entity,
+                            component: type_path.clone(),
+                            field: field.to_string(),
+                            target: *target,
+                        });
+                    }
+                }
+            });
+
+            let Some(fields) = component
+                .get_represented_type_info()
+                .and_then(|info| asset_paths.get(&info.type_id()))
+            else {
+                continue;
+            };
+            for field in fields.iter() {
+                let Some(path) = component
+                    .reflect_path(field.as_str())
+                    .ok()
+                    .and_then(|value| value.downcast_ref::<String>())
+                else {
+                    continue;
+                };
+                if path.is_empty() {
+                    continue;
+                }
+                let path = strip_label(path);
+                if !asset_folder.join(path).exists() {
+                    issues.push(SceneIssue::MissingAsset {
+                        entity: dyn_entity.entity,
+                        component: type_path.clone(),
+                        field: format!(".{}", field),
+                        path: path.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// Count of entities and components in scene
+#[derive(Default, Debug, Clone)]
+pub struct SceneStats {
+    pub entities: usize,
+    pub resources: usize,
+    /// Count of each component type
+    pub components: BTreeMap<String, usize>,
+}
+
+impl SceneStats {
+    pub fn from_scene(scene: &DynamicScene) -> Self {
+        let mut stats = Self {
+            entities: scene.entities.len(),
+            resources: scene.resources.len(),
+            ..default()
+        };
+        for component in scene.entities.iter().flat_map(|e| e.components.iter()) {
+            *stats
+                .components
+                .entry(component_type_path(component.as_ref()))
+                .or_default() += 1;
+        }
+        stats
+    }
+
+    pub fn total_components(&self) -> usize {
+        self.components.values().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::scene::DynamicEntity;
+
+    use super::*;
+    use crate::component::GltfPrefab;
+
+    #[test]
+    fn only_registered_asset_fields_are_checked() {
+        let mut asset_paths = HashMap::new();
+        asset_paths.insert(TypeId::of::<GltfPrefab>(), vec!["path".to_string()]);
+        let mut scene = DynamicScene::default();
+        scene.entities.push(DynamicEntity {
+            entity: Entity::from_raw(0),
+            components: vec![
+                Box::new(GltfPrefab {
+                    path: "models/missing.glb#Scene0".to_string(),
+                    scene: "Scene0".to_string(),
+                }),
+                Box::new(Name::new("notes.txt")),
+            ],
+        });
+
+        let issues = validate_scene(&scene, &asset_paths, Path::new("not_existing_assets"));
+
+        assert_eq!(
+            issues,
+            vec![SceneIssue::MissingAsset {
+                entity: Entity::from_raw(0),
+                component: GltfPrefab::type_path().to_string(),
+                field: ".path".to_string(),
+                path: "models/missing.glb".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn unknown_types_are_collected_from_all_entities() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Name>();
+        let data = r#"(
+            resources: {},
+            entities: {
+                0: (components: { "bevy_core::name::Name": "a", "game::Unknown": () }),
+                1: (components: { "game::Unknown": (), "game::Other": (x: 1) }),
+            },
+        )"#;
+
+        let value = ron::from_str::<ron::Value>(data).unwrap();
+
+        let unknown = unknown_types(&value, &registry).unwrap();
+
+        assert_eq!(unknown, vec!["game::Other", "game::Unknown"]);
+    }
+}
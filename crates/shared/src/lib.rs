@@ -1,4 +1,6 @@
-use bevy::prelude::*;
+use std::path::PathBuf;
+
+use bevy::{asset::io::file::FileAssetReader, prelude::*};
 
 pub mod ext {
     pub use bevy_inspector_egui;
@@ -7,8 +9,8 @@ pub mod ext {
 
 pub mod prelude {
     pub use crate::{
-        EditorCameraMarker, EditorEvent, EditorPrefabPath, EditorSet, EditorState, PrefabMarker,
-        PrefabMemoryCache, SelectParent,
+        AssetFolder, EditorCameraMarker, EditorEvent, EditorPrefabPath, EditorSet, EditorState,
+        PrefabMarker, PrefabMemoryCache, SelectParent,
     };
 }
 
@@ -46,6 +48,22 @@ pub enum EditorSet {
     Game,
 }
 
+/// Project asset folder, resolved from [`AssetPlugin::file_path`] of app.
+/// Used to read and check asset files outside of [`AssetServer`]
+#[derive(Resource, Clone, Debug)]
+pub struct AssetFolder(pub PathBuf);
+
+impl AssetFolder {
+    /// Folder of [`AssetPlugin`] added to app, or default folder if plugin is not added yet
+    pub fn from_app(app: &App) -> Self {
+        let file_path = app.get_added_plugins::<AssetPlugin>().first().map_or_else(
+            || AssetPlugin::default().file_path,
+            |plugin| plugin.file_path.clone(),
+        );
+        Self(FileAssetReader::get_base_path().join(file_path))
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct PrefabMemoryCache {
     pub scene: Option<Handle<DynamicScene>>,
@@ -62,6 +80,8 @@ pub enum EditorPrefabPath {
 pub enum EditorEvent {
     Load(EditorPrefabPath),
     Save(EditorPrefabPath),
+    /// Save flat runtime scene without editor only data
+    Cook(EditorPrefabPath),
     LoadGltfAsPrefab(String),
     StartGame,
 }
@@ -146,3 +146,32 @@ fn undo_ignore_ticks() {
 
     assert_eq!(ignore_storage.storage.len(), 1)
 }
+
+#[test]
+fn same_frame_changes_of_many_entities_are_one_change() {
+    let mut app = configure_app();
+    app.auto_reflected_undo::<Name>();
+
+    let first = app.world.spawn((Name::new("a"), UndoMarker)).id();
+    let second = app.world.spawn((Name::new("b"), UndoMarker)).id();
+    // wait until undo ignore of just added components is over
+    for _ in 0..15 {
+        app.update();
+    }
+    app.world.resource_mut::<ChangeChain>().changes.clear();
+
+    // multi entity edit in inspector changes all selected entities in one frame
+    app.world.get_mut::<Name>(first).unwrap().set("edited");
+    app.world.get_mut::<Name>(second).unwrap().set("edited");
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(app.world.resource::<ChangeChain>().changes.len(), 1);
+
+    app.world.send_event(UndoRedo::Undo);
+    app.update();
+
+    assert_eq!(app.world.get::<Name>(first).unwrap().as_str(), "a");
+    assert_eq!(app.world.get::<Name>(second).unwrap().as_str(), "b");
+}
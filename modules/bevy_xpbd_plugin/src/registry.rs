@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_xpbd_3d::prelude::*;
 use space_editor_ui::{
-    prelude::{EditorRegistryExt, EditorState, PrefabSet},
+    prelude::{ComponentInfo, EditorRegistryExt, EditorState, PrefabSet},
     settings::RegisterSettingsBlockExt,
 };
 
@@ -21,25 +21,7 @@ impl Plugin for BevyXpbdPlugin {
         app.add_plugins(PhysicsPlugins::default());
         app.add_plugins(bevy_xpbd_3d::plugins::PhysicsDebugPlugin::default());
 
-        app.editor_registry::<collider::ColliderPrefab>()
-            .editor_registry::<RigidBodyPrefab>()
-            .editor_registry::<Mass>()
-            .editor_registry::<Friction>()
-            .editor_registry::<Restitution>()
-            .editor_registry::<LinearDamping>()
-            .editor_registry::<AngularDamping>()
-            .editor_registry::<Inertia>()
-            .editor_registry::<CenterOfMass>()
-            .editor_registry::<LockedAxes>()
-            .editor_registry::<GravityScale>()
-            .editor_registry::<Sensor>();
-
-        app.register_type::<ColliderPrimitive>()
-            .register_type::<ColliderPart>()
-            .register_type::<Vec<ColliderPart>>()
-            .register_type::<ColliderPrefabCompound>();
-
-        register_xpbd_spatial_types(app);
+        register_xpbd_types(app);
 
         app.add_systems(
             PreUpdate,
@@ -94,6 +76,50 @@ impl Plugin for BevyXpbdPlugin {
     }
 }
 
+/// Register prefab components of the module and types they need for (de)serialization.
+/// It does not add physics systems, so it can be used in headless tools like `space_cli`
+pub fn register_xpbd_types(app: &mut App) {
+    app.editor_registry::<collider::ColliderPrefab>()
+        .editor_gltf_extras::<collider::ColliderPrefab>("collider")
+        .editor_registry::<RigidBodyPrefab>()
+        .editor_registry::<Mass>()
+        .editor_registry::<Friction>()
+        .editor_registry::<Restitution>()
+        .editor_registry::<LinearDamping>()
+        .editor_registry::<AngularDamping>()
+        .editor_registry::<Inertia>()
+        .editor_registry::<CenterOfMass>()
+        .editor_registry::<LockedAxes>()
+        .editor_registry::<GravityScale>()
+        .editor_registry::<Sensor>();
+
+    let physics = ComponentInfo::new("Physics").icon("⚛");
+    app.editor_component_info::<collider::ColliderPrefab>(physics.clone().name("Collider"))
+        .editor_component_info::<RigidBodyPrefab>(physics.clone().name("Rigid Body"))
+        .editor_component_info::<Mass>(physics.clone())
+        .editor_component_info::<Friction>(physics.clone())
+        .editor_component_info::<Restitution>(physics.clone())
+        .editor_component_info::<LinearDamping>(physics.clone())
+        .editor_component_info::<AngularDamping>(physics.clone())
+        .editor_component_info::<Inertia>(physics.clone())
+        .editor_component_info::<CenterOfMass>(physics.clone())
+        .editor_component_info::<LockedAxes>(physics.clone())
+        .editor_component_info::<GravityScale>(physics.clone())
+        .editor_component_info::<Sensor>(physics);
+
+    app.register_type::<ColliderPrimitive>()
+        .register_type::<ColliderPart>()
+        .register_type::<Vec<ColliderPart>>()
+        .register_type::<ColliderPrefabCompound>();
+
+    register_xpbd_spatial_types(app);
+
+    app.register_type::<Option<Vec3>>()
+        .register_type::<Option<Color>>()
+        .register_type::<Option<[f32; 4]>>()
+        .register_type::<[f32; 4]>();
+}
+
 fn sync_position_spawn(
     mut commands: Commands,
     query: Query<
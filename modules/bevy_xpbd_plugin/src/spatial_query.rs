@@ -10,6 +10,10 @@ pub fn register_xpbd_spatial_types(app: &mut App) {
         .editor_into_sync::<RayCasterPrefab, RayCaster>();
     app.editor_registry::<ShapeCasterPrefab>()
         .editor_into_sync::<ShapeCasterPrefab, ShapeCaster>();
+
+    let physics = ComponentInfo::new("Physics").icon("⚛");
+    app.editor_component_info::<RayCasterPrefab>(physics.clone().name("Ray Caster"))
+        .editor_component_info::<ShapeCasterPrefab>(physics.name("Shape Caster"));
 }
 
 #[derive(Component, Reflect, Clone, Debug, InspectorOptions)]
@@ -0,0 +1,5 @@
+use space_editor::cli::SpaceCli;
+
+fn main() {
+    SpaceCli::default().run_from_env();
+}
@@ -0,0 +1,375 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{prelude::*, reflect::TypeRegistryArc, scene::serde::SceneSerializer};
+use space_prefab::{
+    cook::{cook_scene, read_scene_file, CookRuntimeApp, CookSettings, SceneFormat},
+    editor_registry::EditorRegistry,
+    plugins::BasePrefabPlugin,
+    validate::{unknown_types, validate_scene, SceneIssue, SceneStats},
+};
+
+const USAGE: &str = "Usage: space_cli <command> [args]
+
+Commands:
+    cook <input> <output> [--assets <folder>]
+        Inline nested prefabs, strip editor only components and save flat runtime scene
+    validate <files>... [--assets <folder>]
+        Check files for unknown types, missing assets and dangling entity links
+    convert <input> <output>
+        Convert scene between .scn.ron and .scn.json formats
+    stats <files>...
+        Print entity and component statistics
+    help
+        Show this message";
+
+/// Headless tools to work with prefab files without window and editor ui.
+///
+/// Default app knows prefab types and types of enabled community modules.
+/// Game types are not known to it, so scenes with them are reported as having unknown types.
+/// Register your own types in [`SpaceCli::app`] before calling [`SpaceCli::run`]
+pub struct SpaceCli {
+    pub app: App,
+}
+
+impl Default for SpaceCli {
+    fn default() -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BasePrefabPlugin));
+
+        #[cfg(feature = "bevy_xpbd_3d")]
+        {
+            use space_prefab::cook::CookAppExt;
+            space_bevy_xpbd_plugin::registry::register_xpbd_types(&mut app);
+            app.cook_runtime_types(space_bevy_xpbd_plugin::registry::register_xpbd_types);
+        }
+        // bevy_mesh_terrain module stores terrain in own chunk files and adds no prefab components
+
+        Self { app }
+    }
+}
+
+impl SpaceCli {
+    /// Run cli with arguments from command line
+    pub fn run_from_env(&mut self) {
+        let args = std::env::args().skip(1).collect::<Vec<_>>();
+        if let Err(err) = self.run(&args) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+
+    /// Run cli with given arguments (without program name)
+    pub fn run(&mut self, args: &[String]) -> Result<(), String> {
+        let Some(command) = args.first() else {
+            return Err(USAGE.to_string());
+        };
+        match command.as_str() {
+            "cook" => self.cook(&args[1..]),
+            "validate" => self.validate(&args[1..]),
+            "convert" => self.convert(&args[1..]),
+            "stats" => self.stats(&args[1..]),
+            "help" | "--help" | "-h" => {
+                println!("{}", USAGE);
+                Ok(())
+            }
+            _ => Err(format!("Unknown command {}\n\n{}", command, USAGE)),
+        }
+    }
+
+    fn type_registry(&self) -> TypeRegistryArc {
+        self.app.world.resource::<AppTypeRegistry>().0.clone()
+    }
+
+    /// Read scene file in any supported format
+    pub fn read_scene(&self, path: &Path) -> Result<DynamicScene, SceneIssue> {
+        read_scene_file(path, &self.type_registry())
+            .map_err(|err| SceneIssue::Parse(err.to_string()))
+    }
+
+    /// Write scene file in format detected by extension
+    pub fn write_scene(&self, path: &Path, scene: &DynamicScene) -> Result<(), String> {
+        let registry = self.type_registry();
+        let data = match SceneFormat::from_path(path) {
+            SceneFormat::Ron => scene
+                .serialize_ron(&registry)
+                .map_err(|err| err.to_string())?,
+            SceneFormat::Json => {
+                serde_json::to_string_pretty(&SceneSerializer::new(scene, &registry))
+                    .map_err(|err| err.to_string())?
+            }
+        };
+        std::fs::write(path, data).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// Find all problems of scene file
+    pub fn validate_file(&self, path: &Path, asset_folder: &Path) -> Vec<SceneIssue> {
+        let data = match read_file(path) {
+            Ok(data) => data,
+            Err(err) => return vec![err],
+        };
+
+        // Unknown types break deserialization, so they are collected from raw file first
+        let value = match SceneFormat::from_path(path) {
+            SceneFormat::Ron => ron::from_str::<ron::Value>(&data).map_err(|err| err.to_string()),
+            SceneFormat::Json => {
+                serde_json::from_str::<ron::Value>(&data).map_err(|err| err.to_string())
+            }
+        };
+        let unknown = value.and_then(|value| unknown_types(&value, &self.type_registry().read()));
+        match unknown {
+            Ok(unknown) if !unknown.is_empty() => {
+                return unknown.into_iter().map(SceneIssue::UnknownType).collect();
+            }
+            Err(err) => return vec![SceneIssue::Parse(err)],
+            _ => {}
+        }
+
+        match self.read_scene(path) {
+            Ok(scene) => validate_scene(
+                &scene,
+                &self.app.world.resource::<EditorRegistry>().asset_paths,
+                asset_folder,
+            ),
+            Err(err) => vec![err],
+        }
+    }
+
+    fn cook(&mut self, args: &[String]) -> Result<(), String> {
+        let (paths, asset_folder) = parse_asset_folder(args)?;
+        let [input, output] = paths.as_slice() else {
+            return Err(USAGE.to_string());
+        };
+
+        let registry = self.type_registry();
+        let settings = CookSettings {
+            asset_folder,
+            editor_only: self
+                .app
+                .world
+                .resource::<EditorRegistry>()
+                .editor_only
+                .clone(),
+            runtime_registry: Some(self.app.world.resource::<CookRuntimeApp>().type_registry()),
+            ..default()
+        };
+
+        let scene = self
+            .read_scene(&PathBuf::from(input))
+            .map_err(|err| format!("{}: {}", input, err))?;
+        let (cooked, report) =
+            cook_scene(&scene, &registry, &settings).map_err(|err| err.to_string())?;
+        self.write_scene(&PathBuf::from(output), &cooked)?;
+
+        println!(
+            "Cooked {} entities to {} ({} prefabs inlined, {} editor components stripped)",
+            report.entities, output, report.inlined_prefabs, report.stripped_components
+        );
+        Ok(())
+    }
+
+    fn validate(&mut self, args: &[String]) -> Result<(), String> {
+        let (paths, asset_folder) = parse_asset_folder(args)?;
+        if paths.is_empty() {
+            return Err(USAGE.to_string());
+        }
+
+        let mut issue_count = 0;
+        for path in paths.iter() {
+            let issues = self.validate_file(&PathBuf::from(path), &asset_folder);
+            if issues.is_empty() {
+                println!("{}: ok", path);
+            } else {
+                println!("{}: {} issues", path, issues.len());
+                for issue in issues.iter() {
+                    println!("    {}", issue);
+                }
+            }
+            issue_count += issues.len();
+        }
+
+        if issue_count == 0 {
+            Ok(())
+        } else {
+            Err(format!("Found {} issues", issue_count))
+        }
+    }
+
+    fn convert(&mut self, args: &[String]) -> Result<(), String> {
+        let [input, output] = args else {
+            return Err(USAGE.to_string());
+        };
+        let scene = self
+            .read_scene(&PathBuf::from(input))
+            .map_err(|err| format!("{}: {}", input, err))?;
+        self.write_scene(&PathBuf::from(output), &scene)?;
+        println!("Converted {} to {}", input, output);
+        Ok(())
+    }
+
+    fn stats(&mut self, args: &[String]) -> Result<(), String> {
+        if args.is_empty() {
+            return Err(USAGE.to_string());
+        }
+
+        for path in args.iter() {
+            let scene = self
+                .read_scene(&PathBuf::from(path))
+                .map_err(|err| format!("{}: {}", path, err))?;
+            let stats = SceneStats::from_scene(&scene);
+
+            println!("{}", path);
+            println!("    entities: {}", stats.entities);
+            println!("    resources: {}", stats.resources);
+            println!("    components: {}", stats.total_components());
+
+            let mut components = stats.components.into_iter().collect::<Vec<_>>();
+            components.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            for (type_path, count) in components {
+                println!("    {:>6}  {}", count, type_path);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, SceneIssue> {
+    std::fs::read_to_string(path)
+        .map_err(|err| SceneIssue::Parse(format!("{}: {}", path.display(), err)))
+}
+
+/// Split `--assets <folder>` option from positional arguments
+fn parse_asset_folder(args: &[String]) -> Result<(Vec<String>, PathBuf), String> {
+    let mut positional = vec![];
+    let mut asset_folder = PathBuf::from("assets");
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--assets" {
+            let Some(folder) = iter.next() else {
+                return Err("--assets requires folder path".to_string());
+            };
+            asset_folder = PathBuf::from(folder);
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+    Ok((positional, asset_folder))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use space_prefab::{component::GltfPrefab, editor_registry::EditorRegistryExt};
+
+    /// Registered in editor, but not in runtime app and not marked as editor only
+    #[derive(Component, Reflect, Default, Clone)]
+    #[reflect(Component, Default)]
+    struct EditorTag;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("space_cli_{}_{}", std::process::id(), name))
+    }
+
+    /// Save all entities of cli world to scene file
+    fn write_world_scene(cli: &SpaceCli, path: &Path) {
+        let scene = DynamicSceneBuilder::from_world(&cli.app.world)
+            .extract_entities(cli.app.world.iter_entities().map(|e| e.id()))
+            .build();
+        cli.write_scene(path, &scene).unwrap();
+    }
+
+    fn args(args: &[&Path]) -> Vec<String> {
+        args.iter().map(|arg| arg.display().to_string()).collect()
+    }
+
+    #[test]
+    fn cook_reports_editor_types_missing_in_runtime_app() {
+        let mut cli = SpaceCli::default();
+        cli.app.editor_registry::<EditorTag>();
+        let input = temp_path("cook_input.scn.ron");
+        let output = temp_path("cook_output.scn.ron");
+        cli.app.world.spawn((Name::new("tagged"), EditorTag));
+        write_world_scene(&cli, &input);
+
+        let res = cli.run(&[
+            "cook".to_string(),
+            input.display().to_string(),
+            output.display().to_string(),
+        ]);
+
+        let _ = std::fs::remove_file(&input);
+        assert!(res.is_err_and(|err| err.contains("EditorTag")));
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn convert_round_trip_keeps_scene() {
+        let mut cli = SpaceCli::default();
+        let ron = temp_path("round_trip.scn.ron");
+        let json = temp_path("round_trip.scn.json");
+        let back = temp_path("round_trip_back.scn.ron");
+        cli.app
+            .world
+            .spawn((Name::new("lamp"), Transform::from_xyz(1., 2., 3.)));
+        cli.app.world.spawn(Name::new("empty"));
+        write_world_scene(&cli, &ron);
+
+        let mut convert = vec!["convert".to_string()];
+        convert.extend(args(&[&ron, &json]));
+        cli.run(&convert).unwrap();
+        let mut convert_back = vec!["convert".to_string()];
+        convert_back.extend(args(&[&json, &back]));
+        cli.run(&convert_back).unwrap();
+
+        let original = std::fs::read_to_string(&ron).unwrap();
+        let converted = std::fs::read_to_string(&back).unwrap();
+        let stats = SceneStats::from_scene(&cli.read_scene(&json).unwrap());
+        let mut stats_args = vec!["stats".to_string()];
+        stats_args.extend(args(&[&ron, &json, &back]));
+        let stats_res = cli.run(&stats_args);
+        let issues = cli.validate_file(&json, Path::new("assets"));
+        for path in [ron, json, back] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert_eq!(original, converted);
+        assert_eq!(stats.entities, 2);
+        assert_eq!(stats.total_components(), 3);
+        assert!(stats_res.is_ok());
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn validate_reports_unknown_types_and_missing_assets() {
+        let mut cli = SpaceCli::default();
+        let unknown = temp_path("unknown.scn.ron");
+        let missing = temp_path("missing.scn.ron");
+        std::fs::write(
+            &unknown,
+            r#"(resources: {}, entities: {0: (components: {"game::Unknown": ()})})"#,
+        )
+        .unwrap();
+        cli.app.world.spawn(GltfPrefab {
+            path: "models/missing.glb".to_string(),
+            ..default()
+        });
+        write_world_scene(&cli, &missing);
+
+        let unknown_issues = cli.validate_file(&unknown, Path::new("assets"));
+        let missing_issues = cli.validate_file(&missing, Path::new("assets"));
+        let mut validate = vec!["validate".to_string()];
+        validate.extend(args(&[&unknown, &missing]));
+        let res = cli.run(&validate);
+        let _ = std::fs::remove_file(unknown);
+        let _ = std::fs::remove_file(missing);
+
+        assert_eq!(
+            unknown_issues,
+            vec![SceneIssue::UnknownType("game::Unknown".to_string())]
+        );
+        assert!(matches!(
+            missing_issues.as_slice(),
+            [SceneIssue::MissingAsset { path, .. }] if path == "models/missing.glb"
+        ));
+        assert_eq!(res, Err("Found 2 issues".to_string()));
+    }
+}
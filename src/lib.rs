@@ -9,6 +9,9 @@ pub mod prelude {
     pub use space_bevy_xpbd_plugin::prelude::*;
 }
 
+/// Headless command line tools for prefab files
+pub mod cli;
+
 pub use space_editor_ui;
 pub use space_prefab;
 